@@ -0,0 +1,5 @@
+pub use synth::{Envelope, Synth, Waveform, WaveformParseError};
+pub use wav::{to_wav, SampleFormat};
+
+pub mod synth;
+pub mod wav;
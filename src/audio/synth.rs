@@ -0,0 +1,325 @@
+use std::f64::consts::TAU;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::midi::{Part, Settings};
+use crate::tuning::Tuning;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid waveform {0}")]
+pub struct WaveformParseError(String);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Saw,
+    Square,
+}
+
+impl Waveform {
+    /// The value of the wave at `phase`, from 0 at the start of a cycle to 1 at its end.
+    pub fn sample(&self, phase: f64) -> f64 {
+        match self {
+            Waveform::Sine => (TAU * phase).sin(),
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Square if phase < 0.5 => 1.0,
+            Waveform::Square => -1.0,
+        }
+    }
+}
+
+impl FromStr for Waveform {
+    type Err = WaveformParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sine" => Ok(Waveform::Sine),
+            "saw" | "sawtooth" => Ok(Waveform::Saw),
+            "square" => Ok(Waveform::Square),
+            _ => Err(WaveformParseError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Waveform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Waveform::Sine => "sine",
+            Waveform::Saw => "saw",
+            Waveform::Square => "square",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// An ADSR envelope. The times are in seconds and `sustain` is the level held after the decay, from 0 to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    pub attack: f64,
+    pub decay: f64,
+    pub sustain: f64,
+    pub release: f64,
+}
+
+impl Default for Envelope {
+    fn default() -> Envelope {
+        Envelope {
+            attack: 0.01,
+            decay: 0.1,
+            sustain: 0.7,
+            release: 0.2,
+        }
+    }
+}
+
+impl Envelope {
+    /// The level `time` seconds after a note starts, for a note held for `held` seconds.
+    pub fn level(&self, time: f64, held: f64) -> f64 {
+        if time >= held {
+            return match self.release {
+                release if release > 0.0 => self.level(held, f64::INFINITY) * (1.0 - (time - held) / release).max(0.0),
+                _ => 0.0,
+            };
+        }
+        if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+        } else {
+            self.sustain
+        }
+    }
+}
+
+/// A simple polyphonic synthesizer playing every note with the same waveform and envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Synth {
+    pub waveform: Waveform,
+    pub envelope: Envelope,
+    pub tuning: Tuning,
+    pub sample_rate: u32,
+    /// The peak level of a single note at full velocity.
+    pub gain: f64,
+}
+
+impl Default for Synth {
+    fn default() -> Synth {
+        Synth {
+            waveform: Waveform::Sine,
+            envelope: Envelope::default(),
+            tuning: Tuning::default(),
+            sample_rate: 44100,
+            gain: 0.25,
+        }
+    }
+}
+
+impl Synth {
+    /// Play the parts at the tempo and velocity of `settings`, returning mono samples. Every note rings out for the
+    /// release time after it ends, and the mix is scaled down if it would clip.
+    pub fn render(&self, parts: &[Part], settings: &Settings) -> Vec<f32> {
+        let rate = self.sample_rate as f64;
+        let seconds_per_tick = 60.0 / (settings.tempo as f64 * settings.ticks_per_quarter as f64);
+        let notes: Vec<_> = parts.iter().flat_map(|part| &part.notes).collect();
+        let length = notes
+            .iter()
+            .map(|note| ((note.start + note.duration) as f64 * seconds_per_tick + self.envelope.release) * rate)
+            .fold(0.0, f64::max)
+            .ceil() as usize;
+
+        let mut mix = vec![0.0; length];
+        let velocity = settings.velocity as f64 / 127.0;
+        for note in notes {
+            let frequency = self.tuning.frequency(note.pitch);
+            let start = note.start as f64 * seconds_per_tick;
+            let held = note.duration as f64 * seconds_per_tick;
+            let first = (start * rate).ceil() as usize;
+            let last = (((start + held + self.envelope.release) * rate).ceil() as usize).min(length);
+            for (i, sample) in mix.iter_mut().enumerate().take(last).skip(first) {
+                // Counting in samples keeps the phase exact when the note starts on a sample.
+                let offset = i as f64 - start * rate;
+                let level = self.envelope.level(offset / rate, held);
+                *sample += self.gain * velocity * level * self.waveform.sample((frequency * offset / rate).fract());
+            }
+        }
+
+        let peak = mix.iter().fold(1.0, |peak: f64, s| peak.max(s.abs()));
+        mix.iter().map(|s| (s / peak) as f32).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::midi::TimedNote;
+    use crate::note::Note::*;
+    use crate::pitch::Pitch;
+    use crate::tuning::Temperament;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Waveform::Sine, [0.0, 1.0, 0.0, -1.0])]
+    #[case(Waveform::Saw, [-1.0, -0.5, 0.0, 0.5])]
+    #[case(Waveform::Square, [1.0, 1.0, -1.0, -1.0])]
+    fn waveforms(#[case] waveform: Waveform, #[case] expected: [f64; 4]) {
+        for (i, expected) in expected.iter().enumerate() {
+            assert!((waveform.sample(i as f64 / 4.0) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[rstest]
+    #[case(0.0, 0.0)]
+    #[case(0.05, 0.5)]
+    #[case(0.1, 1.0)]
+    #[case(0.2, 0.75)]
+    #[case(0.5, 0.5)]
+    #[case(1.0, 0.5)]
+    #[case(1.5, 0.25)]
+    #[case(2.0, 0.0)]
+    #[case(3.0, 0.0)]
+    fn envelope(#[case] time: f64, #[case] expected: f64) {
+        let envelope = Envelope {
+            attack: 0.1,
+            decay: 0.2,
+            sustain: 0.5,
+            release: 1.0,
+        };
+        assert!((envelope.level(time, 1.0) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn release_from_attack() {
+        let envelope = Envelope {
+            attack: 1.0,
+            decay: 0.0,
+            sustain: 1.0,
+            release: 1.0,
+        };
+        assert!((envelope.level(0.75, 0.5) - 0.375).abs() < 1e-9);
+    }
+
+    fn organ(waveform: Waveform, tuning: Tuning) -> Synth {
+        Synth {
+            waveform,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.0,
+                sustain: 1.0,
+                release: 0.0,
+            },
+            tuning,
+            sample_rate: 1760,
+            gain: 0.5,
+        }
+    }
+
+    /// One beat at 60 BPM, so a note lasts a second.
+    fn settings() -> Settings {
+        Settings {
+            tempo: 60,
+            velocity: 127,
+            ..Settings::default()
+        }
+    }
+
+    fn part(pitches: &[Pitch], settings: &Settings) -> Part {
+        Part {
+            name: "Test".to_string(),
+            notes: pitches
+                .iter()
+                .map(|&pitch| TimedNote {
+                    pitch,
+                    start: 0,
+                    duration: settings.ticks_per_beat(),
+                })
+                .collect(),
+        }
+    }
+
+    fn assert_samples(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!((a - e).abs() < 1e-4, "sample {}: {} != {}", i, a, e);
+        }
+    }
+
+    #[rstest]
+    #[case(Waveform::Sine, [0.0, 0.5, 0.0, -0.5])]
+    #[case(Waveform::Saw, [-0.5, -0.25, 0.0, 0.25])]
+    #[case(Waveform::Square, [0.5, 0.5, -0.5, -0.5])]
+    fn a440(#[case] waveform: Waveform, #[case] cycle: [f32; 4]) {
+        let settings = settings();
+        let samples = organ(waveform, Tuning::default()).render(&[part(&[Pitch::new(A, 4)], &settings)], &settings);
+        assert_samples(&samples, &cycle.repeat(440));
+    }
+
+    #[test]
+    fn reference_pitch() {
+        let settings = settings();
+        let tuning = Tuning::new(Temperament::Equal, C, 220.0);
+        let samples = organ(Waveform::Square, tuning).render(&[part(&[Pitch::new(A, 4)], &settings)], &settings);
+        assert_samples(&samples, &[0.5, 0.5, 0.5, 0.5, -0.5, -0.5, -0.5, -0.5].repeat(220));
+    }
+
+    #[test]
+    fn just_fifth() {
+        // A4 and E5 tuned to 440 and 660 Hz repeat together every 1/220 of a second.
+        let settings = settings();
+        let tuning = Tuning::new(Temperament::Just, A, 440.0);
+        let synth = Synth {
+            sample_rate: 1320,
+            ..organ(Waveform::Square, tuning)
+        };
+        let samples = synth.render(&[part(&[Pitch::new(A, 4), Pitch::new(E, 5)], &settings)], &settings);
+        assert_samples(&samples, &[1.0, 0.0, 0.0, 0.0, 1.0, -1.0].repeat(220));
+    }
+
+    #[test]
+    fn normalized_when_clipping() {
+        let settings = settings();
+        let synth = organ(Waveform::Square, Tuning::default());
+        let samples = synth.render(&[part(&[Pitch::new(A, 4); 4], &settings)], &settings);
+        assert_samples(&samples, &[1.0, 1.0, -1.0, -1.0].repeat(440));
+    }
+
+    #[test]
+    fn envelope_and_release() {
+        let settings = Settings {
+            tempo: 120,
+            ..settings()
+        };
+        let synth = Synth {
+            envelope: Envelope {
+                attack: 0.25,
+                decay: 0.0,
+                sustain: 1.0,
+                release: 0.5,
+            },
+            sample_rate: 8,
+            gain: 1.0,
+            ..organ(Waveform::Square, Tuning::new(Temperament::Equal, C, 1.0))
+        };
+        // The half second note at 1 Hz plays the positive half of the cycle, and the release the negative half.
+        let samples = synth.render(&[part(&[Pitch::new(A, 4)], &settings)], &settings);
+        assert_samples(&samples, &[0.0, 0.5, 1.0, 1.0, -1.0, -0.75, -0.5, -0.25]);
+    }
+
+    #[test]
+    fn velocity_and_start() {
+        let settings = Settings {
+            velocity: 64,
+            ..settings()
+        };
+        let mut part = part(&[Pitch::new(A, 4)], &settings);
+        part.notes[0].start = settings.ticks_per_beat() / 2;
+        part.notes[0].duration /= 2;
+        let synth = Synth {
+            sample_rate: 4,
+            ..organ(Waveform::Square, Tuning::new(Temperament::Equal, C, 1.0))
+        };
+        let level = 0.5 * 64.0 / 127.0;
+        assert_samples(&synth.render(&[part], &settings), &[0.0, 0.0, level, level]);
+    }
+}
@@ -0,0 +1,111 @@
+/// How samples are stored in a WAV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// 16-bit signed integers, readable by anything that plays WAV files.
+    Pcm16,
+    /// 32-bit IEEE floats, which keep the full resolution of the synthesizer.
+    Float32,
+}
+
+impl SampleFormat {
+    fn tag(&self) -> u16 {
+        match self {
+            SampleFormat::Pcm16 => 1,
+            SampleFormat::Float32 => 3,
+        }
+    }
+
+    fn bytes_per_sample(&self) -> u32 {
+        match self {
+            SampleFormat::Pcm16 => 2,
+            SampleFormat::Float32 => 4,
+        }
+    }
+}
+
+/// Write mono samples between -1 and 1 to a WAV file. Float files get the extended format chunk and the `fact` chunk
+/// that the format requires for anything but integer PCM.
+pub fn to_wav(samples: &[f32], sample_rate: u32, format: SampleFormat) -> Vec<u8> {
+    let block_align = format.bytes_per_sample();
+    let mut fmt = vec![];
+    fmt.extend_from_slice(&format.tag().to_le_bytes());
+    fmt.extend_from_slice(&1u16.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+    fmt.extend_from_slice(&(block_align as u16).to_le_bytes());
+    fmt.extend_from_slice(&(block_align as u16 * 8).to_le_bytes());
+    if format == SampleFormat::Float32 {
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let mut data = Vec::with_capacity(samples.len() * block_align as usize);
+    for sample in samples {
+        match format {
+            SampleFormat::Pcm16 => {
+                let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            SampleFormat::Float32 => data.extend_from_slice(&sample.to_le_bytes()),
+        }
+    }
+
+    let mut body = b"WAVE".to_vec();
+    write_chunk(&mut body, b"fmt ", &fmt);
+    if format == SampleFormat::Float32 {
+        write_chunk(&mut body, b"fact", &(samples.len() as u32).to_le_bytes());
+    }
+    write_chunk(&mut body, b"data", &data);
+    let mut bytes = vec![];
+    write_chunk(&mut bytes, b"RIFF", &body);
+    bytes
+}
+
+/// Append a chunk with its id, length and contents, padded to an even length.
+fn write_chunk(bytes: &mut Vec<u8>, id: &[u8; 4], contents: &[u8]) {
+    bytes.extend_from_slice(id);
+    bytes.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(contents);
+    if contents.len() % 2 == 1 {
+        bytes.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pcm16() {
+        let bytes = to_wav(&[0.0, 1.0, -1.0, 0.5, 2.0], 8000, SampleFormat::Pcm16);
+        let mut expected = b"RIFF".to_vec();
+        expected.extend_from_slice(&46u32.to_le_bytes());
+        expected.extend_from_slice(b"WAVEfmt ");
+        expected.extend_from_slice(&[16, 0, 0, 0, 1, 0, 1, 0]);
+        expected.extend_from_slice(&8000u32.to_le_bytes());
+        expected.extend_from_slice(&16000u32.to_le_bytes());
+        expected.extend_from_slice(&[2, 0, 16, 0]);
+        expected.extend_from_slice(b"data");
+        expected.extend_from_slice(&[10, 0, 0, 0]);
+        expected.extend_from_slice(&[0x00, 0x00, 0xff, 0x7f, 0x01, 0x80, 0x00, 0x40, 0xff, 0x7f]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn float32() {
+        let bytes = to_wav(&[0.25, -0.5], 44100, SampleFormat::Float32);
+        let mut expected = b"RIFF".to_vec();
+        expected.extend_from_slice(&58u32.to_le_bytes());
+        expected.extend_from_slice(b"WAVEfmt ");
+        expected.extend_from_slice(&[18, 0, 0, 0, 3, 0, 1, 0]);
+        expected.extend_from_slice(&44100u32.to_le_bytes());
+        expected.extend_from_slice(&176400u32.to_le_bytes());
+        expected.extend_from_slice(&[4, 0, 32, 0, 0, 0]);
+        expected.extend_from_slice(b"fact");
+        expected.extend_from_slice(&[4, 0, 0, 0, 2, 0, 0, 0]);
+        expected.extend_from_slice(b"data");
+        expected.extend_from_slice(&[8, 0, 0, 0]);
+        expected.extend_from_slice(&0.25f32.to_le_bytes());
+        expected.extend_from_slice(&(-0.5f32).to_le_bytes());
+        assert_eq!(bytes, expected);
+    }
+}
@@ -15,6 +15,14 @@ impl Chord {
     pub fn apply_to(&self, root: Note) -> Vec<Note> {
         self.intervals.iter().map(|i| root << *i).collect()
     }
+
+    /// The distinct semitone distances of the chord tones from the root, folded into one octave and sorted.
+    pub fn semitones(&self) -> Vec<usize> {
+        let mut semitones: Vec<usize> = self.intervals.iter().map(|i| i.semitones() % 12).collect();
+        semitones.sort();
+        semitones.dedup();
+        semitones
+    }
 }
 
 impl Add<Interval> for Chord {
@@ -55,4 +63,18 @@ mod tests {
         let maj7 = triad + Interval::MajorSeventh;
         assert_eq!(maj7.apply_to(C), vec![C, E, G, B]);
     }
+
+    #[test]
+    fn chord_semitones_are_folded_and_sorted() {
+        let chord = Chord {
+            intervals: vec![
+                Interval::PerfectUnison,
+                Interval::MajorThird,
+                Interval::MinorSeventh,
+                Interval::MajorSecond,
+                Interval::PerfectOctave,
+            ],
+        };
+        assert_eq!(chord.semitones(), vec![0, 2, 4, 10]);
+    }
 }
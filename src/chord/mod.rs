@@ -1,10 +1,16 @@
 pub use chord::Chord;
 pub use generator::{augmented, diminished, major, minor, sus2, sus4};
+pub use polychord::{Polychord, UpperStructure};
+pub use stacked::{so_what, Stacking};
+pub use symbol::ChordSymbol;
 pub use tetrad::Tetrad;
 pub use triad::Triad;
 
 #[allow(clippy::module_inception)]
 pub mod chord;
 mod generator;
+pub mod polychord;
+pub mod stacked;
+pub mod symbol;
 pub mod tetrad;
 pub mod triad;
@@ -0,0 +1,265 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::symbol::{chord_from_semitones, parse_root, suffix};
+use crate::chord::{Chord, ChordSymbol, Tetrad, Triad};
+use crate::note::Note;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid polychord {0}")]
+pub struct PolychordParseError(String);
+
+/// Two chords sounding at once, one stacked over the other, like `D/C7` or `Ab|E`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polychord {
+    pub upper: ChordSymbol,
+    pub lower: ChordSymbol,
+}
+
+impl Polychord {
+    pub fn new(upper: ChordSymbol, lower: ChordSymbol) -> Polychord {
+        Polychord { upper, lower }
+    }
+
+    /// The notes of the lower chord followed by the notes of the upper one that aren't already in it.
+    pub fn notes(&self) -> Vec<Note> {
+        let mut notes = self.lower.notes();
+        for note in self.upper.notes() {
+            if !notes.contains(&note) {
+                notes.push(note);
+            }
+        }
+        notes
+    }
+
+    /// The polychord as a single chord on the lower root, like `C13#11` for `D/C7`.
+    pub fn to_chord_symbol(&self) -> ChordSymbol {
+        let semitones: Vec<usize> = self
+            .notes()
+            .iter()
+            .map(|n| (*n - self.lower.root).semitones())
+            .collect();
+        ChordSymbol {
+            root: self.lower.root,
+            chord: chord_from_semitones(&semitones),
+            bass: self.lower.bass,
+        }
+    }
+
+    pub fn transpose(&self, semitones: isize) -> Polychord {
+        Polychord::new(self.upper.transpose(semitones), self.lower.transpose(semitones))
+    }
+}
+
+impl fmt::Display for Polychord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A plain major triad below a slash would read as a bass note, so it takes the `|` form.
+        let separator = if suffix(&self.lower.chord).is_empty() { "|" } else { "/" };
+        write!(f, "{}{}{}", self.upper, separator, self.lower)
+    }
+}
+
+impl FromStr for Polychord {
+    type Err = PolychordParseError;
+
+    /// Parse `upper|lower` or `upper/lower`. With a slash the lower part must be more than a note, since `D/C` is a
+    /// slash chord rather than a polychord.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || PolychordParseError(s.to_string());
+        let (upper, lower) = match s.split_once('|') {
+            Some(parts) => parts,
+            None => {
+                let (upper, lower) = s.split_once('/').ok_or_else(error)?;
+                if parse_root(lower).is_some_and(|(_, rest)| rest.is_empty()) {
+                    return Err(error());
+                }
+                (upper, lower)
+            }
+        };
+        let upper: ChordSymbol = upper.trim().parse().map_err(|_| error())?;
+        let lower: ChordSymbol = lower.trim().parse().map_err(|_| error())?;
+        if upper.bass.is_some() {
+            return Err(error());
+        }
+        Ok(Polychord { upper, lower })
+    }
+}
+
+/// The major triads commonly stacked over a dominant seventh to voice its tensions, named by their root's distance from
+/// the dominant root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpperStructure {
+    II,
+    FlatIII,
+    SharpIV,
+    FlatVI,
+    VI,
+    FlatVII,
+}
+
+impl UpperStructure {
+    pub fn array() -> [UpperStructure; 6] {
+        [
+            UpperStructure::II,
+            UpperStructure::FlatIII,
+            UpperStructure::SharpIV,
+            UpperStructure::FlatVI,
+            UpperStructure::VI,
+            UpperStructure::FlatVII,
+        ]
+    }
+
+    /// The distance of the triad's root above the dominant root.
+    pub fn semitones(&self) -> usize {
+        match self {
+            UpperStructure::II => 2,
+            UpperStructure::FlatIII => 3,
+            UpperStructure::SharpIV => 6,
+            UpperStructure::FlatVI => 8,
+            UpperStructure::VI => 9,
+            UpperStructure::FlatVII => 10,
+        }
+    }
+
+    /// The upper structure over the dominant seventh on `root`, like `D/C7` for `US II` over C.
+    pub fn over(&self, root: Note) -> Polychord {
+        let lower = ChordSymbol::new(
+            root,
+            Chord {
+                intervals: Tetrad::Dominant.intervals().to_vec(),
+            },
+        );
+        let upper = ChordSymbol::new(
+            root.transpose(self.semitones() as isize),
+            Chord {
+                intervals: Triad::Major.intervals().to_vec(),
+            },
+        );
+        Polychord::new(upper, lower)
+    }
+
+    /// What each note of the triad is over the dominant, like `["9", "#11", "13"]` for `US II`.
+    pub fn degrees(&self) -> [&'static str; 3] {
+        Triad::Major
+            .intervals()
+            .map(|i| degree_name((self.semitones() + i.semitones()) % 12))
+    }
+
+    /// The tensions the triad adds to the dominant seventh, i.e. its notes other than the root, third, fifth and seventh.
+    pub fn tensions(&self) -> Vec<&'static str> {
+        self.degrees()
+            .into_iter()
+            .filter(|d| !matches!(*d, "R" | "3" | "5" | "b7"))
+            .collect()
+    }
+}
+
+impl fmt::Display for UpperStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UpperStructure::II => "II",
+            UpperStructure::FlatIII => "bIII",
+            UpperStructure::SharpIV => "#IV",
+            UpperStructure::FlatVI => "bVI",
+            UpperStructure::VI => "VI",
+            UpperStructure::FlatVII => "bVII",
+        };
+        write!(f, "US {}", name)
+    }
+}
+
+/// The upper structures whose notes are all part of a dominant chord, like `US II` for `C13#11`. Empty for chords
+/// without a major third and minor seventh.
+pub fn upper_structures(symbol: &ChordSymbol) -> Vec<UpperStructure> {
+    let semitones = symbol.chord.semitones();
+    if !(semitones.contains(&4) && semitones.contains(&10)) {
+        return vec![];
+    }
+    UpperStructure::array()
+        .into_iter()
+        .filter(|us| {
+            Triad::Major
+                .intervals()
+                .iter()
+                .all(|i| semitones.contains(&((us.semitones() + i.semitones()) % 12)))
+        })
+        .collect()
+}
+
+fn degree_name(semitones: usize) -> &'static str {
+    match semitones {
+        0 => "R",
+        1 => "b9",
+        2 => "9",
+        3 => "#9",
+        4 => "3",
+        5 => "11",
+        6 => "#11",
+        7 => "5",
+        8 => "b13",
+        9 => "13",
+        10 => "b7",
+        _ => "7",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("D/C7", D, C, "D/C7")]
+    #[case("Ab|E", Ab, E, "Ab|E")]
+    #[case("Ab | E", Ab, E, "Ab|E")]
+    #[case("F#m/Cmaj7", Gb, C, "Gbm/Cmaj7")]
+    #[case("D|C7", D, C, "D/C7")]
+    fn parse_polychords(#[case] s: &str, #[case] upper: Note, #[case] lower: Note, #[case] display: &str) {
+        let polychord: Polychord = s.parse().unwrap();
+        assert_eq!(polychord.upper.root, upper);
+        assert_eq!(polychord.lower.root, lower);
+        assert_eq!(polychord.to_string(), display);
+    }
+
+    #[rstest]
+    #[case("D/C")]
+    #[case("D")]
+    #[case("X|C")]
+    #[case("D/E|C")]
+    fn invalid_polychords(#[case] s: &str) {
+        assert!(s.parse::<Polychord>().is_err());
+    }
+
+    #[rstest]
+    #[case("D/C7", vec![C, E, G, Bb, D, Gb, A], "C13#11")]
+    #[case("Ab|E", vec![E, Ab, B, C, Eb], "Emaj7b13")]
+    #[case("Eb/C7", vec![C, E, G, Bb, Eb], "C7#9")]
+    fn combined(#[case] s: &str, #[case] notes: Vec<Note>, #[case] symbol: &str) {
+        let polychord: Polychord = s.parse().unwrap();
+        assert_eq!(polychord.notes(), notes);
+        assert_eq!(polychord.to_chord_symbol().to_string(), symbol);
+    }
+
+    #[rstest]
+    #[case(UpperStructure::II, "D/C7", vec!["9", "#11", "13"])]
+    #[case(UpperStructure::FlatIII, "Eb/C7", vec!["#9"])]
+    #[case(UpperStructure::SharpIV, "Gb/C7", vec!["#11", "b9"])]
+    #[case(UpperStructure::FlatVI, "Ab/C7", vec!["b13", "#9"])]
+    #[case(UpperStructure::VI, "A/C7", vec!["13", "b9"])]
+    #[case(UpperStructure::FlatVII, "Bb/C7", vec!["9", "11"])]
+    fn catalogue(#[case] us: UpperStructure, #[case] polychord: &str, #[case] tensions: Vec<&str>) {
+        assert_eq!(us.over(C).to_string(), polychord);
+        assert_eq!(us.tensions(), tensions);
+    }
+
+    #[rstest]
+    #[case("C13#11", vec![UpperStructure::II])]
+    #[case("C7alt", vec![UpperStructure::SharpIV, UpperStructure::FlatVI])]
+    #[case("Cmaj7", vec![])]
+    fn structures_in_a_chord(#[case] chord: &str, #[case] expected: Vec<UpperStructure>) {
+        assert_eq!(upper_structures(&chord.parse().unwrap()), expected);
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::chord::symbol::{chord_from_semitones, exact_quality};
+use crate::chord::{Chord, ChordSymbol};
+use crate::interval::{canonical_interval, Interval};
+use crate::note::Note;
+
+/// Chords built by stacking an interval other than a third.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stacking {
+    /// Stacked perfect fourths.
+    Quartal,
+    /// Stacked fifths.
+    Quintal,
+    /// Stacked seconds, i.e. clusters.
+    Secondal,
+}
+
+impl Stacking {
+    /// The number of notes a stacked chord can have.
+    pub const SIZES: RangeInclusive<usize> = 3..=6;
+
+    pub fn array() -> [Stacking; 3] {
+        [Stacking::Quartal, Stacking::Quintal, Stacking::Secondal]
+    }
+
+    /// The interval between neighbouring notes of a chromatic stack.
+    pub fn interval(&self) -> Interval {
+        match self {
+            Stacking::Quartal => Interval::PerfectFourth,
+            Stacking::Quintal => Interval::PerfectFifth,
+            Stacking::Secondal => Interval::MajorSecond,
+        }
+    }
+
+    /// The number of scale steps between neighbouring notes of a diatonic stack.
+    pub fn steps(&self) -> usize {
+        match self {
+            Stacking::Quartal => 3,
+            Stacking::Quintal => 4,
+            Stacking::Secondal => 1,
+        }
+    }
+
+    /// A stack of `size` perfect fourths, perfect fifths or major seconds, with the intervals in stacking order and
+    /// folded into one octave. Panics unless `size` is in [`Stacking::SIZES`].
+    pub fn chord(&self, size: usize) -> Chord {
+        assert!(
+            Stacking::SIZES.contains(&size),
+            "a stacked chord has 3 to 6 notes, not {}",
+            size
+        );
+        Chord {
+            intervals: (0..size)
+                .map(|n| canonical_interval(n * self.interval().semitones()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Stacking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stacking::Quartal => "quartal",
+            Stacking::Quintal => "quintal",
+            Stacking::Secondal => "cluster",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The So What voicing, three perfect fourths topped by a major third, like `E A D G B`, with the intervals in stacking
+/// order and folded into one octave.
+pub fn so_what() -> Chord {
+    let mut chord = Stacking::Quartal.chord(4);
+    chord.intervals.push(canonical_interval(3 * 5 + 4));
+    chord
+}
+
+/// Name a stacked chord. When its notes form a known tertian chord on any of them that name is used, over the root of
+/// the stack as a slash bass when needed, so that quintal `C G D` is `Csus2` and quartal `D G C` is `Gsus4/D`. Otherwise
+/// the chord is named after its stacking and size, like `C cluster(3)`.
+pub fn name(root: Note, chord: &Chord, stacking: Stacking) -> String {
+    let notes = chord.apply_to(root);
+    let tertian = notes.iter().find_map(|candidate| {
+        let mut semitones: Vec<usize> = notes.iter().map(|n| (*n - *candidate).semitones()).collect();
+        semitones.sort();
+        semitones.dedup();
+        exact_quality(&semitones)?;
+        Some(ChordSymbol {
+            root: *candidate,
+            chord: chord_from_semitones(&semitones),
+            bass: (*candidate != root).then_some(root),
+        })
+    });
+    match tertian {
+        Some(symbol) => symbol.to_string(),
+        None => format!("{} {}({})", root, stacking, chord.semitones().len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Stacking::Quartal, 3, vec![D, G, C])]
+    #[case(Stacking::Quartal, 6, vec![D, G, C, F, Bb, Eb])]
+    #[case(Stacking::Quintal, 4, vec![D, A, E, B])]
+    #[case(Stacking::Secondal, 3, vec![D, E, Gb])]
+    fn chromatic_stacks(#[case] stacking: Stacking, #[case] size: usize, #[case] expected: Vec<Note>) {
+        assert_eq!(stacking.chord(size).apply_to(D), expected);
+    }
+
+    #[rstest]
+    #[case(2)]
+    #[case(7)]
+    #[should_panic(expected = "a stacked chord has 3 to 6 notes")]
+    fn sizes_out_of_range(#[case] size: usize) {
+        Stacking::Quartal.chord(size);
+    }
+
+    #[test]
+    fn so_what_voicing() {
+        let chord = so_what();
+        assert_eq!(chord.apply_to(E), vec![E, A, D, G, B]);
+        let steps: Vec<Interval> = chord
+            .intervals
+            .windows(2)
+            .map(|pair| canonical_interval(pair[1].semitones() + 12 - pair[0].semitones()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                Interval::PerfectFourth,
+                Interval::PerfectFourth,
+                Interval::PerfectFourth,
+                Interval::MajorThird
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(Stacking::Quintal, 3, "Csus2")]
+    #[case(Stacking::Quintal, 5, "C6/9")]
+    #[case(Stacking::Quartal, 3, "Fsus4/C")]
+    #[case(Stacking::Quartal, 4, "F7sus4/C")]
+    #[case(Stacking::Quartal, 6, "Bbm11/C")]
+    #[case(Stacking::Quintal, 4, "D7sus4/C")]
+    #[case(Stacking::Secondal, 3, "C cluster(3)")]
+    fn names_prefer_tertian(#[case] stacking: Stacking, #[case] size: usize, #[case] expected: &str) {
+        assert_eq!(name(C, &stacking.chord(size), stacking), expected);
+    }
+}
@@ -0,0 +1,545 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::Chord;
+use crate::interval::Interval;
+use crate::note::Note;
+
+/// Chord qualities by their canonical suffix and the semitones of their tones above the root.
+const QUALITIES: &[(&str, &[usize])] = &[
+    ("", &[0, 4, 7]),
+    ("m", &[0, 3, 7]),
+    ("dim", &[0, 3, 6]),
+    ("aug", &[0, 4, 8]),
+    ("sus4", &[0, 5, 7]),
+    ("sus2", &[0, 2, 7]),
+    ("5", &[0, 7]),
+    ("7", &[0, 4, 7, 10]),
+    ("maj7", &[0, 4, 7, 11]),
+    ("m7", &[0, 3, 7, 10]),
+    ("mMaj7", &[0, 3, 7, 11]),
+    ("m7b5", &[0, 3, 6, 10]),
+    ("dim7", &[0, 3, 6, 9]),
+    ("6", &[0, 4, 7, 9]),
+    ("m6", &[0, 3, 7, 9]),
+    ("7sus4", &[0, 5, 7, 10]),
+    ("aug7", &[0, 4, 8, 10]),
+    ("maj7#5", &[0, 4, 8, 11]),
+    ("7b5", &[0, 4, 6, 10]),
+    ("add9", &[0, 2, 4, 7]),
+    ("madd9", &[0, 2, 3, 7]),
+    ("9", &[0, 2, 4, 7, 10]),
+    ("maj9", &[0, 2, 4, 7, 11]),
+    ("m9", &[0, 2, 3, 7, 10]),
+    ("6/9", &[0, 2, 4, 7, 9]),
+    ("7b9", &[0, 1, 4, 7, 10]),
+    ("7#9", &[0, 3, 4, 7, 10]),
+    ("7#11", &[0, 4, 6, 7, 10]),
+    ("maj7#11", &[0, 4, 6, 7, 11]),
+    ("11", &[0, 2, 4, 5, 7, 10]),
+    ("m11", &[0, 2, 3, 5, 7, 10]),
+    ("13", &[0, 2, 4, 7, 9, 10]),
+    ("maj13", &[0, 2, 4, 7, 9, 11]),
+    ("m13", &[0, 2, 3, 7, 9, 10]),
+    ("7alt", &[0, 1, 3, 4, 6, 8, 10]),
+];
+
+/// Other common spellings of the suffixes in `QUALITIES`.
+const ALIASES: &[(&str, &str)] = &[
+    ("M", ""),
+    ("maj", ""),
+    ("min", "m"),
+    ("mi", "m"),
+    ("-", "m"),
+    ("°", "dim"),
+    ("o", "dim"),
+    ("+", "aug"),
+    ("sus", "sus4"),
+    ("dom7", "7"),
+    ("M7", "maj7"),
+    ("Maj7", "maj7"),
+    ("ma7", "maj7"),
+    ("Δ", "maj7"),
+    ("Δ7", "maj7"),
+    ("min7", "m7"),
+    ("mi7", "m7"),
+    ("-7", "m7"),
+    ("mM7", "mMaj7"),
+    ("m(maj7)", "mMaj7"),
+    ("minmaj7", "mMaj7"),
+    ("-maj7", "mMaj7"),
+    ("ø", "m7b5"),
+    ("ø7", "m7b5"),
+    ("m7-5", "m7b5"),
+    ("°7", "dim7"),
+    ("o7", "dim7"),
+    ("7sus", "7sus4"),
+    ("+7", "aug7"),
+    ("7#5", "aug7"),
+    ("7+5", "aug7"),
+    ("add2", "add9"),
+    ("69", "6/9"),
+    ("M9", "maj9"),
+    ("Maj9", "maj9"),
+    ("-9", "m9"),
+    ("min9", "m9"),
+    ("Δ9", "maj9"),
+    ("alt", "7alt"),
+];
+
+enum Alteration {
+    Add(usize),
+    Replace(&'static [usize], usize),
+    Remove(&'static [usize]),
+}
+
+/// Tensions and alterations that may follow the quality, like the `#11` in `Cmaj7#11`.
+const ALTERATIONS: &[(&str, Alteration)] = &[
+    ("add9", Alteration::Add(2)),
+    ("add2", Alteration::Add(2)),
+    ("add11", Alteration::Add(5)),
+    ("add4", Alteration::Add(5)),
+    ("add13", Alteration::Add(9)),
+    ("add6", Alteration::Add(9)),
+    ("no3", Alteration::Remove(&[3, 4])),
+    ("no5", Alteration::Remove(&[7])),
+    ("sus4", Alteration::Replace(&[3, 4], 5)),
+    ("sus2", Alteration::Replace(&[3, 4], 2)),
+    ("sus", Alteration::Replace(&[3, 4], 5)),
+    ("b5", Alteration::Replace(&[7], 6)),
+    ("-5", Alteration::Replace(&[7], 6)),
+    ("#5", Alteration::Replace(&[7], 8)),
+    ("+5", Alteration::Replace(&[7], 8)),
+    ("b9", Alteration::Add(1)),
+    ("-9", Alteration::Add(1)),
+    ("#9", Alteration::Add(3)),
+    ("+9", Alteration::Add(3)),
+    ("9", Alteration::Add(2)),
+    ("#11", Alteration::Add(6)),
+    ("+11", Alteration::Add(6)),
+    ("11", Alteration::Add(5)),
+    ("b13", Alteration::Add(8)),
+    ("b6", Alteration::Add(8)),
+    ("-13", Alteration::Add(8)),
+    ("13", Alteration::Add(9)),
+];
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid chord symbol {0}")]
+pub struct ChordSymbolParseError(String);
+
+/// A chord symbol as written on a lead sheet, like `Dm7`, `G7b9` or `C/E`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChordSymbol {
+    pub root: Note,
+    pub chord: Chord,
+    pub bass: Option<Note>,
+}
+
+impl ChordSymbol {
+    pub fn new(root: Note, chord: Chord) -> ChordSymbol {
+        ChordSymbol {
+            root,
+            chord,
+            bass: None,
+        }
+    }
+
+    pub fn notes(&self) -> Vec<Note> {
+        self.chord.apply_to(self.root)
+    }
+
+    /// The lowest note of the chord: the slash bass if there is one, the root otherwise.
+    pub fn bass_note(&self) -> Note {
+        self.bass.unwrap_or(self.root)
+    }
+
+    pub fn transpose(&self, semitones: isize) -> ChordSymbol {
+        ChordSymbol {
+            root: self.root.transpose(semitones),
+            chord: self.chord.clone(),
+            bass: self.bass.map(|b| b.transpose(semitones)),
+        }
+    }
+}
+
+/// Name a set of notes heard together over `bass`, like `C/E` for E, G and C over E.
+///
+/// A root is chosen whose notes make a chord with a name of its own, trying the bass first so that `A C E G` over `A`
+/// is `Am7` rather than `C6/A`. Failing that, it's a note with a third and a perfect fifth above it, then one with a
+/// third and an altered fifth, then the bass. Single notes and intervals other than fifths aren't chords.
+pub fn identify(notes: &[Note], bass: Note) -> Option<ChordSymbol> {
+    let mut candidates = vec![bass];
+    for note in notes {
+        if !candidates.contains(note) {
+            candidates.push(*note);
+        }
+    }
+    let semitones_above = |root: Note| {
+        let mut semitones: Vec<usize> = candidates.iter().map(|n| (*n - root).semitones()).collect();
+        semitones.sort();
+        semitones
+    };
+    let symbol = |root: Note| ChordSymbol {
+        root,
+        chord: chord_from_semitones(&semitones_above(root)),
+        bass: (root != bass).then_some(bass),
+    };
+
+    if let Some(root) = candidates
+        .iter()
+        .find(|r| exact_quality(&semitones_above(**r)).is_some())
+    {
+        return Some(symbol(*root));
+    }
+    if candidates.len() < 3 {
+        return None;
+    }
+    let with_third_and = |fifths: &'static [usize]| {
+        move |root: &&Note| {
+            let semitones = semitones_above(**root);
+            semitones.iter().any(|s| matches!(s, 3 | 4)) && semitones.iter().any(|s| fifths.contains(s))
+        }
+    };
+    let root = candidates
+        .iter()
+        .find(with_third_and(&[7]))
+        .or_else(|| candidates.iter().find(with_third_and(&[6, 8])))
+        .unwrap_or(&bass);
+    Some(symbol(*root))
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.root, suffix(&self.chord))?;
+        match self.bass {
+            Some(bass) if bass != self.root => write!(f, "/{}", bass),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for ChordSymbol {
+    type Err = ChordSymbolParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ChordSymbolParseError(s.to_string());
+        let (root, rest) = parse_root(s).ok_or_else(error)?;
+        let (quality, bass) = match rest.rfind('/') {
+            Some(i) if parse_root(&rest[i + 1..]).is_some_and(|(_, r)| r.is_empty()) => {
+                (&rest[..i], parse_root(&rest[i + 1..]).map(|(n, _)| n))
+            }
+            _ => (rest, None),
+        };
+        let chord = parse_suffix(quality).map_err(|_| error())?;
+        Ok(ChordSymbol { root, chord, bass })
+    }
+}
+
+/// Parse a root note at the start of `s` (an upper case letter and an optional `#` or `b`), returning it and the rest
+/// of the string.
+pub(crate) fn parse_root(s: &str) -> Option<(Note, &str)> {
+    let mut chars = s.chars();
+    let base = match chars.next()? {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (accidental, rest) = match rest.chars().next() {
+        Some(c @ ('#' | '♯')) => (1, &rest[c.len_utf8()..]),
+        Some(c @ ('b' | '♭')) => (-1, &rest[c.len_utf8()..]),
+        _ => (0, rest),
+    };
+    Some((Note::C.transpose(base + accidental), rest))
+}
+
+/// Parse a chord quality suffix like `m7`, `maj7#11` or `7alt` into a chord.
+pub fn parse_suffix(suffix: &str) -> Result<Chord, ChordSymbolParseError> {
+    let error = || ChordSymbolParseError(suffix.to_string());
+    let (name, mut rest) = QUALITIES
+        .iter()
+        .map(|(name, _)| (*name, *name))
+        .chain(ALIASES.iter().copied())
+        .filter(|(spelling, _)| suffix.starts_with(spelling))
+        .max_by_key(|(spelling, _)| spelling.len())
+        .map(|(spelling, name)| (name, &suffix[spelling.len()..]))
+        .ok_or_else(error)?;
+    let mut semitones: Vec<usize> = quality_semitones(name).ok_or_else(error)?.to_vec();
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix(['(', ')', ',', ' ']) {
+            rest = stripped;
+            continue;
+        }
+        let (spelling, alteration) = ALTERATIONS
+            .iter()
+            .find(|(spelling, _)| rest.starts_with(spelling))
+            .ok_or_else(error)?;
+        match alteration {
+            Alteration::Add(s) => semitones.push(*s),
+            Alteration::Replace(removed, s) => {
+                semitones.retain(|x| !removed.contains(x));
+                semitones.push(*s);
+            }
+            Alteration::Remove(removed) => semitones.retain(|x| !removed.contains(x)),
+        }
+        rest = &rest[spelling.len()..];
+    }
+    Ok(chord_from_semitones(&semitones))
+}
+
+fn quality_semitones(name: &str) -> Option<&'static [usize]> {
+    QUALITIES.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+}
+
+/// Build a chord from the semitones of its tones above the root, naming each interval after the role it plays in the
+/// chord: the `3` of a `7#9` is an augmented second, the `6` of a `m7b5` is a diminished fifth and so on.
+pub fn chord_from_semitones(semitones: &[usize]) -> Chord {
+    let mut set: Vec<usize> = semitones.iter().map(|s| s % 12).collect();
+    set.push(0);
+    set.sort();
+    set.dedup();
+    let has = |s: usize| set.contains(&s);
+    let has_third = has(3) || has(4);
+    let has_seventh = has(10) || has(11);
+
+    let mut tones: Vec<(usize, Interval)> = set
+        .iter()
+        .map(|s| match s {
+            0 => (1, Interval::PerfectUnison),
+            1 => (9, Interval::MinorSecond),
+            2 if has_third => (9, Interval::MajorSecond),
+            2 => (3, Interval::MajorSecond),
+            3 if has(4) => (9, Interval::AugmentedSecond),
+            3 => (3, Interval::MinorThird),
+            4 => (3, Interval::MajorThird),
+            5 if has_third => (11, Interval::PerfectFourth),
+            5 => (3, Interval::PerfectFourth),
+            6 if has(7) => (11, Interval::AugmentedFourth),
+            6 => (5, Interval::DiminishedFifth),
+            7 => (5, Interval::PerfectFifth),
+            8 if has(7) || !has(4) => (13, Interval::MinorSixth),
+            8 => (5, Interval::AugmentedFifth),
+            9 if has(3) && has(6) && !has_seventh => (7, Interval::DiminishedSeventh),
+            9 if has_seventh => (13, Interval::MajorSixth),
+            9 => (6, Interval::MajorSixth),
+            10 => (7, Interval::MinorSeventh),
+            11 => (7, Interval::MajorSeventh),
+            _ => unreachable!(),
+        })
+        .collect();
+    tones.sort_by_key(|(role, interval)| (*role, interval.semitones()));
+    Chord {
+        intervals: tones.into_iter().map(|(_, i)| i).collect(),
+    }
+}
+
+/// The conventional suffix for a chord, like `m7` or `7#11`.
+pub fn suffix(chord: &Chord) -> String {
+    let semitones = chord.semitones();
+    if let Some(name) = exact_quality(&semitones) {
+        return name.to_string();
+    }
+    if !semitones.contains(&7) {
+        let mut with_fifth = semitones.clone();
+        with_fifth.push(7);
+        with_fifth.sort();
+        if let Some(name) = exact_quality(&with_fifth) {
+            return format!("{}(no5)", name);
+        }
+    }
+
+    let base = QUALITIES
+        .iter()
+        .filter(|(_, tones)| tones.iter().all(|t| semitones.contains(t)))
+        .rev()
+        .max_by_key(|(_, tones)| tones.len());
+    match base {
+        Some((name, tones)) => {
+            let has_seventh = tones.contains(&10) || tones.contains(&11);
+            let has_fifth = tones.contains(&7);
+            let alterations: Vec<&str> = semitones
+                .iter()
+                .filter(|s| !tones.contains(s))
+                .map(|s| alteration_name(*s, has_seventh, has_fifth))
+                .collect();
+            let alterations = alterations.join("");
+            // `C7(9)` and `C(b9)` rather than `C79` and `Cb9`, which read as other chords.
+            let digits =
+                name.ends_with(|c: char| c.is_ascii_digit()) && alterations.starts_with(|c: char| c.is_ascii_digit());
+            let accidental = name.is_empty() && alterations.starts_with(['b', '#']);
+            if digits || accidental {
+                format!("{}({})", name, alterations)
+            } else {
+                format!("{}{}", name, alterations)
+            }
+        }
+        None => {
+            let mut with_fifth = semitones.clone();
+            with_fifth.push(7);
+            format!("{}(no5)", suffix(&chord_from_semitones(&with_fifth)))
+        }
+    }
+}
+
+pub(crate) fn exact_quality(semitones: &[usize]) -> Option<&'static str> {
+    QUALITIES
+        .iter()
+        .find(|(_, tones)| *tones == semitones)
+        .map(|(name, _)| *name)
+}
+
+fn alteration_name(semitones: usize, has_seventh: bool, has_fifth: bool) -> &'static str {
+    match (semitones, has_seventh) {
+        (1, _) => "b9",
+        (2, true) => "9",
+        (2, false) => "add9",
+        (3, _) => "#9",
+        (4, _) => "add3",
+        (5, true) => "11",
+        (5, false) => "add11",
+        (6, true) => "#11",
+        (6, false) => "b5",
+        (8, true) => "b13",
+        (8, false) if has_fifth => "b6",
+        (8, false) => "#5",
+        (9, true) => "13",
+        (9, false) => "6",
+        (10, _) => "7",
+        (11, _) => "maj7",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::{Tetrad, Triad};
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("C", C, vec![C, E, G])]
+    #[case("Dm", D, vec![D, F, A])]
+    #[case("Dm7", D, vec![D, F, A, C])]
+    #[case("G7", G, vec![G, B, D, F])]
+    #[case("Cmaj7", C, vec![C, E, G, B])]
+    #[case("CΔ7", C, vec![C, E, G, B])]
+    #[case("F#m7b5", Gb, vec![Gb, A, C, E])]
+    #[case("Bø", B, vec![B, D, F, A])]
+    #[case("C#dim7", Db, vec![Db, E, G, Bb])]
+    #[case("Bbaug", Bb, vec![Bb, D, Gb])]
+    #[case("Gsus", G, vec![G, C, D])]
+    #[case("C6/9", C, vec![C, E, G, A, D])]
+    #[case("Cmaj7#11", C, vec![C, E, G, B, Gb])]
+    #[case("G7b9#11", G, vec![G, B, D, F, Ab, Db])]
+    #[case("C7(b9,#9)", C, vec![C, E, G, Bb, Db, Eb])]
+    #[case("D7alt", D, vec![D, Gb, Ab, Bb, C, Eb, F])]
+    #[case("Am(maj7)", A, vec![A, C, E, Ab])]
+    #[case("E7sus4", E, vec![E, A, B, D])]
+    fn parse_symbol(#[case] input: &str, #[case] root: Note, #[case] notes: Vec<Note>) {
+        let symbol: ChordSymbol = input.parse().unwrap();
+        assert_eq!(symbol.root, root);
+        assert_eq!(symbol.notes(), notes);
+        assert_eq!(symbol.bass, None);
+    }
+
+    #[rstest]
+    #[case("C/E", C, Some(E))]
+    #[case("Am7/G", A, Some(G))]
+    #[case("D/F#", D, Some(Gb))]
+    fn parse_slash_chord(#[case] input: &str, #[case] root: Note, #[case] bass: Option<Note>) {
+        let symbol: ChordSymbol = input.parse().unwrap();
+        assert_eq!(symbol.root, root);
+        assert_eq!(symbol.bass, bass);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("H7")]
+    #[case("cmaj7")]
+    #[case("Cxyz")]
+    #[case("C7/X")]
+    fn parse_error(#[case] input: &str) {
+        assert!(input.parse::<ChordSymbol>().is_err());
+    }
+
+    #[rstest]
+    #[case(Triad::Major.intervals().to_vec(), "")]
+    #[case(Triad::Minor.intervals().to_vec(), "m")]
+    #[case(Triad::Diminished.intervals().to_vec(), "dim")]
+    #[case(Triad::Augmented.intervals().to_vec(), "aug")]
+    #[case(Tetrad::Dominant.intervals().to_vec(), "7")]
+    #[case(Tetrad::Minor7Flat5.intervals().to_vec(), "m7b5")]
+    #[case(Tetrad::Diminished7.intervals().to_vec(), "dim7")]
+    #[case(Tetrad::MinorMajor7.intervals().to_vec(), "mMaj7")]
+    fn suffix_of_chord(#[case] intervals: Vec<Interval>, #[case] expected: &str) {
+        assert_eq!(suffix(&Chord { intervals }), expected);
+    }
+
+    #[rstest]
+    #[case("Cmaj7#11", "Cmaj7#11")]
+    #[case("G7b9#11", "G7b9#11")]
+    #[case("G9#11", "G9#11")]
+    #[case("C7(13)", "C7(13)")]
+    #[case("C7(9,13)", "C13")]
+    #[case("C7no5", "C7(no5)")]
+    #[case("Cmb6", "Cmb6")]
+    #[case("Cm7b5b9", "Cm7b5b9")]
+    #[case("CM7", "Cmaj7")]
+    #[case("C-7", "Cm7")]
+    #[case("C/E", "C/E")]
+    #[case("C6add9", "C6/9")]
+    #[case("C(b9)/E", "C(b9)/E")]
+    fn display_round_trip(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(input.parse::<ChordSymbol>().unwrap().to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(&[C, E, G], C, Some("C"))]
+    #[case(&[C, E, G], E, Some("C/E"))]
+    #[case(&[A, C, E, G], A, Some("Am7"))]
+    #[case(&[A, C, E, G], C, Some("C6"))]
+    #[case(&[G, B, D, F, A], G, Some("G9"))]
+    #[case(&[C, G], C, Some("C5"))]
+    #[case(&[C, E, G, B, Gb], C, Some("Cmaj7#11"))]
+    #[case(&[C, E, G, Db, A], C, Some("A7#9/C"))]
+    #[case(&[E, G, Db, C], E, Some("C(b9)/E"))]
+    #[case(&[C, E], C, None)]
+    #[case(&[C], C, None)]
+    fn identify_notes(#[case] notes: &[Note], #[case] bass: Note, #[case] expected: Option<&str>) {
+        let symbol = identify(notes, bass);
+        assert_eq!(symbol.as_ref().map(|s| s.to_string()).as_deref(), expected);
+        if let Some(symbol) = symbol {
+            let parsed: ChordSymbol = symbol.to_string().parse().unwrap();
+            assert_eq!(
+                (parsed.root, parsed.chord.semitones()),
+                (symbol.root, symbol.chord.semitones())
+            );
+        }
+    }
+
+    #[test]
+    fn intervals_are_named_by_role() {
+        assert_eq!(
+            parse_suffix("7#9").unwrap().intervals,
+            vec![
+                Interval::PerfectUnison,
+                Interval::MajorThird,
+                Interval::PerfectFifth,
+                Interval::MinorSeventh,
+                Interval::AugmentedSecond,
+            ]
+        );
+        assert_eq!(parse_suffix("dim7").unwrap().intervals, Tetrad::Diminished7.intervals());
+        assert_eq!(parse_suffix("m7b5").unwrap().intervals, Tetrad::Minor7Flat5.intervals());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::interval::Interval;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Triad {
     Major,
     Minor,
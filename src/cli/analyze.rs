@@ -0,0 +1,76 @@
+use clap::{arg, ArgMatches, Command};
+
+use crate::chord::ChordSymbol;
+use crate::cli::scales::ScaleName;
+use crate::harmony::{analyze_progression, cadences, functions, phrase_ends};
+use crate::key::Key;
+use crate::note::Note;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let key = key_arg(m)?;
+    let (written, boundaries) = parse_chords(m.get_many::<String>("CHORDS").unwrap_or_default())?;
+    let chords: Vec<ChordSymbol> = written.iter().map(|(_, chord)| chord.clone()).collect();
+    let numerals = analyze_progression(&key, &chords);
+    let ends = phrase_ends(&numerals, &boundaries);
+    let cadences = cadences(&numerals, &boundaries);
+    let functions = functions(&numerals);
+    for (i, (((text, _), numeral), function)) in written.iter().zip(&numerals).zip(functions).enumerate() {
+        let line = format!("{:<10}{:<10}{}", text, numeral.to_string(), function);
+        match cadences.iter().find(|c| c.at == i) {
+            Some(cadence) => println!("{:<24}{}", line, cadence.cadence),
+            None if ends.contains(&i) => println!("{:<24}phrase end", line),
+            None => println!("{}", line),
+        }
+    }
+    Ok(())
+}
+
+/// Chord symbols as they were typed, with their parsed symbols.
+type Written<'a> = Vec<(&'a str, ChordSymbol)>;
+
+/// Read chord symbols as written and parsed, where a `|` token or a trailing comma marks the end of a phrase.
+fn parse_chords<'a>(tokens: impl Iterator<Item = &'a String>) -> anyhow::Result<(Written<'a>, Vec<usize>)> {
+    let mut chords = vec![];
+    let mut boundaries = vec![];
+    for token in tokens {
+        for part in token.split_whitespace() {
+            if part.chars().all(|c| c == '|') {
+                boundaries.extend(chords.len().checked_sub(1));
+                continue;
+            }
+            let chord = part.trim_end_matches(',');
+            if !chord.is_empty() {
+                chords.push((chord, chord.parse()?));
+            }
+            if chord.len() < part.len() {
+                boundaries.extend(chords.len().checked_sub(1));
+            }
+        }
+    }
+    Ok((chords, boundaries))
+}
+
+/// Read the `--key <TONIC> <SCALE>` option.
+pub(crate) fn key_arg(m: &ArgMatches) -> anyhow::Result<Key> {
+    key_option(m, "key")
+}
+
+/// Read an option holding a key as a tonic and a scale name, like `--key C major`.
+pub(crate) fn key_option(m: &ArgMatches, id: &str) -> anyhow::Result<Key> {
+    let values: Vec<&String> = m.get_many::<String>(id).unwrap_or_default().collect();
+    let tonic: Note = values[0].parse()?;
+    let scale: ScaleName = values[1].parse()?;
+    Ok(Key::new(tonic, scale.to_scale()))
+}
+
+pub fn analyze_subcommand() -> Command {
+    Command::new("analyze")
+        .about("Label a chord progression with Roman numerals, harmonic functions and cadences")
+        .arg(
+            arg!(--key <KEY> "the key, as a tonic and a scale name, like C major")
+                .num_args(2)
+                .value_names(["TONIC", "SCALE"])
+                .required(true),
+        )
+        .arg(arg!(<CHORDS> ... "chord symbols, like Dm7 G7 Cmaj7; end a phrase with a comma or a | token"))
+}
@@ -0,0 +1,29 @@
+use clap::{arg, ArgMatches, Command};
+
+use crate::cli::key::{profile_arg, profile_option};
+use crate::notation::tunes;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let path = m.get_one::<String>("FILE").expect("FILE is required");
+    let profile = profile_option(m);
+    for tune in tunes(&std::fs::read_to_string(path)?)? {
+        let reference = tune.reference.map_or("-".to_string(), |x| x.to_string());
+        let written = tune.key.as_ref().map_or("none".to_string(), |k| k.key().to_string());
+        let mode = tune.mode(profile).map_or("-".to_string(), |k| k.to_string());
+        println!(
+            "{:<6}{:<32}{:<20}{}",
+            reference,
+            tune.title.as_deref().unwrap_or_default(),
+            written,
+            mode
+        );
+    }
+    Ok(())
+}
+
+pub fn analyze_abc_subcommand() -> Command {
+    Command::new("analyze-abc")
+        .about("Find the diatonic mode of each tune of an ABC tune book")
+        .arg(arg!(<FILE> "an .abc file"))
+        .arg(profile_arg())
+}
@@ -0,0 +1,64 @@
+use clap::{arg, ArgMatches, Command};
+
+use crate::cli::key::{modes_arg, profile_arg, profile_option, scales_option};
+use crate::midi::{chords, keys, notes, scale_matches, spans, time_signatures, Resolution, Smf};
+use crate::scales::Scale;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let path = m.get_one::<String>("FILE").expect("FILE is required");
+    let smf = Smf::parse(&std::fs::read(path)?)?;
+    let notes = notes(&smf);
+    let pitched = notes.iter().filter(|n| !n.is_percussion()).count();
+    let seconds = notes.iter().map(|n| n.end_seconds).fold(0.0, f64::max);
+    println!("{} tracks, {} notes, {:.1} s", smf.tracks.len(), pitched, seconds);
+
+    let ranked = keys(&notes, profile_option(m), &scales_option(m));
+    if !ranked.is_empty() {
+        let best: Vec<String> = ranked
+            .iter()
+            .take(3)
+            .map(|k| format!("{} ({:.3})", k.key, k.score))
+            .collect();
+        println!("Key: {}", best.join(", "));
+    }
+    let matches: Vec<String> = scale_matches(&notes, &Scale::all())
+        .iter()
+        .take(3)
+        .map(|s| format!("{} ({:.0}%)", s.key, s.coverage * 100.0))
+        .collect();
+    if !matches.is_empty() {
+        println!("Scales: {}", matches.join(", "));
+    }
+
+    let resolution = match m.get_one::<String>("per").map(String::as_str) {
+        Some("beat") => Resolution::Beat,
+        _ => Resolution::Bar,
+    };
+    let end = notes.iter().map(|n| n.end).max().unwrap_or(0);
+    println!();
+    for chord in chords(
+        &notes,
+        &spans(&time_signatures(&smf), smf.ticks_per_quarter, end, resolution),
+    ) {
+        let label = match chord.span.beat {
+            Some(beat) => format!("{}.{}", chord.span.bar, beat),
+            None => chord.span.bar.to_string(),
+        };
+        let name = chord.chord.map_or("-".to_string(), |c| c.to_string());
+        println!("{:<8}{}", label, name);
+    }
+    Ok(())
+}
+
+pub fn analyze_midi_subcommand() -> Command {
+    Command::new("analyze-midi")
+        .about("Find the key, scale and chords of a Standard MIDI File")
+        .arg(arg!(<FILE> "a .mid file"))
+        .arg(
+            arg!(--per <SPAN> "name a chord for every bar or every beat")
+                .value_parser(["bar", "beat"])
+                .default_value("bar"),
+        )
+        .arg(profile_arg())
+        .arg(modes_arg())
+}
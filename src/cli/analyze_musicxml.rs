@@ -0,0 +1,109 @@
+use clap::{arg, value_parser, ArgMatches, Command};
+
+use crate::cli::key::{modes_arg, profile_arg, profile_option, scales_option};
+use crate::midi::{keys, scale_matches};
+use crate::notation::{
+    key_name, key_tonic, spell_in, spell_scale, transpose_tonic, ImportedScore, SpelledChord, SpelledNote,
+};
+use crate::scales::Scale;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let path = m.get_one::<String>("FILE").expect("FILE is required");
+    let score = ImportedScore::parse(&std::fs::read(path)?)?;
+    let notes = score.note_events();
+    let harmonies: Vec<_> = score.parts.iter().flat_map(|p| &p.harmonies).collect();
+    if let Some(title) = &score.title {
+        println!("{}", title);
+    }
+    println!(
+        "{} parts, {} notes, {} chord symbols",
+        score.parts.len(),
+        notes.len(),
+        harmonies.len()
+    );
+
+    let signature = score
+        .key_signature()
+        .and_then(|s| Some((s.key()?, s.fifths)))
+        .map(|(key, fifths)| (key, spell_in(key.tonic, fifths)));
+    if let Some((key, tonic)) = signature {
+        println!("Key signature: {} {}", tonic, key.scale);
+    }
+    let ranked = keys(&notes, profile_option(m), &scales_option(m));
+    if !ranked.is_empty() {
+        let best: Vec<String> = ranked
+            .iter()
+            .take(3)
+            .map(|k| format!("{} ({:.3})", key_name(&k.key), k.score))
+            .collect();
+        println!("Key: {}", best.join(", "));
+    }
+    let matches: Vec<String> = scale_matches(&notes, &Scale::all())
+        .iter()
+        .take(3)
+        .map(|s| format!("{} ({:.0}%)", key_name(&s.key), s.coverage * 100.0))
+        .collect();
+    if !matches.is_empty() {
+        println!("Scales: {}", matches.join(", "));
+    }
+
+    let semitones = m.get_one::<isize>("transpose").copied();
+    let tonics = signature
+        .or(ranked.first().map(|k| (k.key, key_tonic(&k.key))))
+        .zip(semitones)
+        .map(|((key, tonic), semitones)| (key.scale, tonic, transpose_tonic(tonic, key.scale, semitones)));
+    if let Some((scale, _, tonic)) = tonics {
+        println!("Transposed to {} {}", tonic, scale);
+    }
+    if !harmonies.is_empty() {
+        println!();
+    }
+    for harmony in harmonies {
+        let line = match harmony.symbol() {
+            Ok(None) => "N.C.".to_string(),
+            Ok(Some(symbol)) => {
+                let written = SpelledChord {
+                    bass: harmony.bass.filter(|_| symbol.bass.is_some()),
+                    root: harmony.root.expect("chord symbols have a root"),
+                    symbol: symbol.clone(),
+                };
+                let transposed = match (semitones, tonics) {
+                    (Some(semitones), Some((scale, from, to))) => {
+                        let fifths = spell_scale(to, scale).iter().map(|n| n.alter).sum();
+                        let moved = |note: SpelledNote| match note.moved(from, to) {
+                            moved if moved.accidentals() < 2 => moved,
+                            moved => spell_in(moved.note(), fifths),
+                        };
+                        Some(SpelledChord {
+                            symbol: symbol.transpose(semitones),
+                            root: moved(written.root),
+                            bass: written.bass.map(moved),
+                        })
+                    }
+                    (Some(semitones), None) => Some(SpelledChord::new(&symbol.transpose(semitones), None)),
+                    (None, _) => None,
+                };
+                match transposed {
+                    Some(transposed) => format!("{:<12}{}", written.to_string(), transposed),
+                    None => written.to_string(),
+                }
+            }
+            Err(e) => e.to_string(),
+        };
+        println!("{:<8}{}", harmony.measure, line);
+    }
+    Ok(())
+}
+
+pub fn analyze_musicxml_subcommand() -> Command {
+    Command::new("analyze-musicxml")
+        .about("Check the chord symbols of a MusicXML score and find its key and scale")
+        .arg(arg!(<FILE> "a .musicxml or .mxl file"))
+        .arg(
+            arg!(--transpose <SEMITONES> "also list the chord symbols transposed by this many semitones")
+                .value_parser(value_parser!(isize))
+                .allow_negative_numbers(true),
+        )
+        .arg(profile_arg())
+        .arg(modes_arg())
+}
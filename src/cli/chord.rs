@@ -0,0 +1,125 @@
+use clap::{arg, value_parser, ArgAction, ArgMatches, Command};
+
+use crate::cli::fretboard::{fretboard_args, fretboard_option, octave_diagram};
+use crate::cli::keyboard::{keyboard_arg, keyboard_option};
+use crate::cli::scales::{format_arg, labels_arg, labels_option, staff_arg, staff_option, wav_args, write_wav};
+use crate::fretboard::{chord_box, fingerings, Constraints};
+use crate::harmony::chord_scale::label;
+use crate::midi;
+use crate::midi::{ChordStyle, Direction, Settings};
+use crate::notation::{to_abc, to_lilypond, Labels, Score, SpelledChord, SpelledNote};
+use crate::pitch::Pitch;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let root = *m.get_one::<SpelledNote>("KEY").expect("KEY is required");
+    let kind = m.get_one::<String>("TYPE").expect("TYPE is required");
+    let harmony: SpelledChord = format!("{}{}", root, kind).parse()?;
+    let symbol = &harmony.symbol;
+    let arpeggio = m.get_flag("arpeggio");
+
+    let mut score = if arpeggio {
+        Score::arpeggio(&harmony, 4, Direction::Ascending)
+    } else {
+        Score::chord(&harmony, 4)
+    };
+    if let Some(labels) = labels_option(m) {
+        score.label(labels);
+    }
+    let notes = harmony.notes();
+    match (m.get_one::<String>("format").map(String::as_str), fretboard_option(m)) {
+        (Some("lilypond"), _) => print!("{}", to_lilypond(&score)),
+        (Some("abc"), _) => print!("{}", to_abc(&score)),
+        _ if m.contains_id("staff") => print!("{}", staff_option(m, &score).expect("staff is present")),
+        _ if m.contains_id("keyboard") => {
+            let semitones = symbol.chord.semitones();
+            let marks: Vec<_> = notes
+                .iter()
+                .zip(&symbol.chord.intervals)
+                .map(|(n, interval)| {
+                    let label = match labels_option(m) {
+                        Some(Labels::Degrees) => label(interval.semitones() % 12, &semitones).to_string(),
+                        Some(Labels::Intervals) => interval.abbreviation().to_string(),
+                        None => n.to_string(),
+                    };
+                    (n.note(), label)
+                })
+                .collect();
+            print!("{}", keyboard_option(m, &marks).expect("keyboard is present"));
+        }
+        (_, Some(fretboard)) if m.contains_id("fingerings") => {
+            let constraints = Constraints {
+                stretch: *m.get_one::<u8>("stretch").expect("stretch has a default"),
+                fingers: *m.get_one::<u8>("fingers").expect("fingers has a default"),
+                open_strings: !m.get_flag("no-open"),
+                muted_strings: !m.get_flag("no-mute"),
+                root_in_bass: !m.get_flag("any-bass"),
+            };
+            let count = *m.get_one::<usize>("fingerings").expect("fingerings is present");
+            let shapes = fingerings(&fretboard, symbol.root, &symbol.chord, &constraints);
+            if shapes.is_empty() {
+                println!("No playable fingerings");
+            }
+            for (i, fingering) in shapes.iter().take(count).enumerate() {
+                if m.get_flag("tab") {
+                    println!("{}", fingering);
+                } else {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("{}", fingering);
+                    print!("{}", chord_box(&fretboard, fingering));
+                }
+            }
+        }
+        (_, Some(fretboard)) => {
+            let labels: Vec<_> = notes.iter().map(|n| (n.note(), n.to_string())).collect();
+            print!("{}", octave_diagram(&fretboard, &labels));
+        }
+        _ => {
+            let notes: Vec<String> = notes.iter().map(|n| n.to_string()).collect();
+            println!("{}", notes.join(" "));
+        }
+    }
+
+    let settings = Settings::default();
+    let style = if arpeggio {
+        ChordStyle::Arpeggio
+    } else {
+        ChordStyle::Block
+    };
+    let part = midi::chord(Pitch::new(symbol.root, 4), &symbol.chord, style, &settings);
+    write_wav(m, &[part], &settings, symbol.root)
+}
+
+pub fn chord_subcommand() -> Command {
+    Command::new("chord")
+        .about("Spell a chord")
+        .arg(arg!(<KEY> "the root of the chord").value_parser(value_parser!(SpelledNote)))
+        .arg(arg!(<TYPE> "the chord type, like maj, m7 or 7#11"))
+        .arg(arg!(--arpeggio "play the chord one note at a time from the bottom up").action(ArgAction::SetTrue))
+        .arg(format_arg())
+        .arg(labels_arg())
+        .args(fretboard_args())
+        .arg(
+            arg!(--fingerings <COUNT> "list the easiest ways to play the chord on the --fretboard instrument")
+                .value_parser(value_parser!(usize))
+                .requires("fretboard"),
+        )
+        .arg(arg!(--tab "list the fingerings as tab, like x32010, instead of chord boxes").action(ArgAction::SetTrue))
+        .arg(
+            arg!(--stretch <FRETS> "the most frets a fingering may cover")
+                .value_parser(value_parser!(u8).range(1..=6))
+                .default_value("4"),
+        )
+        .arg(
+            arg!(--fingers <COUNT> "the number of fretting fingers")
+                .value_parser(value_parser!(u8).range(1..=4))
+                .default_value("4"),
+        )
+        .arg(arg!(--"no-open" "leave out fingerings with open strings").action(ArgAction::SetTrue))
+        .arg(arg!(--"no-mute" "leave out fingerings with muted strings").action(ArgAction::SetTrue))
+        .arg(arg!(--"any-bass" "allow other chord tones than the root in the bass").action(ArgAction::SetTrue))
+        .arg(keyboard_arg())
+        .arg(staff_arg())
+        .args(wav_args())
+}
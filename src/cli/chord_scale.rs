@@ -0,0 +1,25 @@
+use clap::{arg, value_parser, ArgMatches, Command};
+
+use crate::cli::analyze::key_arg;
+use crate::harmony::chord_scales;
+use crate::notation::SpelledChord;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let chord = m.get_one::<SpelledChord>("CHORD").expect("CHORD is required");
+    let key = if m.contains_id("key") { Some(key_arg(m)?) } else { None };
+    for fit in chord_scales(chord, key.as_ref()) {
+        println!("{}", fit);
+    }
+    Ok(())
+}
+
+pub fn chord_scale_subcommand() -> Command {
+    Command::new("chord-scale")
+        .about("List the scales that fit a chord, marking chord tones (R), tensions [9] and avoid notes <11>")
+        .arg(arg!(<CHORD> "a chord symbol, like G7 or Cmaj7#11").value_parser(value_parser!(SpelledChord)))
+        .arg(
+            arg!(--key <KEY> "rank scales by how well they fit a key, given as a tonic and a scale name")
+                .num_args(2)
+                .value_names(["TONIC", "SCALE"]),
+        )
+}
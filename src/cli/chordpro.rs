@@ -0,0 +1,66 @@
+use anyhow::anyhow;
+use clap::{arg, value_parser, ArgAction, ArgGroup, ArgMatches, Command};
+
+use crate::notation::{Song, SongKey};
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let (name, m) = m.subcommand().expect("a subcommand is required");
+    let path = m.get_one::<String>("FILE").expect("FILE is required");
+    let mut song: Song = std::fs::read_to_string(path)?.parse()?;
+    match name {
+        "transpose" => {
+            if song.key().is_none() {
+                return Err(anyhow!("{} has no key and no chords", path));
+            }
+            match (m.get_one::<SongKey>("to"), m.get_one::<isize>("semitones")) {
+                (Some(key), _) => song.transpose_to(key),
+                (None, Some(semitones)) => song.transpose(*semitones),
+                (None, None) => unreachable!("--to or --semitones is required"),
+            }
+        }
+        "capo" => song.set_capo(*m.get_one::<u8>("FRET").expect("FRET is required")),
+        _ => {}
+    }
+    if name == "render" || m.get_flag("text") {
+        print!("{}", song.render());
+    } else {
+        print!("{}", song.to_chordpro());
+    }
+    Ok(())
+}
+
+pub fn chordpro_subcommand() -> Command {
+    let text =
+        || arg!(--text "print the song as plain text with the chords above the lyrics").action(ArgAction::SetTrue);
+    Command::new("chordpro")
+        .about("Transpose, move the capo of and print ChordPro songs")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("transpose")
+                .about("Move every chord into another key, spelled after its function")
+                .arg(arg!(<FILE> "a ChordPro file"))
+                .arg(
+                    arg!(--to <KEY> "the new key, like Eb or C#m; a minor song stays minor")
+                        .value_parser(value_parser!(SongKey)),
+                )
+                .arg(
+                    arg!(--semitones <N> "how many semitones to move the chords up, or down when negative")
+                        .value_parser(value_parser!(isize))
+                        .allow_negative_numbers(true),
+                )
+                .group(ArgGroup::new("interval").args(["to", "semitones"]).required(true))
+                .arg(text()),
+        )
+        .subcommand(
+            Command::new("capo")
+                .about("Put the capo on another fret, keeping the song in the same key")
+                .arg(arg!(<FILE> "a ChordPro file"))
+                .arg(arg!(<FRET> "the fret of the capo, 0 for none").value_parser(value_parser!(u8).range(0..=12)))
+                .arg(text()),
+        )
+        .subcommand(
+            Command::new("render")
+                .about("Print a song as plain text with the chords above the lyrics")
+                .arg(arg!(<FILE> "a ChordPro file")),
+        )
+}
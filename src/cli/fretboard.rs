@@ -0,0 +1,121 @@
+use clap::{arg, value_parser, Arg, ArgMatches, Command};
+
+use crate::fretboard::{diagram, note_diagram, tab, Fretboard, Pattern, Position, Preset};
+use crate::key::Key;
+use crate::notation::{spell_key, Labels};
+use crate::note::Note;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let fretboard = m
+        .get_one::<Preset>("INSTRUMENT")
+        .expect("INSTRUMENT is required")
+        .fretboard()
+        .with_capo(*m.get_one::<u8>("capo").expect("capo has a default"));
+
+    if let Some(note) = m.get_one::<Note>("find") {
+        print!(
+            "{}",
+            note_diagram(&fretboard, &[(*note, note.to_string())], fretboard.playable())
+        );
+    } else if let Some(values) = m.get_many::<u8>("at") {
+        let values: Vec<u8> = values.copied().collect();
+        let position = Position::new(values[0] as usize, values[1]);
+        match fretboard.pitch_at(position) {
+            Some(pitch) => println!("{}", pitch),
+            None => println!("String {} has no fret {} to play", values[0], values[1]),
+        }
+    } else {
+        let strings: Vec<String> = fretboard.strings.iter().map(|p| p.to_string()).collect();
+        println!("{}, {} frets", strings.join(" "), fretboard.frets);
+    }
+    Ok(())
+}
+
+pub(crate) fn fretboard_args() -> [Arg; 2] {
+    [
+        arg!(--fretboard <INSTRUMENT> "draw the notes on the neck of a guitar, bass, ukulele or other instrument")
+            .value_parser(value_parser!(Preset)),
+        arg!(--capo <FRET> "the fret of the capo on the instrument, 0 for none")
+            .value_parser(value_parser!(u8).range(0..=12))
+            .default_value("0"),
+    ]
+}
+
+/// The instrument given with `--fretboard`, if any, with its capo.
+pub(crate) fn fretboard_option(m: &ArgMatches) -> Option<Fretboard> {
+    let capo = *m.get_one::<u8>("capo").expect("capo has a default");
+    Some(m.get_one::<Preset>("fretboard")?.fretboard().with_capo(capo))
+}
+
+/// A diagram of the first twelve frets from the capo.
+pub(crate) fn octave_diagram(fretboard: &Fretboard, labels: &[(Note, String)]) -> String {
+    let last = fretboard.frets.min(fretboard.capo + 12);
+    note_diagram(fretboard, labels, fretboard.capo..=last)
+}
+
+/// Print scale patterns as a diagram of their notes and one of their fingers, or as tab going up and back down.
+pub(crate) fn print_patterns(
+    fretboard: &Fretboard,
+    key: &Key,
+    patterns: &[Pattern],
+    labels: Option<Labels>,
+    as_tab: bool,
+) {
+    let names = spell_key(key);
+    let intervals = key.scale.intervals();
+    for (i, pattern) in patterns.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", pattern.name);
+        if as_tab {
+            let up: Vec<_> = pattern.notes.iter().map(|n| (n.position, n.finger)).collect();
+            let down: Vec<_> = up.iter().rev().copied().collect();
+            print!("{}\n{}", tab(fretboard, &up), tab(fretboard, &down));
+            continue;
+        }
+        let (low, high) = pattern.frets();
+        let frets = low..=high;
+        let marks: Vec<_> = pattern
+            .notes
+            .iter()
+            .map(|n| {
+                let label = match labels {
+                    Some(Labels::Degrees) => n.degree.to_string(),
+                    Some(Labels::Intervals) => intervals[n.degree - 1].abbreviation().to_string(),
+                    None => names[n.degree - 1].to_string(),
+                };
+                (n.position, label)
+            })
+            .collect();
+        let fingers: Vec<_> = pattern
+            .notes
+            .iter()
+            .map(|n| (n.position, n.finger.unwrap_or(0).to_string()))
+            .collect();
+        print!("{}", diagram(fretboard, &marks, frets.clone()));
+        print!("{}", diagram(fretboard, &fingers, frets));
+    }
+}
+
+pub fn fretboard_subcommand() -> Command {
+    Command::new("fretboard")
+        .about("Find notes on the neck of a guitar, bass, ukulele, mandolin or banjo")
+        .arg(
+            arg!(<INSTRUMENT> "guitar, drop-d, dadgad, open-g, 7-string, bass, 5-string-bass, ukulele, mandolin or banjo")
+                .value_parser(value_parser!(Preset)),
+        )
+        .arg(
+            arg!(--capo <FRET> "the fret of the capo, 0 for none")
+                .value_parser(value_parser!(u8).range(0..=12))
+                .default_value("0"),
+        )
+        .arg(arg!(--find <NOTE> "draw every place to play a note").value_parser(value_parser!(Note)))
+        .arg(
+            arg!(--at <POSITION> "the note at a string, counted from 1 for the highest, and a fret")
+                .num_args(2)
+                .value_names(["STRING", "FRET"])
+                .value_parser(value_parser!(u8))
+                .conflicts_with("find"),
+        )
+}
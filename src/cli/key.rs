@@ -0,0 +1,83 @@
+use anyhow::anyhow;
+use clap::{arg, value_parser, Arg, ArgAction, ArgMatches, Command};
+
+use crate::harmony::{find_keys, Profile};
+use crate::note::Note;
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let notes = m
+        .get_many::<String>("NOTES")
+        .unwrap_or_default()
+        .map(|s| parse_weighted(s))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let (profile, scales) = (profile_option(m), scales_option(m));
+    let top = *m.get_one::<usize>("top").expect("top has a default");
+
+    let ranked = find_keys(&notes, profile, &scales);
+    if ranked.is_empty() {
+        return Err(anyhow!("The notes don't point to any key"));
+    }
+    for score in ranked.iter().take(top) {
+        println!("{:<24}{:.3}", score.key.to_string(), score.score);
+    }
+    Ok(())
+}
+
+/// The key profile chosen with `--profile`.
+pub(crate) fn profile_option(m: &ArgMatches) -> Profile {
+    match m.get_one::<String>("profile").map(String::as_str) {
+        Some("temperley") => Profile::Temperley,
+        _ => Profile::KrumhanslSchmuckler,
+    }
+}
+
+/// Major and minor, or all the diatonic and harmonic minor modes with `--modes`.
+pub(crate) fn scales_option(m: &ArgMatches) -> Vec<Scale> {
+    if m.get_flag("modes") {
+        diatonic::Mode::array()
+            .into_iter()
+            .map(Scale::Diatonic)
+            .chain(harmonic_minor::Mode::array().into_iter().map(Scale::HarmonicMinor))
+            .collect()
+    } else {
+        vec![
+            Scale::Diatonic(diatonic::Mode::Ionian),
+            Scale::Diatonic(diatonic::Mode::Aeolian),
+        ]
+    }
+}
+
+pub(crate) fn profile_arg() -> Arg {
+    arg!(--profile <PROFILE> "the key profiles to compare the notes with")
+        .value_parser(["krumhansl", "temperley"])
+        .default_value("krumhansl")
+}
+
+pub(crate) fn modes_arg() -> Arg {
+    arg!(--modes "also consider the diatonic and harmonic minor modes, not just major and minor")
+        .action(ArgAction::SetTrue)
+}
+
+/// Parse a note with an optional weight, like `C` or `E:1.5`.
+fn parse_weighted(s: &str) -> anyhow::Result<(Note, f64)> {
+    let (note, weight) = s.split_once(':').unwrap_or((s, "1"));
+    let note: Note = note.parse()?;
+    let weight: f64 = weight
+        .parse()
+        .map_err(|_| anyhow!("Invalid weight {} for note {}", weight, note))?;
+    Ok((note, weight))
+}
+
+pub fn key_subcommand() -> Command {
+    Command::new("key")
+        .about("Find the most likely keys of some notes by correlating them with key profiles")
+        .arg(arg!(<NOTES> ... "notes, optionally weighted by their duration, like C E:2 G"))
+        .arg(profile_arg())
+        .arg(modes_arg())
+        .arg(
+            arg!(--top <N> "how many keys to list")
+                .value_parser(value_parser!(usize))
+                .default_value("5"),
+        )
+}
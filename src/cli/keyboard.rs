@@ -0,0 +1,36 @@
+use std::io::IsTerminal;
+
+use clap::{arg, value_parser, Arg, ArgMatches};
+
+use crate::keyboard::{keyboard, Style};
+use crate::note::Note;
+
+pub(crate) fn keyboard_arg() -> Arg {
+    arg!(--keyboard [OCTAVES] "draw the notes on a piano keyboard of one or more octaves")
+        .value_parser(value_parser!(u8).range(1..=4))
+        .num_args(0..=1)
+        .default_missing_value("1")
+        .conflicts_with("fretboard")
+}
+
+/// The keyboard given with `--keyboard`, if any, drawn with the labelled notes.
+pub(crate) fn keyboard_option(m: &ArgMatches, marks: &[(Note, String)]) -> Option<String> {
+    let octaves = *m.get_one::<u8>("keyboard")?;
+    Some(keyboard(marks, octaves as usize, terminal_style()))
+}
+
+/// Box drawing characters when the locale is UTF-8, and colors when printing to a terminal unless `NO_COLOR` is set.
+pub(crate) fn terminal_style() -> Style {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_default()
+        .to_lowercase();
+    let color = std::io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && std::env::var("TERM").map_or(true, |term| term != "dumb");
+    Style {
+        unicode: locale.contains("utf-8") || locale.contains("utf8"),
+        color,
+    }
+}
@@ -1 +1,16 @@
+pub mod analyze;
+pub mod analyze_abc;
+pub mod analyze_midi;
+pub mod analyze_musicxml;
+pub mod chord;
+pub mod chord_scale;
+pub mod chordpro;
+pub mod fretboard;
+pub mod key;
+pub mod keyboard;
+pub mod modulate;
+pub mod negative;
+pub mod progression;
+pub mod reharm;
 pub mod scales;
+pub mod tonnetz;
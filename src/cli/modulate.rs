@@ -0,0 +1,95 @@
+use anyhow::anyhow;
+use clap::{arg, ArgMatches, Command};
+
+use crate::chord::ChordSymbol;
+use crate::cli::analyze::key_option;
+use crate::harmony::{common_tone_links, common_tones, modulations, pivot_chords};
+use crate::key::Key;
+use crate::notation::{key_name, spell_key, SpelledChord};
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let from = key_option(m, "from")?;
+    let to = key_option(m, "to")?;
+    if from == to {
+        return Err(anyhow!("The starting and target keys are both {}", key_name(&from)));
+    }
+    let spelled = |chords: &[&ChordSymbol], key: &Key| -> String {
+        let names: Vec<String> = chords
+            .iter()
+            .map(|c| SpelledChord::new(c, Some(key)).to_string())
+            .collect();
+        names.join(" ")
+    };
+
+    let notes = spell_key(&to);
+    let tones: Vec<String> = common_tones(&from, &to)
+        .iter()
+        .filter_map(|tone| notes.iter().find(|n| n.note() == *tone))
+        .map(|n| n.to_string())
+        .collect();
+    println!("Common tones: {}", tones.join(" "));
+
+    println!();
+    println!("Pivot chords ({} → {}):", key_name(&from), key_name(&to));
+    for pivot in pivot_chords(&from, &to) {
+        println!(
+            "  {:<10}{:<10}{}",
+            spelled(&[&pivot.chord], &to),
+            pivot.from.to_string(),
+            pivot.to
+        );
+    }
+
+    println!();
+    println!("Common-tone links:");
+    for link in common_tone_links(&from, &to) {
+        let chord = SpelledChord::new(&link.from, Some(&from)).notes();
+        let tones: Vec<String> = link
+            .tones
+            .iter()
+            .filter_map(|tone| chord.iter().find(|n| n.note() == *tone))
+            .map(|n| n.to_string())
+            .collect();
+        println!(
+            "  {} → {}: {}",
+            spelled(&[&link.from], &from),
+            spelled(&[&link.to], &to),
+            tones.join(" ")
+        );
+    }
+
+    println!();
+    println!("Modulations:");
+    for modulation in modulations(&from, &to) {
+        // The first chord is the tonic of the old key, the others lead to the new one.
+        let (start, rest) = modulation
+            .chords
+            .split_first()
+            .expect("a modulation starts on the old tonic");
+        let rest: Vec<&ChordSymbol> = rest.iter().collect();
+        println!(
+            "  {}: {} {}",
+            modulation.technique,
+            spelled(&[start], &from),
+            spelled(&rest, &to)
+        );
+    }
+    Ok(())
+}
+
+pub fn modulate_subcommand() -> Command {
+    Command::new("modulate")
+        .about("Find pivot chords and common tones between two keys and suggest modulations")
+        .arg(
+            arg!(--from <KEY> "the starting key, as a tonic and a scale name, like C major")
+                .num_args(2)
+                .value_names(["TONIC", "SCALE"])
+                .required(true),
+        )
+        .arg(
+            arg!(--to <KEY> "the target key, as a tonic and a scale name, like E minor")
+                .num_args(2)
+                .value_names(["TONIC", "SCALE"])
+                .required(true),
+        )
+}
@@ -0,0 +1,33 @@
+use clap::{arg, value_parser, ArgMatches, Command};
+
+use crate::chord::ChordSymbol;
+use crate::harmony::{negative_chord, NegativeRoot};
+use crate::key::Key;
+use crate::notation::SpelledChord;
+use crate::note::Note;
+use crate::scales::{diatonic, Scale};
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let tonic = *m.get_one::<Note>("KEY").expect("KEY is required");
+    let key = Key::new(tonic, Scale::Diatonic(diatonic::Mode::Ionian));
+    let chords = m.get_many::<ChordSymbol>("CHORDS").unwrap_or_default();
+    let written = m.get_raw("CHORDS").unwrap_or_default();
+    for (chord, text) in chords.zip(written) {
+        let fifth = SpelledChord::new(&negative_chord(tonic, chord, NegativeRoot::Fifth), Some(&key)).to_string();
+        let tertian = SpelledChord::new(&negative_chord(tonic, chord, NegativeRoot::Tertian), Some(&key)).to_string();
+        let text = text.to_string_lossy();
+        if fifth == tertian {
+            println!("{:<10}{}", text, fifth);
+        } else {
+            println!("{:<10}{:<10}{}", text, fifth, tertian);
+        }
+    }
+    Ok(())
+}
+
+pub fn negative_subcommand() -> Command {
+    Command::new("negative")
+        .about("Mirror chords around the axis between the tonic and the fifth of a key (negative harmony)")
+        .arg(arg!(<KEY> "the tonic of the key").value_parser(value_parser!(Note)))
+        .arg(arg!(<CHORDS> ... "chord symbols, like Dm7 G7 Cmaj7").value_parser(value_parser!(ChordSymbol)))
+}
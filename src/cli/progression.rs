@@ -0,0 +1,51 @@
+use clap::{arg, value_parser, ArgAction, ArgMatches, Command};
+
+use crate::cli::scales::{wav_args, write_wav, ScaleName};
+use crate::harmony::{parse_progression, realize};
+use crate::key::Key;
+use crate::midi;
+use crate::midi::Settings;
+use crate::notation::{key_tonic, SpelledChord};
+use crate::note::Note;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let tonic = *m.get_one::<Note>("KEY").expect("KEY is required");
+    let scale = m.get_one::<ScaleName>("SCALE").expect("SCALE is required").to_scale();
+    let numerals = parse_progression(m.get_one::<String>("NUMERALS").expect("NUMERALS is required"))?;
+
+    let tonics: Vec<Note> = if m.get_flag("all-keys") {
+        (0..12).map(|i| tonic.transpose(i)).collect()
+    } else {
+        vec![tonic]
+    };
+    for &tonic in &tonics {
+        let key = Key::new(tonic, scale);
+        let chords: Vec<String> = numerals
+            .iter()
+            .map(|n| SpelledChord::new(&realize(&key, n), Some(&key)).to_string())
+            .collect();
+        if m.get_flag("all-keys") {
+            println!("{:<4}{}", key_tonic(&key).to_string(), chords.join(" "));
+        } else {
+            println!("{}", chords.join(" "));
+        }
+    }
+
+    let key = Key::new(tonic, scale);
+    let symbols: Vec<_> = numerals.iter().map(|n| realize(&key, n)).collect();
+    let settings = Settings::default();
+    write_wav(m, &midi::progression(&symbols, &settings), &settings, tonic)
+}
+
+pub fn progression_subcommand() -> Command {
+    Command::new("progression")
+        .about("Realize a Roman numeral progression in a key")
+        .arg(arg!(<KEY> "the tonic of the key").value_parser(value_parser!(Note)))
+        .arg(arg!(<SCALE> "the scale of the key, like major or dorian").value_parser(value_parser!(ScaleName)))
+        .arg(arg!(<NUMERALS> "the progression, like \"ii7 V7 Imaj7\""))
+        .arg(
+            arg!(--"all-keys" "realize the progression in all twelve keys, starting from KEY")
+                .action(ArgAction::SetTrue),
+        )
+        .args(wav_args())
+}
@@ -0,0 +1,44 @@
+use clap::{arg, value_parser, ArgMatches, Command};
+
+use crate::chord::ChordSymbol;
+use crate::cli::analyze::key_arg;
+use crate::harmony::{reharmonize, substitutions};
+use crate::notation::SpelledChord;
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let key = key_arg(m)?;
+    let chords: Vec<ChordSymbol> = m
+        .get_many::<ChordSymbol>("CHORDS")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let spelled = |chords: &[ChordSymbol]| -> String {
+        let names: Vec<String> = chords
+            .iter()
+            .map(|c| SpelledChord::new(c, Some(&key)).to_string())
+            .collect();
+        names.join(" ")
+    };
+    for chord in &chords {
+        println!("{}", spelled(std::slice::from_ref(chord)));
+        for substitute in substitutions(&key, chord) {
+            println!("  {}: {}", substitute.kind, spelled(&substitute.chords));
+        }
+    }
+    let reharmonized = spelled(&reharmonize(&key, &chords));
+    println!();
+    println!("reharmonized: {}", reharmonized);
+    Ok(())
+}
+
+pub fn reharm_subcommand() -> Command {
+    Command::new("reharm")
+        .about("Suggest substitutions for each chord of a progression and reharmonize it")
+        .arg(
+            arg!(--key <KEY> "the key, as a tonic and a scale name, like C major")
+                .num_args(2)
+                .value_names(["TONIC", "SCALE"])
+                .required(true),
+        )
+        .arg(arg!(<CHORDS> ... "chord symbols, like C Am Dm G7").value_parser(value_parser!(ChordSymbol)))
+}
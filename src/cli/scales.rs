@@ -1,8 +1,22 @@
+use crate::audio::{to_wav, SampleFormat, Synth};
+use crate::cli::fretboard::{fretboard_args, fretboard_option, octave_diagram, print_patterns};
+use crate::cli::keyboard::{keyboard_arg, keyboard_option, terminal_style};
+use crate::fretboard::{caged, three_notes_per_string};
 use crate::interval::Interval;
+use crate::key::Key;
+use crate::midi;
+use crate::midi::{Direction, Format, Part, Settings};
+use crate::notation::{
+    spell_scale, to_abc, to_lilypond, to_musicxml, to_staff, written_tonic, Clef, Labels, Score, SpelledNote,
+};
 use crate::note::Note;
+use crate::pitch::Pitch;
 use crate::scales;
-use clap::arg;
-use clap::{ArgMatches, Command, ValueEnum};
+use crate::scales::{diatonic, harmonic_minor, melodic_minor, Scale};
+use crate::tuning::Tuning;
+use anyhow::anyhow;
+use clap::{arg, value_parser};
+use clap::{Arg, ArgAction, ArgMatches, Command, ValueEnum};
 use inquire::Select;
 
 #[derive(Clone, clap::ValueEnum)]
@@ -26,6 +40,18 @@ pub enum ScaleName {
     PhrygianDominant,
     LydianAug2,
     SuperLocrian,
+
+    // Melodic minor modes
+    MelodicMinor,
+    #[value(alias = "dorian-b2")]
+    DorianFlat2,
+    LydianAugmented,
+    LydianDominant,
+    #[value(alias = "mixolydian-b6")]
+    MixolydianFlat6,
+    #[value(alias = "locrian-nat2")]
+    LocrianNatural2,
+    Altered,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -41,25 +67,37 @@ impl std::str::FromStr for ScaleName {
 }
 
 impl ScaleName {
-    pub fn to_intervals(&self) -> Vec<Interval> {
+    pub fn to_scale(&self) -> Scale {
         match self {
-            ScaleName::Major => scales::diatonic::Mode::Ionian.intervals().to_vec(),
-            ScaleName::Dorian => scales::diatonic::Mode::Dorian.intervals().to_vec(),
-            ScaleName::Phrygian => scales::diatonic::Mode::Phrygian.intervals().to_vec(),
-            ScaleName::Lydian => scales::diatonic::Mode::Lydian.intervals().to_vec(),
-            ScaleName::Mixolydian => scales::diatonic::Mode::Mixolydian.intervals().to_vec(),
-            ScaleName::Minor => scales::diatonic::Mode::Aeolian.intervals().to_vec(),
-            ScaleName::Locrian => scales::diatonic::Mode::Locrian.intervals().to_vec(),
-
-            ScaleName::HarmonicMinor => scales::harmonic_minor::Mode::HarmonicMinor.intervals().to_vec(),
-            ScaleName::LocrianMaj6 => scales::harmonic_minor::Mode::LocrianMaj6.intervals().to_vec(),
-            ScaleName::IonianAug5 => scales::harmonic_minor::Mode::IonianAug5.intervals().to_vec(),
-            ScaleName::DorianLydian => scales::harmonic_minor::Mode::DorianLydian.intervals().to_vec(),
-            ScaleName::PhrygianDominant => scales::harmonic_minor::Mode::PhrygianDominant.intervals().to_vec(),
-            ScaleName::LydianAug2 => scales::harmonic_minor::Mode::LydianAug2.intervals().to_vec(),
-            ScaleName::SuperLocrian => scales::harmonic_minor::Mode::SuperLocrian.intervals().to_vec(),
+            ScaleName::Major => Scale::Diatonic(diatonic::Mode::Ionian),
+            ScaleName::Dorian => Scale::Diatonic(diatonic::Mode::Dorian),
+            ScaleName::Phrygian => Scale::Diatonic(diatonic::Mode::Phrygian),
+            ScaleName::Lydian => Scale::Diatonic(diatonic::Mode::Lydian),
+            ScaleName::Mixolydian => Scale::Diatonic(diatonic::Mode::Mixolydian),
+            ScaleName::Minor => Scale::Diatonic(diatonic::Mode::Aeolian),
+            ScaleName::Locrian => Scale::Diatonic(diatonic::Mode::Locrian),
+
+            ScaleName::HarmonicMinor => Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor),
+            ScaleName::LocrianMaj6 => Scale::HarmonicMinor(harmonic_minor::Mode::LocrianMaj6),
+            ScaleName::IonianAug5 => Scale::HarmonicMinor(harmonic_minor::Mode::IonianAug5),
+            ScaleName::DorianLydian => Scale::HarmonicMinor(harmonic_minor::Mode::DorianLydian),
+            ScaleName::PhrygianDominant => Scale::HarmonicMinor(harmonic_minor::Mode::PhrygianDominant),
+            ScaleName::LydianAug2 => Scale::HarmonicMinor(harmonic_minor::Mode::LydianAug2),
+            ScaleName::SuperLocrian => Scale::HarmonicMinor(harmonic_minor::Mode::SuperLocrian),
+
+            ScaleName::MelodicMinor => Scale::MelodicMinor(melodic_minor::Mode::MelodicMinor),
+            ScaleName::DorianFlat2 => Scale::MelodicMinor(melodic_minor::Mode::DorianFlat2),
+            ScaleName::LydianAugmented => Scale::MelodicMinor(melodic_minor::Mode::LydianAugmented),
+            ScaleName::LydianDominant => Scale::MelodicMinor(melodic_minor::Mode::LydianDominant),
+            ScaleName::MixolydianFlat6 => Scale::MelodicMinor(melodic_minor::Mode::MixolydianFlat6),
+            ScaleName::LocrianNatural2 => Scale::MelodicMinor(melodic_minor::Mode::LocrianNatural2),
+            ScaleName::Altered => Scale::MelodicMinor(melodic_minor::Mode::Altered),
         }
     }
+
+    pub fn to_intervals(&self) -> Vec<Interval> {
+        self.to_scale().intervals().to_vec()
+    }
 }
 
 pub fn handle_interactive() -> anyhow::Result<()> {
@@ -77,7 +115,7 @@ pub fn handle_interactive() -> anyhow::Result<()> {
 }
 
 pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
-    let key = match m.get_one::<Note>("KEY") {
+    let written = match m.get_one::<SpelledNote>("KEY") {
         Some(k) => *k,
         None => {
             let notes = vec!["C", "Db", "D", "Eb", "E", "F", "Gb", "G", "Ab", "A", "Bb", "B"];
@@ -94,13 +132,176 @@ pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
             Select::new("Select a scale:", options).prompt()?.parse()?
         }
     };
-    println!("{:?}", scales::generate_scale(key, &scale.to_intervals()));
+    let key = written.note();
+    let tonic = written_tonic(&Key::new(key, scale.to_scale()), written);
+    let mut score = Score::scale_on(tonic, scale.to_scale(), 4, Direction::Both);
+    if let Some(labels) = labels_option(m) {
+        score.label(labels);
+    }
+    match (m.get_one::<String>("format").map(String::as_str), fretboard_option(m)) {
+        (Some("lilypond"), _) => print!("{}", to_lilypond(&score)),
+        (Some("abc"), _) => print!("{}", to_abc(&score)),
+        _ if m.contains_id("staff") => print!("{}", staff_option(m, &score).expect("staff is present")),
+        _ if m.contains_id("keyboard") => {
+            let key = Key::new(key, scale.to_scale());
+            let intervals = key.scale.intervals();
+            let marks: Vec<_> = spell_scale(tonic, key.scale)
+                .iter()
+                .enumerate()
+                .map(|(i, n)| {
+                    let label = match labels_option(m) {
+                        Some(Labels::Degrees) => (i + 1).to_string(),
+                        Some(Labels::Intervals) => intervals[i].abbreviation().to_string(),
+                        None => n.to_string(),
+                    };
+                    (n.note(), label)
+                })
+                .collect();
+            print!("{}", keyboard_option(m, &marks).expect("keyboard is present"));
+        }
+        (_, Some(fretboard)) if m.contains_id("system") => {
+            let key = Key::new(key, scale.to_scale());
+            let patterns = match m.get_one::<String>("system").map(String::as_str) {
+                Some("caged") => caged(&fretboard, &key)
+                    .ok_or_else(|| anyhow!("CAGED positions need a guitar in standard tuning"))?,
+                _ => three_notes_per_string(&fretboard, &key),
+            };
+            print_patterns(&fretboard, &key, &patterns, labels_option(m), m.get_flag("tab"));
+        }
+        (_, Some(fretboard)) => {
+            let labels: Vec<_> = spell_scale(tonic, scale.to_scale())
+                .iter()
+                .map(|n| (n.note(), n.to_string()))
+                .collect();
+            print!("{}", octave_diagram(&fretboard, &labels));
+        }
+        _ => println!("{:?}", scales::generate_scale(key, &scale.to_intervals())),
+    }
+
+    let settings = Settings {
+        tempo: *m.get_one::<u32>("tempo").expect("tempo has a default"),
+        ..Settings::default()
+    };
+    let part = midi::scale(Pitch::new(key, 4), &scale.to_intervals(), Direction::Both, &settings);
+    if let Some(path) = m.get_one::<String>("midi") {
+        std::fs::write(
+            path,
+            midi::to_smf(std::slice::from_ref(&part), &settings, Format::SingleTrack).to_bytes(),
+        )?;
+    }
+    write_wav(m, &[part], &settings, key)?;
+    if let Some(path) = m.get_one::<String>("musicxml") {
+        std::fs::write(path, to_musicxml(&score))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn format_arg() -> Arg {
+    arg!(--format <FORMAT> "how to print the output: as text, LilyPond source or an ABC tune")
+        .value_parser(["text", "lilypond", "abc"])
+        .default_value("text")
+}
+
+pub(crate) fn labels_arg() -> Arg {
+    arg!(--labels <LABELS> "what to label the notes with in LilyPond, ABC and MusicXML output, positions and keyboards")
+        .value_parser(["degrees", "intervals", "none"])
+}
+
+pub(crate) fn labels_option(m: &ArgMatches) -> Option<Labels> {
+    match m.get_one::<String>("labels").map(String::as_str) {
+        Some("degrees") => Some(Labels::Degrees),
+        Some("intervals") => Some(Labels::Intervals),
+        _ => None,
+    }
+}
+
+pub(crate) fn staff_arg() -> Arg {
+    arg!(--staff [CLEF] "write the notes on a treble, bass or grand staff")
+        .value_parser(value_parser!(Clef))
+        .num_args(0..=1)
+        .default_missing_value("treble")
+        .conflicts_with_all(["fretboard", "keyboard"])
+}
+
+/// The score written on the staff given with `--staff`, if any.
+pub(crate) fn staff_option(m: &ArgMatches, score: &Score) -> Option<String> {
+    let clef = *m.get_one::<Clef>("staff")?;
+    Some(to_staff(score, clef, terminal_style().unicode))
+}
+
+pub(crate) fn wav_args() -> [Arg; 5] {
+    [
+        arg!(--wav <FILE> "also play the output with the built-in synthesizer and write it to a WAV file"),
+        arg!(--waveform <WAVEFORM> "the waveform of the synthesizer")
+            .value_parser(["sine", "saw", "square"])
+            .default_value("sine"),
+        arg!(--tuning <TEMPERAMENT> "the tuning system of the WAV file, built on the tonic or root")
+            .value_parser(["equal", "just", "pythagorean", "meantone"])
+            .default_value("equal"),
+        arg!(--reference <HZ> "the frequency of A4 in the WAV file")
+            .value_parser(value_parser!(f64))
+            .default_value("440"),
+        arg!(--float "write 32-bit float samples to the WAV file instead of 16-bit integers")
+            .action(ArgAction::SetTrue),
+    ]
+}
+
+/// Render the parts to the file given with `--wav`, if any, tuning them from `tonic`.
+pub(crate) fn write_wav(m: &ArgMatches, parts: &[Part], settings: &Settings, tonic: Note) -> anyhow::Result<()> {
+    let Some(path) = m.get_one::<String>("wav") else {
+        return Ok(());
+    };
+    let reference = *m.get_one::<f64>("reference").expect("reference has a default");
+    if !(reference > 0.0 && reference.is_finite()) {
+        return Err(anyhow!("Invalid reference pitch {}", reference));
+    }
+    let synth = Synth {
+        waveform: m
+            .get_one::<String>("waveform")
+            .expect("waveform has a default")
+            .parse()?,
+        tuning: Tuning::new(
+            m.get_one::<String>("tuning").expect("tuning has a default").parse()?,
+            tonic,
+            reference,
+        ),
+        ..Synth::default()
+    };
+    let format = if m.get_flag("float") {
+        SampleFormat::Float32
+    } else {
+        SampleFormat::Pcm16
+    };
+    std::fs::write(path, to_wav(&synth.render(parts, settings), synth.sample_rate, format))?;
     Ok(())
 }
 
 pub fn scale_subcommand() -> Command {
     Command::new("scale")
         .about("Generate a scale")
-        .arg(arg!([KEY] "the first note of the scale").value_parser(clap::value_parser!(Note)))
+        .arg(arg!([KEY] "the first note of the scale").value_parser(clap::value_parser!(SpelledNote)))
         .arg(arg!([NAME] "the name of the scale, like major or minor").value_parser(clap::value_parser!(ScaleName)))
+        .arg(arg!(--midi <FILE> "also write the scale up and down from the fourth octave to a MIDI file"))
+        .arg(arg!(--musicxml <FILE> "also write the scale up and down from the fourth octave to a MusicXML file"))
+        .arg(
+            arg!(--tempo <BPM> "the tempo of the MIDI and WAV files, in quarter notes per minute")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("120"),
+        )
+        .arg(format_arg())
+        .arg(labels_arg())
+        .args(fretboard_args())
+        .arg(
+            arg!(--system <SYSTEM> "play the scale in CAGED box positions or 3 notes per string on the --fretboard")
+                .value_parser(["caged", "3nps"])
+                .requires("fretboard"),
+        )
+        .arg(
+            arg!(--tab "write the positions as tab going up and down instead of diagrams")
+                .action(ArgAction::SetTrue)
+                .requires("system"),
+        )
+        .arg(keyboard_arg())
+        .arg(staff_arg())
+        .args(wav_args())
 }
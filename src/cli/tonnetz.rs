@@ -0,0 +1,36 @@
+use clap::{arg, value_parser, ArgMatches, Command};
+
+use crate::harmony::neo_riemannian::{shortest_path, tonnetz, Transformation, Triad};
+
+pub fn handle(m: &ArgMatches) -> anyhow::Result<()> {
+    let start = *m.get_one::<Triad>("TRIAD").expect("TRIAD is required");
+    let chain = match (m.get_one::<String>("apply"), m.get_one::<Triad>("to")) {
+        (Some(chain), _) => Transformation::parse_chain(chain)?,
+        (None, Some(to)) => {
+            shortest_path(start, *to, &[Transformation::P, Transformation::L, Transformation::R]).unwrap_or_default()
+        }
+        (None, None) => vec![],
+    };
+    let mut triad = start;
+    let mut steps = vec![triad.to_string()];
+    for transformation in chain {
+        triad = triad.apply(transformation);
+        steps.push(format!("-{}-> {}", transformation, triad));
+    }
+    println!("{}", steps.join(" "));
+    println!();
+    println!("{}", tonnetz(triad, 1));
+    Ok(())
+}
+
+pub fn tonnetz_subcommand() -> Command {
+    Command::new("tonnetz")
+        .about("Apply neo-Riemannian transformations to a triad and show it on the Tonnetz")
+        .arg(arg!(<TRIAD> "a major or minor triad, like C or Ebm").value_parser(value_parser!(Triad)))
+        .arg(arg!(--apply <CHAIN> "transformations to apply from left to right, like PLR or N S"))
+        .arg(
+            arg!(--to <TRIAD> "find the shortest P/L/R chain to another triad")
+                .value_parser(value_parser!(Triad))
+                .conflicts_with("apply"),
+        )
+}
@@ -0,0 +1,277 @@
+use std::ops::RangeInclusive;
+
+use crate::fretboard::fingering::Fingering;
+use crate::fretboard::instrument::{Fretboard, Position};
+use crate::note::Note;
+
+/// Draw the neck from string 1 at the top, with a label at each marked position, like
+///
+/// ```text
+///          1    2    3
+/// E  E  ||-F--|----|-G--|
+/// B  B  ||-C--|----|-D--|
+/// ```
+///
+/// When `frets` starts at the nut or the capo, that fret is drawn as the open strings left of `||`. Labels are cut
+/// to three characters.
+pub fn diagram(fretboard: &Fretboard, marks: &[(Position, String)], frets: RangeInclusive<u8>) -> String {
+    let open = *frets.start() == fretboard.capo;
+    let first = if open { *frets.start() + 1 } else { *frets.start() };
+    let label = |position: Position| {
+        marks
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, label)| label.chars().take(3).collect::<String>())
+    };
+
+    let prefix = if open { 8 } else { 4 };
+    let mut header = " ".repeat(prefix);
+    for fret in first..=*frets.end() {
+        header += &format!("{:^4} ", fret);
+    }
+    let mut lines = vec![header.trim_end().to_string()];
+    for string in 1..=fretboard.strings.len() {
+        let name = fretboard.string(string).map(|p| p.note.to_string()).unwrap_or_default();
+        let mut line = format!("{:<3}", name);
+        if open {
+            let position = Position::new(string, *frets.start());
+            line += &format!("{:<3}||", label(position).unwrap_or_default());
+        } else {
+            line += "|";
+        }
+        for fret in first..=*frets.end() {
+            match label(Position::new(string, fret)) {
+                Some(label) => line += &format!("-{:-<3}|", label),
+                None => line += "----|",
+            }
+        }
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Draw every position of the notes within `frets`, each labelled with the text given for its note.
+pub fn note_diagram(fretboard: &Fretboard, labels: &[(Note, String)], frets: RangeInclusive<u8>) -> String {
+    let notes: Vec<Note> = labels.iter().map(|(note, _)| *note).collect();
+    let marks: Vec<(Position, String)> = fretboard
+        .find(&notes, frets.clone())
+        .into_iter()
+        .filter_map(|position| {
+            let pitch = fretboard.pitch_at(position)?;
+            let (_, label) = labels.iter().find(|(note, _)| *note == pitch.note)?;
+            Some((position, label.clone()))
+        })
+        .collect();
+    diagram(fretboard, &marks, frets)
+}
+
+/// Draw a chord box with the lowest string on the left, like
+///
+/// ```text
+/// x     o   o
+/// ===========
+/// | | | | 1 |
+/// | | 2 | | |
+/// | 3 | | | |
+/// | | | | | |
+/// ```
+///
+/// Muted strings are marked `x` and open strings `o` above the nut, or above the capo. Shapes that don't fit in the
+/// first four frets start at their lowest fret, which is written next to it, and a barre joins its strings with `-`.
+pub fn chord_box(fretboard: &Fretboard, fingering: &Fingering) -> String {
+    let capo = fretboard.capo;
+    let (lowest, highest) = fingering.span(capo).unwrap_or((capo + 1, capo + 1));
+    let first = if highest <= capo + 4 { capo + 1 } else { lowest };
+    let last = highest.max(first + 3);
+
+    let marks: Vec<&str> = fingering
+        .frets
+        .iter()
+        .map(|fret| match fret {
+            None => "x",
+            Some(fret) if *fret == capo => "o",
+            _ => " ",
+        })
+        .collect();
+    let width = 2 * fingering.frets.len() - 1;
+    let top = if first == capo + 1 { "=" } else { "-" };
+    let mut lines = vec![marks.join(" ").trim_end().to_string(), top.repeat(width)];
+
+    let barred = |string: usize| {
+        let strings: Vec<usize> = (0..fingering.frets.len())
+            .filter(|&s| fingering.barre.is_some() && fingering.frets[s] == fingering.barre)
+            .collect();
+        strings.first().is_some_and(|&s| s <= string) && strings.last().is_some_and(|&s| string < s)
+    };
+    for fret in first..=last {
+        let mut line = String::new();
+        for (string, played) in fingering.frets.iter().enumerate() {
+            match (played, fingering.fingers[string]) {
+                (Some(played), Some(finger)) if *played == fret => line += &finger.to_string(),
+                _ => line += "|",
+            }
+            if string + 1 < fingering.frets.len() {
+                line += if fingering.barre == Some(fret) && barred(string) {
+                    "-"
+                } else {
+                    " "
+                };
+            }
+        }
+        if fret == first && first != capo + 1 {
+            line += &format!(" {}fr", first);
+        }
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Write notes played one after the other as tab, with string 1 at the top and the finger for each note underneath,
+/// `0` for open strings.
+pub fn tab(fretboard: &Fretboard, notes: &[(Position, Option<u8>)]) -> String {
+    let width = notes.iter().map(|(p, _)| p.fret.to_string().len()).max().unwrap_or(1);
+    let mut lines = vec![];
+    for string in 1..=fretboard.strings.len() {
+        let name = fretboard.string(string).map(|p| p.note.to_string()).unwrap_or_default();
+        let mut line = format!("{:<3}|", name);
+        for (position, _) in notes {
+            if position.string == string {
+                line += &format!("-{:-<width$}", position.fret);
+            } else {
+                line += &"-".repeat(width + 1);
+            }
+        }
+        lines.push(line + "-|");
+    }
+    let mut fingers = "    ".to_string();
+    for (_, finger) in notes {
+        fingers += &format!(" {:<width$}", finger.unwrap_or(0));
+    }
+    lines.push(fingers.trim_end().to_string());
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chord::symbol::parse_suffix;
+    use crate::fretboard::fingering::{fingerings, Constraints};
+    use crate::fretboard::instrument::Preset;
+    use crate::note::Note::*;
+
+    use super::*;
+
+    fn c_major() -> Vec<(Note, String)> {
+        [C, D, E, F, G, A, B].iter().map(|n| (*n, n.to_string())).collect()
+    }
+
+    #[test]
+    fn open_position() {
+        let expected = "         1    2    3
+E  E  ||-F--|----|-G--|
+B  B  ||-C--|----|-D--|
+G  G  ||----|-A--|----|
+D  D  ||----|-E--|-F--|
+A  A  ||----|-B--|-C--|
+E  E  ||-F--|----|-G--|
+";
+        assert_eq!(note_diagram(&Preset::Guitar.fretboard(), &c_major(), 0..=3), expected);
+    }
+
+    #[test]
+    fn up_the_neck() {
+        let expected = "     7    8    9
+G  |-D--|----|-E--|
+D  |-A--|----|-B--|
+A  |-E--|-F--|----|
+E  |-B--|-C--|----|
+";
+        assert_eq!(note_diagram(&Preset::Bass.fretboard(), &c_major(), 7..=9), expected);
+    }
+
+    #[test]
+    fn capo_and_marks() {
+        let fretboard = Preset::Ukulele.fretboard().with_capo(2);
+        let marks = vec![
+            (Position::new(4, 2), "R".to_string()),
+            (Position::new(1, 4), "b13".to_string()),
+        ];
+        let expected = "         3    4
+A     ||----|-b13|
+E     ||----|----|
+C     ||----|----|
+G  R  ||----|----|
+";
+        assert_eq!(diagram(&fretboard, &marks, 2..=4), expected);
+    }
+
+    fn shape(root: Note, suffix: &str, tab: &str) -> Fingering {
+        let chord = parse_suffix(suffix).unwrap();
+        fingerings(&Preset::Guitar.fretboard(), root, &chord, &Constraints::default())
+            .into_iter()
+            .find(|f| f.to_string() == tab)
+            .unwrap()
+    }
+
+    #[test]
+    fn open_chord_box() {
+        let expected = "x     o   o
+===========
+| | | | 1 |
+| | 2 | | |
+| 3 | | | |
+| | | | | |
+";
+        assert_eq!(
+            chord_box(&Preset::Guitar.fretboard(), &shape(C, "", "x32010")),
+            expected
+        );
+    }
+
+    #[test]
+    fn barre_chord_box() {
+        let expected = "
+===========
+1-|-|-|-1-1
+| | | 2 | |
+| 3 4 | | |
+| | | | | |
+";
+        assert_eq!(
+            chord_box(&Preset::Guitar.fretboard(), &shape(F, "", "133211")),
+            expected
+        );
+    }
+
+    #[test]
+    fn chord_box_up_the_neck() {
+        let expected = "x
+-----------
+| 1-|-|-|-1 5fr
+| | | | | |
+| | 2 3 4 |
+| | | | | |
+";
+        assert_eq!(
+            chord_box(&Preset::Guitar.fretboard(), &shape(D, "", "x57775")),
+            expected
+        );
+    }
+
+    #[test]
+    fn tab_with_fingers() {
+        let notes = vec![
+            (Position::new(4, 0), None),
+            (Position::new(4, 2), Some(2)),
+            (Position::new(3, 0), None),
+            (Position::new(3, 2), Some(2)),
+            (Position::new(2, 12), Some(4)),
+        ];
+        let expected = "A  |----------------|
+E  |-------------12-|
+C  |-------0--2-----|
+G  |-0--2-----------|
+     0  2  0  2  4
+";
+        assert_eq!(tab(&Preset::Ukulele.fretboard(), &notes), expected);
+    }
+}
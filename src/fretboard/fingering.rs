@@ -0,0 +1,337 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::fretboard::instrument::Fretboard;
+use crate::note::Note;
+use crate::pitch::Pitch;
+
+/// Limits on what the fretting hand can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Constraints {
+    /// The most frets the hand covers, counting both ends, so 4 allows frets 1 to 4.
+    pub stretch: u8,
+    pub fingers: u8,
+    pub open_strings: bool,
+    pub muted_strings: bool,
+    /// Whether the lowest sounding string has to play the root.
+    pub root_in_bass: bool,
+}
+
+impl Default for Constraints {
+    fn default() -> Constraints {
+        Constraints {
+            stretch: 4,
+            fingers: 4,
+            open_strings: true,
+            muted_strings: true,
+            root_in_bass: true,
+        }
+    }
+}
+
+/// A way to play a chord, string by string from the lowest string like the tunings in [`Fretboard::strings`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fingering {
+    /// The fret of each string, `None` when it is muted. Open strings are at the fret of the capo.
+    pub frets: Vec<Option<u8>>,
+    /// The finger on each string, from 1 for the index finger, `None` when it is open or muted.
+    pub fingers: Vec<Option<u8>>,
+    /// The fret the index finger lays across several strings, if it has to.
+    pub barre: Option<u8>,
+}
+
+impl Fingering {
+    /// The lowest and highest fretted frets, if any string is fretted.
+    pub fn span(&self, capo: u8) -> Option<(u8, u8)> {
+        let fretted = self.frets.iter().flatten().filter(|&&f| f > capo);
+        Some((*fretted.clone().min()?, *fretted.max()?))
+    }
+}
+
+/// Tab notation, like `x32010`, with the frets separated by spaces once any of them reaches 10.
+impl fmt::Display for Fingering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frets: Vec<String> = self
+            .frets
+            .iter()
+            .map(|fret| fret.map_or("x".to_string(), |fret| fret.to_string()))
+            .collect();
+        let separator = if self.frets.iter().flatten().any(|&f| f >= 10) {
+            " "
+        } else {
+            ""
+        };
+        write!(f, "{}", frets.join(separator))
+    }
+}
+
+/// The playable fingerings of a chord, easiest first. Every note of the chord sounds, except that the perfect fifth
+/// may be left out of chords with four or more notes.
+pub fn fingerings(fretboard: &Fretboard, root: Note, chord: &Chord, constraints: &Constraints) -> Vec<Fingering> {
+    let semitones = chord.semitones();
+    let tones: Vec<Note> = semitones.iter().map(|s| root.transpose(*s as isize)).collect();
+    let optional = (semitones.len() >= 4 && semitones.contains(&7)).then(|| root.transpose(7));
+    let stretch = constraints.stretch.max(1);
+
+    let mut shapes = BTreeSet::new();
+    for low in fretboard.capo + 1..=fretboard.frets {
+        let high = fretboard.frets.min(low + stretch - 1);
+        let choices: Vec<Vec<Option<u8>>> = fretboard
+            .strings
+            .iter()
+            .map(|open| {
+                let mut choices = vec![];
+                if constraints.muted_strings {
+                    choices.push(None);
+                }
+                let open = open.transpose(fretboard.capo as i32);
+                if constraints.open_strings && tones.contains(&open.note) {
+                    choices.push(Some(fretboard.capo));
+                }
+                choices.extend(
+                    (low..=high)
+                        .filter(|&fret| tones.contains(&open.note.transpose((fret - fretboard.capo) as isize)))
+                        .map(Some),
+                );
+                choices
+            })
+            .collect();
+        let mut frets = vec![None; choices.len()];
+        collect(&choices, 0, &mut frets, &mut shapes);
+    }
+
+    let mut ranked: Vec<(u32, usize, Fingering)> = shapes
+        .into_iter()
+        .filter_map(|frets| {
+            let sounding: Vec<Pitch> = fretboard
+                .strings
+                .iter()
+                .zip(&frets)
+                .filter_map(|(open, fret)| fret.map(|f| open.transpose(f as i32)))
+                .collect();
+            let notes: Vec<Note> = sounding.iter().map(|p| p.note).collect();
+            let complete = tones.iter().all(|t| notes.contains(t) || Some(*t) == optional);
+            // Re-entrant tunings like the ukulele's can have the lowest note on an inner string.
+            let bass_ok = !constraints.root_in_bass || sounding.iter().min().is_some_and(|p| p.note == root);
+            if !complete || !bass_ok {
+                return None;
+            }
+            let fingering = finger(&frets, fretboard.capo, constraints)?;
+            let mut score = difficulty(&fingering, fretboard.capo);
+            if optional.is_some_and(|fifth| !notes.contains(&fifth)) {
+                score += 1;
+            }
+            Some((score, sounding.len(), fingering))
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.frets.cmp(&b.2.frets)));
+    ranked.into_iter().map(|(_, _, fingering)| fingering).collect()
+}
+
+/// Every combination of a choice per string, from `string` on.
+fn collect(
+    choices: &[Vec<Option<u8>>],
+    string: usize,
+    frets: &mut Vec<Option<u8>>,
+    shapes: &mut BTreeSet<Vec<Option<u8>>>,
+) {
+    if string == choices.len() {
+        shapes.insert(frets.clone());
+        return;
+    }
+    for choice in &choices[string] {
+        frets[string] = *choice;
+        collect(choices, string + 1, frets, shapes);
+    }
+}
+
+/// Put fingers on the fretted strings, from the lowest fret and the lowest string, barring the lowest fret with the
+/// index finger when there are more notes than fingers. Returns `None` when the hand can't manage.
+fn finger(frets: &[Option<u8>], capo: u8, constraints: &Constraints) -> Option<Fingering> {
+    let mut fretted: Vec<(u8, usize)> = frets
+        .iter()
+        .enumerate()
+        .filter_map(|(string, fret)| fret.filter(|&f| f > capo).map(|f| (f, string)))
+        .collect();
+    fretted.sort();
+    let mut fingering = Fingering {
+        frets: frets.to_vec(),
+        fingers: vec![None; frets.len()],
+        barre: None,
+    };
+    let Some(&(lowest, _)) = fretted.first() else {
+        return Some(fingering);
+    };
+    let highest = fretted.iter().map(|(fret, _)| *fret).max().unwrap_or(lowest);
+    if highest - lowest >= constraints.stretch {
+        return None;
+    }
+
+    let mut finger = 1;
+    if fretted.len() > constraints.fingers as usize {
+        let barred: Vec<usize> = fretted.iter().filter(|(f, _)| *f == lowest).map(|(_, s)| *s).collect();
+        let (first, last) = (barred[0], barred[barred.len() - 1]);
+        let covered = frets[first..=last]
+            .iter()
+            .all(|f| f.is_some_and(|f| f >= lowest && f > capo));
+        if barred.len() < 2 || !covered {
+            return None;
+        }
+        for string in barred {
+            fingering.fingers[string] = Some(1);
+        }
+        fingering.barre = Some(lowest);
+        fretted.retain(|(f, _)| *f > lowest);
+        finger = 2;
+    }
+    if fretted.len() + finger as usize - 1 > constraints.fingers as usize {
+        return None;
+    }
+    for (_, string) in fretted {
+        fingering.fingers[string] = Some(finger);
+        finger += 1;
+    }
+    Some(fingering)
+}
+
+/// A rough measure of how hard a fingering is: wide stretches, many fingers, barres, muted strings (worse between
+/// sounding strings) and positions above the third fret all add to it.
+fn difficulty(fingering: &Fingering, capo: u8) -> u32 {
+    let mut score = 0;
+    if let Some((lowest, highest)) = fingering.span(capo) {
+        score += 2 * (highest - lowest + 1) as u32 + (lowest - capo).saturating_sub(3) as u32;
+    }
+    score += fingering.fingers.iter().flatten().collect::<BTreeSet<_>>().len() as u32;
+    if fingering.barre.is_some() {
+        score += 2;
+    }
+    let first = fingering.frets.iter().position(Option::is_some).unwrap_or(0);
+    let last = fingering.frets.iter().rposition(Option::is_some).unwrap_or(0);
+    for (string, fret) in fingering.frets.iter().enumerate() {
+        if fret.is_none() {
+            score += if string > first && string < last { 5 } else { 3 };
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::symbol::parse_suffix;
+    use crate::fretboard::instrument::Preset;
+    use crate::note::Note::*;
+
+    use super::*;
+
+    fn best(preset: Preset, root: Note, suffix: &str, constraints: &Constraints) -> Vec<String> {
+        let chord = parse_suffix(suffix).unwrap();
+        fingerings(&preset.fretboard(), root, &chord, constraints)
+            .iter()
+            .take(3)
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(C, "", "x32010")]
+    #[case(G, "", "320003")]
+    #[case(D, "", "xx0232")]
+    #[case(A, "m", "x02210")]
+    #[case(E, "m", "022000")]
+    #[case(E, "", "022100")]
+    #[case(F, "", "133211")]
+    #[case(A, "7", "x02020")]
+    #[case(C, "maj7", "x32000")]
+    fn open_chords(#[case] root: Note, #[case] suffix: &str, #[case] expected: &str) {
+        assert_eq!(best(Preset::Guitar, root, suffix, &Constraints::default())[0], expected);
+    }
+
+    #[test]
+    fn ukulele() {
+        assert_eq!(best(Preset::Ukulele, C, "", &Constraints::default())[0], "0003");
+    }
+
+    #[test]
+    fn without_open_strings() {
+        let constraints = Constraints {
+            open_strings: false,
+            ..Constraints::default()
+        };
+        assert_eq!(best(Preset::Guitar, G, "", &constraints)[0], "355433");
+    }
+
+    #[test]
+    fn without_muted_strings() {
+        let constraints = Constraints {
+            muted_strings: false,
+            ..Constraints::default()
+        };
+        for tab in best(Preset::Guitar, C, "", &constraints) {
+            assert!(!tab.contains('x'), "{}", tab);
+        }
+    }
+
+    #[test]
+    fn root_anywhere() {
+        let constraints = Constraints {
+            root_in_bass: false,
+            ..Constraints::default()
+        };
+        assert_eq!(best(Preset::Guitar, C, "", &constraints)[0], "032010");
+    }
+
+    #[test]
+    fn barre() {
+        let chord = parse_suffix("m").unwrap();
+        let fingering = fingerings(&Preset::Guitar.fretboard(), B, &chord, &Constraints::default())
+            .into_iter()
+            .find(|f| f.to_string() == "x24432")
+            .unwrap();
+        assert_eq!(fingering.barre, Some(2));
+        assert_eq!(
+            fingering.fingers,
+            vec![None, Some(1), Some(3), Some(4), Some(2), Some(1)]
+        );
+    }
+
+    #[test]
+    fn fingers_without_barre() {
+        let chord = parse_suffix("").unwrap();
+        let fingering = &fingerings(&Preset::Guitar.fretboard(), C, &chord, &Constraints::default())[0];
+        assert_eq!(fingering.barre, None);
+        assert_eq!(fingering.fingers, vec![None, Some(3), Some(2), None, Some(1), None]);
+    }
+
+    #[test]
+    fn stretch() {
+        let constraints = Constraints {
+            stretch: 2,
+            ..Constraints::default()
+        };
+        for tab in best(Preset::Guitar, C, "", &constraints) {
+            assert_ne!(tab, "x32010");
+        }
+    }
+
+    #[test]
+    fn capo() {
+        let chord = parse_suffix("").unwrap();
+        let fretboard = Preset::Guitar.fretboard().with_capo(2);
+        let fingering = &fingerings(&fretboard, D, &chord, &Constraints::default())[0];
+        assert_eq!(fingering.to_string(), "x54232");
+        assert_eq!(fingering.span(2), Some((3, 5)));
+    }
+
+    #[test]
+    fn tab_with_high_frets() {
+        let fingering = Fingering {
+            frets: vec![None, Some(10), Some(12), Some(12), Some(12), Some(10)],
+            fingers: vec![None; 6],
+            barre: None,
+        };
+        assert_eq!(fingering.to_string(), "x 10 12 12 12 10");
+    }
+}
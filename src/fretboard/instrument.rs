@@ -0,0 +1,247 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::note::Note;
+use crate::note::Note::*;
+use crate::pitch::Pitch;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid instrument {0}")]
+pub struct PresetParseError(String);
+
+/// A string and fret. Strings are numbered the way players count them, from 1 for the highest sounding string (the
+/// last one in [`Fretboard::strings`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    pub string: usize,
+    pub fret: u8,
+}
+
+impl Position {
+    pub fn new(string: usize, fret: u8) -> Position {
+        Position { string, fret }
+    }
+}
+
+/// A fretted instrument: its open strings, the number of frets on the neck and an optional capo.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fretboard {
+    /// The open strings from the lowest string, as tunings are written (`E A D G B E`).
+    pub strings: Vec<Pitch>,
+    pub frets: u8,
+    /// The fret the capo is on, 0 without a capo.
+    pub capo: u8,
+}
+
+impl Fretboard {
+    pub fn new(strings: Vec<Pitch>, frets: u8) -> Fretboard {
+        Fretboard {
+            strings,
+            frets,
+            capo: 0,
+        }
+    }
+
+    pub fn with_capo(self, capo: u8) -> Fretboard {
+        Fretboard { capo, ..self }
+    }
+
+    /// The open pitch of a string, without the capo.
+    pub fn string(&self, string: usize) -> Option<Pitch> {
+        let index = self.strings.len().checked_sub(string)?;
+        (string > 0).then(|| self.strings[index])
+    }
+
+    /// The pitch at a string and fret. Frets are counted from the nut, so with a capo on the second fret the strings
+    /// ring at fret 2 and frets 0 and 1 can't be played.
+    pub fn pitch_at(&self, position: Position) -> Option<Pitch> {
+        if position.fret < self.capo || position.fret > self.frets {
+            return None;
+        }
+        Some(self.string(position.string)?.transpose(position.fret as i32))
+    }
+
+    /// The frets that can be played, from the capo to the last fret.
+    pub fn playable(&self) -> RangeInclusive<u8> {
+        self.capo..=self.frets
+    }
+
+    /// Every position where one of the notes can be played within `frets`, from string 1 and the lowest fret.
+    pub fn find(&self, notes: &[Note], frets: RangeInclusive<u8>) -> Vec<Position> {
+        (1..=self.strings.len())
+            .flat_map(|string| frets.clone().map(move |fret| Position::new(string, fret)))
+            .filter(|&position| self.pitch_at(position).is_some_and(|p| notes.contains(&p.note)))
+            .collect()
+    }
+
+    /// Every position where exactly this pitch can be played.
+    pub fn find_pitch(&self, pitch: Pitch) -> Vec<Position> {
+        (1..=self.strings.len())
+            .flat_map(|string| self.playable().map(move |fret| Position::new(string, fret)))
+            .filter(|&position| self.pitch_at(position) == Some(pitch))
+            .collect()
+    }
+}
+
+/// Common instruments and tunings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    Guitar,
+    DropD,
+    Dadgad,
+    OpenG,
+    SevenString,
+    Bass,
+    FiveStringBass,
+    /// Re-entrant `G C E A` tuning, with the high G.
+    Ukulele,
+    /// Each pair of strings counts as a single string.
+    Mandolin,
+    /// Open G on a five-string banjo. The short fifth string is treated as a full length string.
+    Banjo,
+}
+
+impl Preset {
+    pub fn array() -> [Preset; 10] {
+        [
+            Preset::Guitar,
+            Preset::DropD,
+            Preset::Dadgad,
+            Preset::OpenG,
+            Preset::SevenString,
+            Preset::Bass,
+            Preset::FiveStringBass,
+            Preset::Ukulele,
+            Preset::Mandolin,
+            Preset::Banjo,
+        ]
+    }
+
+    pub fn fretboard(&self) -> Fretboard {
+        let (strings, frets): (&[(Note, i8)], u8) = match self {
+            Preset::Guitar => (&[(E, 2), (A, 2), (D, 3), (G, 3), (B, 3), (E, 4)], 22),
+            Preset::DropD => (&[(D, 2), (A, 2), (D, 3), (G, 3), (B, 3), (E, 4)], 22),
+            Preset::Dadgad => (&[(D, 2), (A, 2), (D, 3), (G, 3), (A, 3), (D, 4)], 22),
+            Preset::OpenG => (&[(D, 2), (G, 2), (D, 3), (G, 3), (B, 3), (D, 4)], 22),
+            Preset::SevenString => (&[(B, 1), (E, 2), (A, 2), (D, 3), (G, 3), (B, 3), (E, 4)], 24),
+            Preset::Bass => (&[(E, 1), (A, 1), (D, 2), (G, 2)], 20),
+            Preset::FiveStringBass => (&[(B, 0), (E, 1), (A, 1), (D, 2), (G, 2)], 24),
+            Preset::Ukulele => (&[(G, 4), (C, 4), (E, 4), (A, 4)], 15),
+            Preset::Mandolin => (&[(G, 3), (D, 4), (A, 4), (E, 5)], 20),
+            Preset::Banjo => (&[(G, 4), (D, 3), (G, 3), (B, 3), (D, 4)], 22),
+        };
+        Fretboard::new(
+            strings.iter().map(|&(note, octave)| Pitch::new(note, octave)).collect(),
+            frets,
+        )
+    }
+}
+
+impl FromStr for Preset {
+    type Err = PresetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "guitar" => Ok(Preset::Guitar),
+            "drop-d" => Ok(Preset::DropD),
+            "dadgad" => Ok(Preset::Dadgad),
+            "open-g" => Ok(Preset::OpenG),
+            "7-string" | "seven-string" => Ok(Preset::SevenString),
+            "bass" => Ok(Preset::Bass),
+            "5-string-bass" | "bass-5" => Ok(Preset::FiveStringBass),
+            "ukulele" | "uke" => Ok(Preset::Ukulele),
+            "mandolin" => Ok(Preset::Mandolin),
+            "banjo" => Ok(Preset::Banjo),
+            _ => Err(PresetParseError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Preset::Guitar => "guitar",
+            Preset::DropD => "drop-d",
+            Preset::Dadgad => "dadgad",
+            Preset::OpenG => "open-g",
+            Preset::SevenString => "7-string",
+            Preset::Bass => "bass",
+            Preset::FiveStringBass => "5-string-bass",
+            Preset::Ukulele => "ukulele",
+            Preset::Mandolin => "mandolin",
+            Preset::Banjo => "banjo",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Preset::Guitar, "E2 A2 D3 G3 B3 E4")]
+    #[case(Preset::DropD, "D2 A2 D3 G3 B3 E4")]
+    #[case(Preset::Dadgad, "D2 A2 D3 G3 A3 D4")]
+    #[case(Preset::OpenG, "D2 G2 D3 G3 B3 D4")]
+    #[case(Preset::SevenString, "B1 E2 A2 D3 G3 B3 E4")]
+    #[case(Preset::Bass, "E1 A1 D2 G2")]
+    #[case(Preset::FiveStringBass, "B0 E1 A1 D2 G2")]
+    #[case(Preset::Ukulele, "G4 C4 E4 A4")]
+    #[case(Preset::Mandolin, "G3 D4 A4 E5")]
+    #[case(Preset::Banjo, "G4 D3 G3 B3 D4")]
+    fn presets(#[case] preset: Preset, #[case] expected: &str) -> Result<(), PresetParseError> {
+        let strings: Vec<String> = preset.fretboard().strings.iter().map(|p| p.to_string()).collect();
+        assert_eq!(strings.join(" "), expected);
+        assert_eq!(preset.to_string().parse::<Preset>()?, preset);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_preset_error() {
+        assert!("sitar".parse::<Preset>().is_err());
+    }
+
+    #[rstest]
+    #[case(Position::new(6, 0), Some(Pitch::new(E, 2)))]
+    #[case(Position::new(6, 3), Some(Pitch::new(G, 2)))]
+    #[case(Position::new(5, 5), Some(Pitch::new(D, 3)))]
+    #[case(Position::new(2, 1), Some(Pitch::new(C, 4)))]
+    #[case(Position::new(1, 22), Some(Pitch::new(D, 6)))]
+    #[case(Position::new(1, 23), None)]
+    #[case(Position::new(0, 0), None)]
+    #[case(Position::new(7, 0), None)]
+    fn pitch_at(#[case] position: Position, #[case] expected: Option<Pitch>) {
+        assert_eq!(Preset::Guitar.fretboard().pitch_at(position), expected);
+    }
+
+    #[test]
+    fn capo() {
+        let fretboard = Preset::Guitar.fretboard().with_capo(2);
+        assert_eq!(fretboard.pitch_at(Position::new(6, 1)), None);
+        assert_eq!(fretboard.pitch_at(Position::new(6, 2)), Some(Pitch::new(Gb, 2)));
+        assert_eq!(
+            fretboard.find(&[E], 0..=5),
+            vec![Position::new(2, 5), Position::new(4, 2)]
+        );
+    }
+
+    #[test]
+    fn find_notes() {
+        let positions = Preset::Guitar.fretboard().find(&[C], 0..=5);
+        let expected = vec![Position::new(2, 1), Position::new(3, 5), Position::new(5, 3)];
+        assert_eq!(positions, expected);
+    }
+
+    #[rstest]
+    #[case(Preset::Guitar, Pitch::new(E, 4), vec![(1, 0), (2, 5), (3, 9), (4, 14), (5, 19)])]
+    #[case(Preset::Ukulele, Pitch::new(G, 4), vec![(2, 3), (3, 7), (4, 0)])]
+    #[case(Preset::Bass, Pitch::new(C, 1), vec![])]
+    fn find_pitch(#[case] preset: Preset, #[case] pitch: Pitch, #[case] expected: Vec<(usize, u8)>) {
+        let expected: Vec<Position> = expected.into_iter().map(|(s, f)| Position::new(s, f)).collect();
+        assert_eq!(preset.fretboard().find_pitch(pitch), expected);
+    }
+}
@@ -0,0 +1,9 @@
+pub use diagram::{chord_box, diagram, note_diagram, tab};
+pub use fingering::{fingerings, Constraints, Fingering};
+pub use instrument::{Fretboard, Position, Preset, PresetParseError};
+pub use positions::{caged, three_notes_per_string, Pattern, PatternNote};
+
+pub mod diagram;
+pub mod fingering;
+pub mod instrument;
+pub mod positions;
@@ -0,0 +1,342 @@
+use crate::fretboard::instrument::{Fretboard, Position};
+use crate::key::Key;
+use crate::pitch::Pitch;
+
+/// The intervals between the top six strings of a guitar in standard tuning, from the lowest.
+const STANDARD: [i32; 5] = [5, 5, 5, 4, 5];
+
+/// The CAGED shapes in the order they climb the neck, with the fret their chord shape starts on, counted from the
+/// root on the sixth string.
+const CAGED: [(&str, u8); 5] = [("E", 0), ("D", 2), ("C", 4), ("A", 7), ("G", 9)];
+
+/// A note of a scale pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatternNote {
+    pub position: Position,
+    /// The scale degree, from 1 for the tonic.
+    pub degree: usize,
+    /// The finger to fret it with, from 1 for the index finger, `None` for an open string.
+    pub finger: Option<u8>,
+}
+
+/// A way to play a scale in one position on the neck, from the lowest string up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: String,
+    pub notes: Vec<PatternNote>,
+}
+
+impl Pattern {
+    /// The lowest and highest frets of the pattern.
+    pub fn frets(&self) -> (u8, u8) {
+        let frets = self.notes.iter().map(|n| n.position.fret);
+        (frets.clone().min().unwrap_or(0), frets.max().unwrap_or(0))
+    }
+}
+
+/// The seven 3-notes-per-string patterns, each starting on the next degree of the scale on the lowest string.
+/// Patterns that don't fit on the neck are left out.
+pub fn three_notes_per_string(fretboard: &Fretboard, key: &Key) -> Vec<Pattern> {
+    let Some(&lowest) = fretboard.strings.first() else {
+        return vec![];
+    };
+    let notes = key.notes();
+    let capo = fretboard.capo as i32;
+    (0..notes.len())
+        .filter_map(|degree| {
+            let open = lowest.transpose(capo);
+            let start = open.transpose((notes[degree].semitones_from_c() as i32 - open.midi()).rem_euclid(12));
+            let pitches = scale_from(key, start, 3 * fretboard.strings.len());
+            let mut frets: Vec<i32> = pitches
+                .iter()
+                .enumerate()
+                .map(|(i, pitch)| pitch.midi() - fretboard.strings[i / 3].midi())
+                .collect();
+            if frets.iter().any(|&f| f < capo) {
+                frets.iter_mut().for_each(|f| *f += 12);
+            }
+            if frets.iter().any(|&f| f > fretboard.frets as i32) {
+                return None;
+            }
+            let count = fretboard.strings.len();
+            let notes = pitches
+                .iter()
+                .zip(&frets)
+                .enumerate()
+                .map(|(i, (pitch, &fret))| {
+                    let string_frets = &frets[i / 3 * 3..i / 3 * 3 + 3];
+                    PatternNote {
+                        position: Position::new(count - i / 3, fret as u8),
+                        degree: key.degree_of(pitch.note).expect("the pitches are in the key"),
+                        finger: three_note_finger(string_frets, i % 3, capo),
+                    }
+                })
+                .collect();
+            Some(Pattern {
+                name: format!("Pattern {}", degree + 1),
+                notes,
+            })
+        })
+        .collect()
+}
+
+/// The index finger on the first note of a string and the little finger on the last, with the middle finger on the
+/// note between unless it is a fret below the last. Strings starting open get a finger per fret.
+fn three_note_finger(frets: &[i32], index: usize, capo: i32) -> Option<u8> {
+    if frets[index] == capo {
+        return None;
+    }
+    if frets[0] == capo {
+        return Some((frets[index] - capo).min(4) as u8);
+    }
+    match index {
+        0 => Some(1),
+        2 => Some(4),
+        _ if frets[1] - frets[0] > 1 && frets[2] - frets[1] == 1 => Some(3),
+        _ => Some(2),
+    }
+}
+
+/// The five CAGED box patterns, named after the chord shape of the tonic they are built around and ordered up the neck
+/// from the nut or capo. Each covers five frets, from a fret below its chord shape, with the notes played in one run
+/// up the strings. Only guitars in standard tuning have them, with any extra strings below the sixth.
+pub fn caged(fretboard: &Fretboard, key: &Key) -> Option<Vec<Pattern>> {
+    let count = fretboard.strings.len();
+    let top = fretboard.strings.get(count.checked_sub(6)?..)?;
+    if top.windows(2).map(|w| w[1].midi() - w[0].midi()).ne(STANDARD) {
+        return None;
+    }
+    let capo = fretboard.capo;
+    let root = (key.tonic.semitones_from_c() as i32 - top[0].transpose(capo as i32).midi()).rem_euclid(12) as u8;
+    let notes = key.notes();
+
+    let mut patterns: Vec<(u8, Pattern)> = CAGED
+        .iter()
+        .filter_map(|(shape, offset)| {
+            let low = (root + offset) % 12;
+            let first = capo + low.saturating_sub(1);
+            let last = capo + low + 3;
+            if last > fretboard.frets {
+                return None;
+            }
+            let mut previous: Option<Pitch> = None;
+            let mut pattern = vec![];
+            for string in (1..=count).rev() {
+                let mut played = vec![];
+                for fret in first..=last {
+                    let Some(pitch) = fretboard.pitch_at(Position::new(string, fret)) else {
+                        continue;
+                    };
+                    if !notes.contains(&pitch.note) || previous.is_some_and(|p| pitch <= p) {
+                        continue;
+                    }
+                    played.push((fret, pitch));
+                    previous = Some(pitch);
+                }
+                let frets: Vec<u8> = played.iter().map(|(fret, _)| *fret).collect();
+                for ((fret, pitch), finger) in played.into_iter().zip(caged_fingers(&frets, first, capo)) {
+                    pattern.push(PatternNote {
+                        position: Position::new(string, fret),
+                        degree: key.degree_of(pitch.note).expect("the note is in the key"),
+                        finger,
+                    });
+                }
+            }
+            Some((
+                low,
+                Pattern {
+                    name: format!("{} shape", shape),
+                    notes: pattern,
+                },
+            ))
+        })
+        .collect();
+    patterns.sort_by_key(|(low, _)| *low);
+    Some(patterns.into_iter().map(|(_, pattern)| pattern).collect())
+}
+
+/// A finger per fret of a string in a CAGED box starting on `first`. Strings within its first four frets get a finger
+/// per fret. Strings reaching the fifth fret shift the hand up a fret, the index finger stretching back for a note on
+/// the first. Strings starting open get a finger per fret from the nut or capo.
+fn caged_fingers(frets: &[u8], first: u8, capo: u8) -> Vec<Option<u8>> {
+    if first == capo {
+        return frets
+            .iter()
+            .map(|&fret| (fret != capo).then(|| (fret - capo).min(4)))
+            .collect();
+    }
+    let shift = u8::from(frets.iter().any(|&fret| fret - first == 4));
+    let mut last = 0;
+    frets
+        .iter()
+        .map(|&fret| {
+            last = (fret - first + 1 - shift).max(last + 1).min(4);
+            Some(last)
+        })
+        .collect()
+}
+
+/// `count` notes of the key going up from `start`, which has to be in the key.
+fn scale_from(key: &Key, start: Pitch, count: usize) -> Vec<Pitch> {
+    let notes = key.notes();
+    (start.midi()..)
+        .map(Pitch::from_midi)
+        .filter(|p| notes.contains(&p.note))
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::fretboard::instrument::Preset;
+    use crate::note::Note;
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, harmonic_minor, Scale};
+
+    use super::*;
+
+    fn major(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Ionian))
+    }
+
+    /// The frets of each string from the lowest, like `"7 8 10|7 8 10"`.
+    fn frets(fretboard: &Fretboard, pattern: &Pattern) -> String {
+        let strings: Vec<String> = (1..=fretboard.strings.len())
+            .rev()
+            .map(|string| {
+                let frets: Vec<String> = pattern
+                    .notes
+                    .iter()
+                    .filter(|n| n.position.string == string)
+                    .map(|n| n.position.fret.to_string())
+                    .collect();
+                frets.join(" ")
+            })
+            .collect();
+        strings.join("|")
+    }
+
+    fn fingers(pattern: &Pattern) -> String {
+        pattern
+            .notes
+            .iter()
+            .map(|n| n.finger.map_or("0".to_string(), |f| f.to_string()))
+            .collect()
+    }
+
+    #[rstest]
+    #[case(0, "C shape", "0 1 3|0 2 3|0 2 3|0 2|0 1 3|0 1 3")]
+    #[case(1, "A shape", "3 5|2 3 5|2 3 5|2 4 5|3 5 6|3 5")]
+    #[case(2, "G shape", "5 7 8|5 7 8|5 7|4 5 7|5 6 8|5 7 8")]
+    #[case(3, "E shape", "7 8 10|7 8 10|7 9 10|7 9 10|8 10|7 8 10")]
+    #[case(4, "D shape", "10 12 13|10 12|9 10 12|9 10 12|10 12 13|10 12 13")]
+    fn caged_c_major(#[case] index: usize, #[case] name: &str, #[case] expected: &str) {
+        let fretboard = Preset::Guitar.fretboard();
+        let patterns = caged(&fretboard, &major(C)).unwrap();
+        assert_eq!(patterns.len(), 5);
+        assert_eq!(patterns[index].name, name);
+        assert_eq!(frets(&fretboard, &patterns[index]), expected);
+    }
+
+    #[test]
+    fn caged_fingers() {
+        let fretboard = Preset::Guitar.fretboard();
+        let patterns = caged(&fretboard, &major(C)).unwrap();
+        assert_eq!(fingers(&patterns[0]), "01302302302013013");
+        assert_eq!(fingers(&patterns[3]), "12412413413424124");
+        assert_eq!(patterns[3].notes[0].degree, 7);
+        assert_eq!(patterns[3].notes[1].degree, 1);
+        assert_eq!(fingers(&patterns[1]), "2412412413413424");
+    }
+
+    #[rstest]
+    #[case(Scale::Diatonic(diatonic::Mode::Ionian))]
+    #[case(Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor))]
+    fn caged_fingers_on_each_string(#[case] scale: Scale) {
+        let fretboard = Preset::Guitar.fretboard();
+        for tonic in (0..12).map(|i| C.transpose(i)) {
+            for pattern in caged(&fretboard, &Key::new(tonic, scale)).unwrap() {
+                for string in 1..=fretboard.strings.len() {
+                    let fingered: Vec<(u8, u8)> = pattern
+                        .notes
+                        .iter()
+                        .filter(|n| n.position.string == string)
+                        .filter_map(|n| Some((n.position.fret, n.finger?)))
+                        .collect();
+                    for (a, b) in fingered.iter().zip(fingered.iter().skip(1)) {
+                        assert!(
+                            a.1 < b.1,
+                            "{} {} string {}: {:?}",
+                            tonic,
+                            pattern.name,
+                            string,
+                            fingered
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn caged_with_capo() {
+        let fretboard = Preset::Guitar.fretboard().with_capo(2);
+        let patterns = caged(&fretboard, &major(D)).unwrap();
+        assert_eq!(patterns[0].name, "C shape");
+        assert_eq!(frets(&fretboard, &patterns[0]), "2 3 5|2 4 5|2 4 5|2 4|2 3 5|2 3 5");
+    }
+
+    #[rstest]
+    #[case(Preset::SevenString, true)]
+    #[case(Preset::DropD, false)]
+    #[case(Preset::Bass, false)]
+    fn caged_tunings(#[case] preset: Preset, #[case] expected: bool) {
+        assert_eq!(caged(&preset.fretboard(), &major(G)).is_some(), expected);
+    }
+
+    #[test]
+    fn three_nps_a_dorian() {
+        let fretboard = Preset::Guitar.fretboard();
+        let key = Key::new(A, Scale::Diatonic(diatonic::Mode::Dorian));
+        let patterns = three_notes_per_string(&fretboard, &key);
+        assert_eq!(patterns.len(), 7);
+        assert_eq!(patterns[0].name, "Pattern 1");
+        assert_eq!(frets(&fretboard, &patterns[0]), "5 7 8|5 7 9|5 7 9|5 7 9|7 8 10|7 8 10");
+        assert_eq!(fingers(&patterns[0]), "134124124124124124");
+        assert_eq!(
+            frets(&fretboard, &patterns[2]),
+            "8 10 12|9 10 12|9 10 12|9 11 12|10 12 13|10 12 14"
+        );
+        assert_eq!(patterns[2].notes[0].degree, 3);
+    }
+
+    #[test]
+    fn three_nps_open_strings() {
+        let fretboard = Preset::Guitar.fretboard();
+        let key = Key::new(E, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+        let patterns = three_notes_per_string(&fretboard, &key);
+        assert_eq!(frets(&fretboard, &patterns[0]), "0 2 3|0 2 3|1 2 4|0 2 4|1 4 5|2 3 5");
+        assert_eq!(fingers(&patterns[0]), "023023124024134124");
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(3)]
+    fn three_nps_on_the_neck(#[case] capo: u8) {
+        let fretboard = Preset::Guitar.fretboard().with_capo(capo);
+        for tonic in (0..12).map(|i| C.transpose(i)) {
+            let patterns = three_notes_per_string(&fretboard, &major(tonic));
+            assert_eq!(patterns.len(), 7);
+            for pattern in patterns {
+                let (low, high) = pattern.frets();
+                assert!(
+                    low >= capo && high <= fretboard.frets && high - low <= 7,
+                    "{:?}",
+                    pattern
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::chord::ChordSymbol;
+use crate::harmony::roman::{AugmentedSixth, Kind, Quality, RomanNumeral};
+use crate::key::Key;
+use crate::note::Note;
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+/// Label a chord with its Roman numeral in a key, recognising secondary dominants and leading-tone chords, chords
+/// borrowed from the parallel key, Neapolitan and augmented sixth chords.
+pub fn analyze(key: &Key, symbol: &ChordSymbol) -> RomanNumeral {
+    let notes = symbol.notes();
+    let quality = Quality::of(&symbol.chord);
+    let root = semitones_above(key.tonic, symbol.root);
+    let inversion = inversion(symbol);
+    let numeral = |accidental: isize, degree: usize, kind: Kind| RomanNumeral {
+        accidental,
+        degree,
+        chord: symbol.chord.clone(),
+        inversion,
+        target: None,
+        kind,
+    };
+
+    if let Some(scale) = diatonic_scale(key, &notes) {
+        let (accidental, degree) = degree_of(root, quality, &scale.semitones());
+        return numeral(accidental, degree, Kind::Diatonic);
+    }
+    if let Some(sixth) = augmented_sixth(key, &notes) {
+        return numeral(-1, 6, Kind::AugmentedSixth(sixth));
+    }
+    if root == 1 && quality == Quality::Major {
+        return numeral(-1, 2, Kind::Neapolitan);
+    }
+    if let Some(target) = secondary_target(key, root, quality) {
+        let degree = if quality.is_diminished() { 7 } else { 5 };
+        return RomanNumeral {
+            target: Some(Box::new(target)),
+            ..numeral(0, degree, Kind::Secondary)
+        };
+    }
+
+    let (accidental, degree) = degree_of(root, quality, &key.scale.semitones());
+    let borrowed = parallel_scales(key)
+        .iter()
+        .any(|scale| notes.iter().all(|n| Key::new(key.tonic, *scale).contains(*n)));
+    numeral(
+        accidental,
+        degree,
+        if borrowed { Kind::Borrowed } else { Kind::Chromatic },
+    )
+}
+
+/// Label every chord of a progression in a key.
+pub fn analyze_progression(key: &Key, symbols: &[ChordSymbol]) -> Vec<RomanNumeral> {
+    symbols.iter().map(|s| analyze(key, s)).collect()
+}
+
+fn semitones_above(tonic: Note, note: Note) -> usize {
+    (note.semitones_from_c() + 12 - tonic.semitones_from_c()) % 12
+}
+
+/// The scale a chord is diatonic to. In a natural minor key the raised leading tone of the harmonic minor is
+/// considered diatonic too, so that `V` and `vii°` are not reported as chromatic.
+fn diatonic_scale(key: &Key, notes: &[Note]) -> Option<Scale> {
+    let mut scales = vec![key.scale];
+    if key.scale == Scale::Diatonic(diatonic::Mode::Aeolian) {
+        scales.push(Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+    }
+    scales
+        .into_iter()
+        .find(|scale| notes.iter().all(|n| Key::new(key.tonic, *scale).contains(*n)))
+}
+
+fn parallel_scales(key: &Key) -> Vec<Scale> {
+    if key.scale.is_major() {
+        vec![
+            Scale::Diatonic(diatonic::Mode::Aeolian),
+            Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor),
+        ]
+    } else {
+        vec![Scale::Diatonic(diatonic::Mode::Ionian)]
+    }
+}
+
+/// The degree a root sits on in a scale, with the accidental needed to reach it. Roots outside the scale are named
+/// after the letter they would usually be spelled with, so `Ab` in C major is a lowered sixth and `E` in C minor a
+/// raised third.
+pub(crate) fn degree_of(root: usize, quality: Quality, scale: &[usize; 7]) -> (isize, usize) {
+    if let Some(i) = scale.iter().position(|s| *s == root) {
+        return (0, i + 1);
+    }
+    let degree = match root {
+        0 => 1,
+        1 | 2 => 2,
+        3 | 4 => 3,
+        5 => 4,
+        6 if quality.is_diminished() => 4,
+        6 | 7 => 5,
+        8 | 9 => 6,
+        _ => 7,
+    };
+    let accidental = (root as isize - scale[degree - 1] as isize + 18).rem_euclid(12) - 6;
+    (accidental, degree)
+}
+
+fn augmented_sixth(key: &Key, notes: &[Note]) -> Option<AugmentedSixth> {
+    let mut semitones: Vec<usize> = notes.iter().map(|n| semitones_above(key.tonic, *n)).collect();
+    semitones.sort();
+    semitones.dedup();
+    match semitones.as_slice() {
+        [0, 6, 8] => Some(AugmentedSixth::Italian),
+        [0, 2, 6, 8] => Some(AugmentedSixth::French),
+        [0, 3, 6, 8] => Some(AugmentedSixth::German),
+        _ => None,
+    }
+}
+
+/// The diatonic chord a dominant (a fifth above) or leading-tone chord (a semitone below) resolves to, if it is a
+/// major or minor triad other than the tonic. The seventh degree is left out: in minor keys the major chord a fifth
+/// above it is far more often the borrowed `IV` than a `V/VII`.
+fn secondary_target(key: &Key, root: usize, quality: Quality) -> Option<RomanNumeral> {
+    let target = match quality {
+        Quality::Major | Quality::Dominant7 => (root + 5) % 12,
+        Quality::Diminished | Quality::HalfDiminished7 | Quality::Diminished7 => (root + 1) % 12,
+        _ => return None,
+    };
+    if target == 0 {
+        return None;
+    }
+    let degree = key.scale.semitones().iter().position(|s| *s == target)?;
+    if degree == 6 {
+        return None;
+    }
+    let (_, chord) = key.triads().into_iter().nth(degree)?;
+    match Quality::of(&chord) {
+        Quality::Major | Quality::Minor => Some(RomanNumeral {
+            accidental: 0,
+            degree: degree + 1,
+            chord,
+            inversion: 0,
+            target: None,
+            kind: Kind::Diatonic,
+        }),
+        _ => None,
+    }
+}
+
+fn inversion(symbol: &ChordSymbol) -> usize {
+    let bass = semitones_above(symbol.root, symbol.bass_note());
+    match bass {
+        3 | 4 => 1,
+        6..=8 => 2,
+        9 if Quality::of(&symbol.chord) == Quality::Diminished7 => 3,
+        10 | 11 => 3,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    fn major(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Ionian))
+    }
+
+    fn minor(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Aeolian))
+    }
+
+    fn label(key: Key, chord: &str) -> (String, Kind) {
+        let numeral = analyze(&key, &chord.parse().unwrap());
+        (numeral.to_string(), numeral.kind)
+    }
+
+    #[rstest]
+    #[case(major(C), "C", "I")]
+    #[case(major(C), "Dm7", "ii⁷")]
+    #[case(major(C), "Dm7/C", "ii⁴₂")]
+    #[case(major(C), "F/A", "IV⁶")]
+    #[case(major(C), "G7", "V⁷")]
+    #[case(major(C), "G7/B", "V⁶₅")]
+    #[case(major(C), "C/G", "I⁶₄")]
+    #[case(major(C), "Cmaj7", "IM⁷")]
+    #[case(major(C), "Bm7b5", "viiø⁷")]
+    #[case(major(D), "Em7/G", "ii⁶₅")]
+    #[case(minor(A), "Am", "i")]
+    #[case(minor(A), "Bdim", "ii°")]
+    #[case(minor(A), "E7", "V⁷")]
+    #[case(minor(A), "G#dim7", "vii°⁷")]
+    #[case(minor(A), "C", "III")]
+    fn diatonic_chords(#[case] key: Key, #[case] chord: &str, #[case] expected: &str) {
+        assert_eq!(label(key, chord), (expected.to_string(), Kind::Diatonic));
+    }
+
+    #[rstest]
+    #[case(major(C), "D7", "V⁷/V", Kind::Secondary)]
+    #[case(major(C), "A7", "V⁷/ii", Kind::Secondary)]
+    #[case(major(C), "E", "V/vi", Kind::Secondary)]
+    #[case(major(C), "C7", "V⁷/IV", Kind::Secondary)]
+    #[case(major(C), "C#dim7", "vii°⁷/ii", Kind::Secondary)]
+    #[case(major(C), "F#m7b5", "viiø⁷/V", Kind::Secondary)]
+    #[case(major(C), "Ab", "bVI", Kind::Borrowed)]
+    #[case(major(C), "Bb", "bVII", Kind::Borrowed)]
+    #[case(major(C), "Fm", "iv", Kind::Borrowed)]
+    #[case(major(C), "Eb", "bIII", Kind::Borrowed)]
+    #[case(major(C), "Bdim7", "vii°⁷", Kind::Borrowed)]
+    #[case(minor(C), "F", "IV", Kind::Borrowed)]
+    #[case(minor(C), "E", "#III", Kind::Chromatic)]
+    #[case(major(C), "Db/F", "N⁶", Kind::Neapolitan)]
+    #[case(minor(A), "Bb", "N", Kind::Neapolitan)]
+    fn chromatic_chords(#[case] key: Key, #[case] chord: &str, #[case] expected: &str, #[case] kind: Kind) {
+        assert_eq!(label(key, chord), (expected.to_string(), kind));
+    }
+
+    #[rstest]
+    #[case(major(C), "Ab7(no5)", AugmentedSixth::Italian)]
+    #[case(major(C), "Ab7b5", AugmentedSixth::French)]
+    #[case(major(C), "Ab7", AugmentedSixth::German)]
+    #[case(minor(A), "F7", AugmentedSixth::German)]
+    fn augmented_sixths(#[case] key: Key, #[case] chord: &str, #[case] expected: AugmentedSixth) {
+        assert_eq!(label(key, chord).1, Kind::AugmentedSixth(expected));
+    }
+
+    #[test]
+    fn progression() {
+        let chords: Vec<ChordSymbol> = ["Dm7", "G7", "Cmaj7"].iter().map(|c| c.parse().unwrap()).collect();
+        let labels: Vec<String> = analyze_progression(&major(C), &chords)
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(labels, vec!["ii⁷", "V⁷", "IM⁷"]);
+    }
+}
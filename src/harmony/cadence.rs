@@ -0,0 +1,244 @@
+use std::fmt;
+
+use crate::harmony::roman::{Kind, RomanNumeral};
+
+/// The harmonic function of a chord: tonic, subdominant (pre-dominant) or dominant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Tonic,
+    Subdominant,
+    Dominant,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Function::Tonic => "T",
+            Function::Subdominant => "S",
+            Function::Dominant => "D",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cadence {
+    /// `V–I` with both chords in root position.
+    PerfectAuthentic,
+    /// `V–I` with an inverted chord, or `vii°–I`.
+    ImperfectAuthentic,
+    /// Ending on `V`.
+    Half,
+    /// `iv⁶–V` in minor.
+    PhrygianHalf,
+    /// `IV–I`.
+    Plagal,
+    /// `V–vi`, or `V–bVI`.
+    Deceptive,
+}
+
+impl fmt::Display for Cadence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Cadence::PerfectAuthentic => "perfect authentic cadence",
+            Cadence::ImperfectAuthentic => "imperfect authentic cadence",
+            Cadence::Half => "half cadence",
+            Cadence::PhrygianHalf => "Phrygian half cadence",
+            Cadence::Plagal => "plagal cadence",
+            Cadence::Deceptive => "deceptive cadence",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A cadence closing the phrase that ends on the chord at index `at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CadencePoint {
+    pub at: usize,
+    pub cadence: Cadence,
+}
+
+/// The function of a chord on its own. Applied chords are dominants, Neapolitan and augmented sixth chords are
+/// subdominants, and the rest follow their degree: `I`, `iii` and `vi` are tonic, `ii` and `IV` subdominant, `V` and
+/// `vii°` dominant. Lowered sixth and seventh degrees borrowed from minor, like `bVI` and `bVII`, are subdominants.
+pub fn function(numeral: &RomanNumeral) -> Function {
+    match numeral.kind {
+        Kind::Secondary => return Function::Dominant,
+        Kind::Neapolitan | Kind::AugmentedSixth(_) => return Function::Subdominant,
+        _ => {}
+    }
+    match (numeral.degree, numeral.accidental) {
+        (6 | 7, a) if a < 0 => Function::Subdominant,
+        (1 | 3 | 6, _) => Function::Tonic,
+        (2 | 4, _) => Function::Subdominant,
+        _ => Function::Dominant,
+    }
+}
+
+/// The function of every chord of a progression. A tonic six-four chord followed by `V` is the cadential six-four,
+/// which belongs to the dominant.
+pub fn functions(numerals: &[RomanNumeral]) -> Vec<Function> {
+    numerals
+        .iter()
+        .enumerate()
+        .map(|(i, numeral)| {
+            let cadential_six_four = is_tonic(numeral)
+                && numeral.inversion == 2
+                && numerals
+                    .get(i + 1)
+                    .is_some_and(|next| is_dominant(next) && next.degree == 5);
+            if cadential_six_four {
+                Function::Dominant
+            } else {
+                function(numeral)
+            }
+        })
+        .collect()
+}
+
+/// The cadence formed by the last two chords of a phrase, if any.
+pub fn cadence(penultimate: &RomanNumeral, last: &RomanNumeral) -> Option<Cadence> {
+    let plain =
+        |n: &RomanNumeral| n.target.is_none() && matches!(n.kind, Kind::Diatonic | Kind::Borrowed | Kind::Chromatic);
+    if !plain(last) {
+        return None;
+    }
+    if is_tonic(last) && plain(penultimate) {
+        if is_dominant(penultimate) {
+            let root_position = penultimate.inversion == 0 && last.inversion == 0;
+            return Some(if penultimate.degree == 5 && root_position {
+                Cadence::PerfectAuthentic
+            } else {
+                Cadence::ImperfectAuthentic
+            });
+        }
+        if penultimate.degree == 4 && penultimate.accidental == 0 {
+            return Some(Cadence::Plagal);
+        }
+    }
+    if last.degree == 6 && last.accidental <= 0 && is_dominant(penultimate) && penultimate.degree == 5 {
+        return Some(Cadence::Deceptive);
+    }
+    if last.degree == 5 && is_dominant(last) {
+        let phrygian = penultimate.degree == 4
+            && penultimate.accidental == 0
+            && penultimate.inversion == 1
+            && !penultimate.quality().is_major();
+        return Some(if phrygian { Cadence::PhrygianHalf } else { Cadence::Half });
+    }
+    None
+}
+
+/// The indices of the chords that end a phrase: each of the given `boundaries`, the last chord, and the tonic of every
+/// perfect authentic cadence that is followed by the tonic again, starting a new phrase.
+pub fn phrase_ends(numerals: &[RomanNumeral], boundaries: &[usize]) -> Vec<usize> {
+    let mut ends: Vec<usize> = boundaries.iter().copied().filter(|b| *b < numerals.len()).collect();
+    ends.extend((1..numerals.len().saturating_sub(1)).filter(|i| {
+        cadence(&numerals[i - 1], &numerals[*i]) == Some(Cadence::PerfectAuthentic) && is_tonic(&numerals[i + 1])
+    }));
+    ends.extend(numerals.len().checked_sub(1));
+    ends.sort();
+    ends.dedup();
+    ends
+}
+
+/// The cadences closing each phrase of a progression, with phrase ends found as in [`phrase_ends`].
+pub fn cadences(numerals: &[RomanNumeral], boundaries: &[usize]) -> Vec<CadencePoint> {
+    phrase_ends(numerals, boundaries)
+        .into_iter()
+        .filter(|at| *at > 0)
+        .filter_map(|at| cadence(&numerals[at - 1], &numerals[at]).map(|cadence| CadencePoint { at, cadence }))
+        .collect()
+}
+
+fn is_tonic(numeral: &RomanNumeral) -> bool {
+    numeral.degree == 1 && numeral.accidental == 0 && numeral.target.is_none()
+}
+
+fn is_dominant(numeral: &RomanNumeral) -> bool {
+    let quality = numeral.quality();
+    numeral.accidental == 0
+        && numeral.target.is_none()
+        && ((numeral.degree == 5 && quality.is_major()) || (numeral.degree == 7 && quality.is_diminished()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::harmony::parse_progression;
+
+    use super::*;
+
+    fn numerals(progression: &str) -> Vec<RomanNumeral> {
+        parse_progression(progression).unwrap()
+    }
+
+    #[rstest]
+    #[case("V7 I", Some(Cadence::PerfectAuthentic))]
+    #[case("V i", Some(Cadence::PerfectAuthentic))]
+    #[case("V65 I", Some(Cadence::ImperfectAuthentic))]
+    #[case("V I6", Some(Cadence::ImperfectAuthentic))]
+    #[case("vii°7 i", Some(Cadence::ImperfectAuthentic))]
+    #[case("I V", Some(Cadence::Half))]
+    #[case("ii6 V", Some(Cadence::Half))]
+    #[case("iv6 V", Some(Cadence::PhrygianHalf))]
+    #[case("IV I", Some(Cadence::Plagal))]
+    #[case("iv i", Some(Cadence::Plagal))]
+    #[case("V7 vi", Some(Cadence::Deceptive))]
+    #[case("V bVI", Some(Cadence::Deceptive))]
+    #[case("I IV", None)]
+    #[case("V7/V V", Some(Cadence::Half))]
+    #[case("V V7/IV", None)]
+    fn cadence_types(#[case] progression: &str, #[case] expected: Option<Cadence>) {
+        let n = numerals(progression);
+        assert_eq!(cadence(&n[0], &n[1]), expected);
+    }
+
+    #[rstest]
+    #[case("I vi ii V7 I", "T T S D T")]
+    #[case("I IV I64 V7 I", "T S D D T")]
+    #[case("i iv V7/V V i", "T S D D T")]
+    #[case("I bVI bVII I", "T S S T")]
+    #[case("iii N6 Ger+6 V", "T S S D")]
+    fn chord_functions(#[case] progression: &str, #[case] expected: &str) {
+        let result: Vec<String> = functions(&numerals(progression))
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(result.join(" "), expected);
+    }
+
+    #[test]
+    fn phrases_end_after_authentic_cadences_and_at_the_end() {
+        let n = numerals("I IV V7 I I vi ii V");
+        assert_eq!(phrase_ends(&n, &[]), vec![3, 7]);
+        assert_eq!(
+            cadences(&n, &[]),
+            vec![
+                CadencePoint {
+                    at: 3,
+                    cadence: Cadence::PerfectAuthentic
+                },
+                CadencePoint {
+                    at: 7,
+                    cadence: Cadence::Half
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_phrase_boundaries() {
+        let n = numerals("I ii V I IV V vi");
+        assert_eq!(phrase_ends(&n, &[2]), vec![2, 6]);
+        let kinds: Vec<Cadence> = cadences(&n, &[2]).iter().map(|c| c.cadence).collect();
+        assert_eq!(kinds, vec![Cadence::Half, Cadence::Deceptive]);
+    }
+
+    #[test]
+    fn empty_progression() {
+        assert!(phrase_ends(&[], &[]).is_empty());
+        assert!(cadences(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::notation::{SpelledChord, SpelledNote};
+use crate::note::Note;
+use crate::scales::{symmetric, Scale};
+
+const KEY_NOTE_WEIGHT: i32 = 3;
+const AVOID_NOTE_PENALTY: i32 = 2;
+
+/// A scale that can be played over a chord: one of the seven-note modes or a symmetric scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChordScale {
+    Scale(Scale),
+    Symmetric(symmetric::Mode),
+}
+
+impl ChordScale {
+    pub fn all() -> Vec<ChordScale> {
+        Scale::all()
+            .into_iter()
+            .map(ChordScale::Scale)
+            .chain(symmetric::Mode::array().into_iter().map(ChordScale::Symmetric))
+            .collect()
+    }
+
+    pub fn intervals(&self) -> Vec<Interval> {
+        match self {
+            ChordScale::Scale(scale) => scale.intervals().to_vec(),
+            ChordScale::Symmetric(mode) => mode.intervals(),
+        }
+    }
+}
+
+impl fmt::Display for ChordScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChordScale::Scale(scale) => write!(f, "{}", scale),
+            ChordScale::Symmetric(symmetric::Mode::HalfWholeDiminished) => write!(f, "half-whole-diminished"),
+            ChordScale::Symmetric(symmetric::Mode::WholeHalfDiminished) => write!(f, "whole-half-diminished"),
+            ChordScale::Symmetric(symmetric::Mode::WholeTone) => write!(f, "whole-tone"),
+        }
+    }
+}
+
+/// What a scale note does over a chord.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    ChordTone,
+    /// A note that can be added to the chord as a colour tone.
+    Tension,
+    /// A note a half step above a chord tone, which clashes when sustained over the chord.
+    Avoid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScaleTone {
+    pub note: Note,
+    /// The function of the note above the chord root, like `b9`, `3` or `13`.
+    pub label: &'static str,
+    pub role: Role,
+}
+
+/// A scale that contains every tone of a chord, built on the chord root, with its notes marked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScaleFit {
+    pub root: Note,
+    /// The root as the chord symbol spells it, in the key if there is one.
+    pub spelled_root: SpelledNote,
+    pub scale: ChordScale,
+    pub tones: Vec<ScaleTone>,
+    pub score: i32,
+}
+
+impl ScaleFit {
+    pub fn tensions(&self) -> Vec<&ScaleTone> {
+        self.tones.iter().filter(|t| t.role == Role::Tension).collect()
+    }
+
+    pub fn avoid_notes(&self) -> Vec<&ScaleTone> {
+        self.tones.iter().filter(|t| t.role == Role::Avoid).collect()
+    }
+
+    /// The tones spelled from the root on the letters of their labels, so that the `3` of D altered is `F#` and the
+    /// `#11` of G lydian dominant is `C#`.
+    pub fn spelled_tones(&self) -> Vec<SpelledNote> {
+        self.tones
+            .iter()
+            .map(|t| {
+                let number: usize = match t.label {
+                    "R" => 1,
+                    label => label
+                        .trim_start_matches(['b', '#'])
+                        .parse()
+                        .expect("labels end with a number"),
+                };
+                SpelledNote::on(self.spelled_root.letter.up(number - 1), t.note)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ScaleFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tones: Vec<String> = self
+            .tones
+            .iter()
+            .zip(self.spelled_tones())
+            .map(|(t, note)| match t.role {
+                Role::ChordTone => format!("{}({})", note, t.label),
+                Role::Tension => format!("{}[{}]", note, t.label),
+                Role::Avoid => format!("{}<{}>", note, t.label),
+            })
+            .collect();
+        write!(f, "{} {}: {}", self.spelled_root, self.scale, tones.join(" "))
+    }
+}
+
+/// The scales that fit a chord, best first.
+///
+/// A scale fits when it contains every chord tone. Scales with fewer avoid notes rank higher and, when a key is given,
+/// so do scales that share more notes with it, so `G7` in C major prefers Mixolydian while `G7` alone prefers Lydian
+/// Dominant. The root is spelled as in the key if there is one, and as written otherwise.
+pub fn chord_scales(written: &SpelledChord, key: Option<&Key>) -> Vec<ScaleFit> {
+    let symbol = &written.symbol;
+    let chord = symbol.chord.semitones();
+    let spelled_root = key.map_or(written.root, |key| SpelledChord::new(symbol, Some(key)).root);
+    let mut fits: Vec<ScaleFit> = ChordScale::all()
+        .into_iter()
+        .filter_map(|scale| {
+            let semitones: Vec<usize> = scale.intervals().iter().map(|i| i.semitones() % 12).collect();
+            if !chord.iter().all(|s| semitones.contains(s)) {
+                return None;
+            }
+            let tones: Vec<ScaleTone> = semitones
+                .iter()
+                .map(|s| ScaleTone {
+                    note: symbol.root.transpose(*s as isize),
+                    label: label(*s, &chord),
+                    role: role(*s, &chord),
+                })
+                .collect();
+            let avoid = tones.iter().filter(|t| t.role == Role::Avoid).count() as i32;
+            let in_key = key.map_or(0, |k| tones.iter().filter(|t| k.contains(t.note)).count() as i32);
+            Some(ScaleFit {
+                root: symbol.root,
+                spelled_root,
+                scale,
+                tones,
+                score: in_key * KEY_NOTE_WEIGHT - avoid * AVOID_NOTE_PENALTY,
+            })
+        })
+        .collect();
+    // The sort is stable, so equally good scales keep the diatonic-first order of `ChordScale::all`.
+    fits.sort_by_key(|fit| -fit.score);
+    fits
+}
+
+fn is_dominant(chord: &[usize]) -> bool {
+    chord.contains(&4) && chord.contains(&10)
+}
+
+fn role(semitones: usize, chord: &[usize]) -> Role {
+    if chord.contains(&semitones) {
+        return Role::ChordTone;
+    }
+    let altered_dominant_tension = is_dominant(chord) && matches!(semitones, 1 | 3 | 8);
+    if chord.contains(&((semitones + 11) % 12)) && !altered_dominant_tension {
+        Role::Avoid
+    } else {
+        Role::Tension
+    }
+}
+
+/// The name of a note above the root of a chord, like `b3`, `5` or `#11`.
+pub(crate) fn label(semitones: usize, chord: &[usize]) -> &'static str {
+    let chord_tone = chord.contains(&semitones);
+    let has_third = chord.contains(&3) || chord.contains(&4);
+    match semitones {
+        0 => "R",
+        1 => "b9",
+        2 if chord_tone && !has_third => "2",
+        2 => "9",
+        3 if chord.contains(&4) => "#9",
+        3 => "b3",
+        4 => "3",
+        5 if chord_tone && !has_third => "4",
+        5 => "11",
+        6 if chord_tone && !chord.contains(&7) => "b5",
+        6 => "#11",
+        7 => "5",
+        8 if chord_tone && !chord.contains(&7) => "#5",
+        8 => "b13",
+        9 if chord_tone && chord.contains(&6) && !chord.contains(&10) => "bb7",
+        9 if chord_tone => "6",
+        9 => "13",
+        10 => "b7",
+        _ => "7",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, melodic_minor};
+
+    use super::*;
+
+    fn names(chord: &str, key: Option<Key>) -> Vec<String> {
+        chord_scales(&chord.parse().unwrap(), key.as_ref())
+            .iter()
+            .map(|fit| fit.scale.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn dominant_in_a_major_key_prefers_mixolydian() {
+        let key = Key::new(C, Scale::Diatonic(diatonic::Mode::Ionian));
+        let scales = names("G7", Some(key));
+        assert_eq!(scales[0], "mixolydian");
+        assert!(scales.contains(&"lydian-dominant".to_string()));
+        assert!(scales.contains(&"half-whole-diminished".to_string()));
+        assert!(!scales.contains(&"major".to_string()));
+    }
+
+    #[test]
+    fn dominant_alone_prefers_scales_without_avoid_notes() {
+        assert_eq!(names("G7", None)[0], "lydian-dominant");
+    }
+
+    #[test]
+    fn altered_dominant_only_fits_the_altered_scale() {
+        assert_eq!(names("D7alt", None), vec!["altered"]);
+    }
+
+    #[rstest]
+    #[case("Cmaj7#11", "lydian")]
+    #[case("Cmaj7", "lydian")]
+    #[case("Cm7", "dorian")]
+    #[case("Cm7b5", "locrian-natural2")]
+    #[case("Cdim7", "whole-half-diminished")]
+    fn best_scale(#[case] chord: &str, #[case] expected: &str) {
+        assert_eq!(names(chord, None)[0], expected);
+    }
+
+    #[test]
+    fn mixolydian_over_dominant_marks_tones() {
+        let fit = chord_scales(&"G7".parse().unwrap(), None)
+            .into_iter()
+            .find(|fit| fit.scale == ChordScale::Scale(Scale::Diatonic(diatonic::Mode::Mixolydian)))
+            .unwrap();
+        let labelled: Vec<(Note, &str, Role)> = fit.tones.iter().map(|t| (t.note, t.label, t.role)).collect();
+        assert_eq!(
+            labelled,
+            vec![
+                (G, "R", Role::ChordTone),
+                (A, "9", Role::Tension),
+                (B, "3", Role::ChordTone),
+                (C, "11", Role::Avoid),
+                (D, "5", Role::ChordTone),
+                (E, "13", Role::Tension),
+                (F, "b7", Role::ChordTone),
+            ]
+        );
+        assert_eq!(fit.to_string(), "G mixolydian: G(R) A[9] B(3) C<11> D(5) E[13] F(b7)");
+    }
+
+    #[test]
+    fn altered_tensions_are_available_on_dominants() {
+        let fit = chord_scales(&"G7".parse().unwrap(), None)
+            .into_iter()
+            .find(|fit| fit.scale == ChordScale::Scale(Scale::MelodicMinor(melodic_minor::Mode::Altered)));
+        assert!(fit.is_none(), "altered has no perfect fifth");
+
+        let fit = chord_scales(&"G7b9".parse().unwrap(), None)
+            .into_iter()
+            .find(|fit| fit.scale == ChordScale::Symmetric(symmetric::Mode::HalfWholeDiminished))
+            .unwrap();
+        let tensions: Vec<&str> = fit.tensions().iter().map(|t| t.label).collect();
+        assert_eq!(tensions, vec!["#9", "#11", "13"]);
+        assert!(fit.avoid_notes().is_empty());
+    }
+
+    #[rstest]
+    #[case("D7alt", None, "altered", "D altered: D(R) Eb(b9) E#(#9) F#(3) Ab(b5) A#(#5) C(b7)")]
+    #[case(
+        "G7#11",
+        None,
+        "lydian-dominant",
+        "G lydian-dominant: G(R) A[9] B(3) C#(#11) D(5) E[13] F(b7)"
+    )]
+    #[case(
+        "F#7",
+        None,
+        "mixolydian",
+        "F# mixolydian: F#(R) G#[9] A#(3) B<11> C#(5) D#[13] E(b7)"
+    )]
+    #[case(
+        "Gb7",
+        Some(Key::new(Db, Scale::Diatonic(diatonic::Mode::Ionian))),
+        "mixolydian",
+        "Gb mixolydian: Gb(R) Ab[9] Bb(3) Cb<11> Db(5) Eb[13] Fb(b7)"
+    )]
+    fn spelled_from_the_root(
+        #[case] chord: &str,
+        #[case] key: Option<Key>,
+        #[case] scale: &str,
+        #[case] expected: &str,
+    ) {
+        let fit = chord_scales(&chord.parse().unwrap(), key.as_ref())
+            .into_iter()
+            .find(|fit| fit.scale.to_string() == scale)
+            .unwrap();
+        assert_eq!(fit.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("Bbdim", "Bb locrian: Bb(R) Cb<b9> Db(b3) Eb[11] Fb(b5) Gb[b13] Ab[b7]")]
+    #[case("A#dim", "A# locrian: A#(R) B<b9> C#(b3) D#[11] E(b5) F#[b13] G#[b7]")]
+    #[case("Gbm7b5", "Gb locrian: Gb(R) Abb<b9> Bbb(b3) Cb[11] Dbb(b5) Ebb[b13] Fb(b7)")]
+    fn root_spelled_as_written(#[case] chord: &str, #[case] expected: &str) {
+        let fit = chord_scales(&chord.parse().unwrap(), None)
+            .into_iter()
+            .find(|fit| fit.scale == ChordScale::Scale(Scale::Diatonic(diatonic::Mode::Locrian)))
+            .unwrap();
+        assert_eq!(fit.to_string(), expected);
+    }
+
+    #[test]
+    fn minor_seventh_in_aeolian_avoids_flat_thirteen() {
+        let fit = chord_scales(&"Am7".parse().unwrap(), None)
+            .into_iter()
+            .find(|fit| fit.scale == ChordScale::Scale(Scale::Diatonic(diatonic::Mode::Aeolian)))
+            .unwrap();
+        let avoid: Vec<(Note, &str)> = fit.avoid_notes().iter().map(|t| (t.note, t.label)).collect();
+        assert_eq!(avoid, vec![(F, "b13")]);
+    }
+}
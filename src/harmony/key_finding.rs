@@ -0,0 +1,194 @@
+use crate::key::Key;
+use crate::note::Note;
+use crate::scales::{diatonic, Scale};
+
+/// Krumhansl and Kessler's probe-tone ratings for major and minor keys, from the tonic up.
+const KRUMHANSL_MAJOR: [f64; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const KRUMHANSL_MINOR: [f64; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+/// Temperley's revised profiles, derived from the Kostka–Payne corpus.
+const TEMPERLEY_MAJOR: [f64; 12] = [5.0, 2.0, 3.5, 2.0, 4.5, 4.0, 2.0, 4.5, 2.0, 3.5, 1.5, 4.0];
+const TEMPERLEY_MINOR: [f64; 12] = [5.0, 2.0, 3.5, 4.5, 2.0, 4.0, 2.0, 4.5, 3.5, 2.0, 1.5, 4.0];
+
+/// The key profiles a pitch histogram can be compared against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    KrumhanslSchmuckler,
+    Temperley,
+}
+
+impl Profile {
+    pub fn major(&self) -> [f64; 12] {
+        match self {
+            Profile::KrumhanslSchmuckler => KRUMHANSL_MAJOR,
+            Profile::Temperley => TEMPERLEY_MAJOR,
+        }
+    }
+
+    pub fn minor(&self) -> [f64; 12] {
+        match self {
+            Profile::KrumhanslSchmuckler => KRUMHANSL_MINOR,
+            Profile::Temperley => TEMPERLEY_MINOR,
+        }
+    }
+
+    /// The profile of any scale, from its tonic up.
+    ///
+    /// The published profiles only cover major and minor, so other scales borrow the one with the same third: each
+    /// degree takes the weight of the same degree of the major or natural minor scale, and the notes outside the scale
+    /// take the weights of the notes outside major or minor, in order. Dorian thus keeps the minor tonic, third and fifth
+    /// weights but gives them to its own sixth.
+    pub fn for_scale(&self, scale: Scale) -> [f64; 12] {
+        let (base, base_scale) = if scale.is_major() {
+            (self.major(), Scale::Diatonic(diatonic::Mode::Ionian))
+        } else {
+            (self.minor(), Scale::Diatonic(diatonic::Mode::Aeolian))
+        };
+        let (degrees, base_degrees) = (scale.semitones(), base_scale.semitones());
+        let outside = |degrees: [usize; 7]| (0..12).filter(move |s| !degrees.contains(s));
+
+        let mut profile = [0.0; 12];
+        for (degree, base_degree) in degrees.iter().zip(base_degrees) {
+            profile[*degree] = base[base_degree];
+        }
+        for (other, base_other) in outside(degrees).zip(outside(base_degrees)) {
+            profile[other] = base[base_other];
+        }
+        profile
+    }
+}
+
+/// A candidate key with the correlation between its profile and the notes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyScore {
+    pub key: Key,
+    pub score: f64,
+}
+
+/// The total weight of each pitch class, indexed by semitones above C.
+pub fn histogram(notes: &[(Note, f64)]) -> [f64; 12] {
+    let mut histogram = [0.0; 12];
+    for (note, weight) in notes {
+        histogram[note.semitones_from_c()] += weight;
+    }
+    histogram
+}
+
+/// Rank the keys on every tonic and each of `scales` by how well their profile correlates with the notes, each
+/// weighted, for instance, by its duration. Ties keep the order of `scales`, then of the tonics from C. The result is
+/// empty when the notes don't tell keys apart, e.g. when there are none.
+pub fn find_keys(notes: &[(Note, f64)], profile: Profile, scales: &[Scale]) -> Vec<KeyScore> {
+    let histogram = histogram(notes);
+    let mut scores: Vec<KeyScore> = scales
+        .iter()
+        .flat_map(|scale| {
+            let weights = profile.for_scale(*scale);
+            (0..12).map(move |tonic| {
+                let rotated: [f64; 12] = std::array::from_fn(|pc| weights[(pc + 12 - tonic) % 12]);
+                KeyScore {
+                    key: Key::new(Note::C.transpose(tonic as isize), *scale),
+                    score: correlation(&histogram, &rotated),
+                }
+            })
+        })
+        .collect();
+    if scores.iter().any(|s| s.score.is_nan()) {
+        return vec![];
+    }
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scores
+}
+
+/// The most likely major or minor key of some unweighted notes.
+pub fn find_key(notes: &[Note], profile: Profile) -> Option<Key> {
+    let weighted: Vec<(Note, f64)> = notes.iter().map(|n| (*n, 1.0)).collect();
+    let scales = [
+        Scale::Diatonic(diatonic::Mode::Ionian),
+        Scale::Diatonic(diatonic::Mode::Aeolian),
+    ];
+    find_keys(&weighted, profile, &scales).first().map(|s| s.key)
+}
+
+/// Pearson's correlation coefficient.
+fn correlation(x: &[f64; 12], y: &[f64; 12]) -> f64 {
+    let mean = |v: &[f64; 12]| v.iter().sum::<f64>() / 12.0;
+    let (mx, my) = (mean(x), mean(y));
+    let covariance: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+    let spread = |v: &[f64; 12], m: f64| v.iter().map(|a| (a - m).powi(2)).sum::<f64>().sqrt();
+    covariance / (spread(x, mx) * spread(y, my))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+    use crate::scales::harmonic_minor;
+
+    use super::*;
+
+    const MAJOR: Scale = Scale::Diatonic(diatonic::Mode::Ionian);
+    const MINOR: Scale = Scale::Diatonic(diatonic::Mode::Aeolian);
+
+    #[rstest]
+    #[case(Profile::KrumhanslSchmuckler)]
+    #[case(Profile::Temperley)]
+    fn profiles_of_major_and_minor_are_the_published_ones(#[case] profile: Profile) {
+        assert_eq!(profile.for_scale(MAJOR), profile.major());
+        assert_eq!(profile.for_scale(MINOR), profile.minor());
+    }
+
+    #[test]
+    fn modal_profiles_move_weights_with_the_degrees() {
+        let dorian = Profile::Temperley.for_scale(Scale::Diatonic(diatonic::Mode::Dorian));
+        assert_eq!(dorian[9], TEMPERLEY_MINOR[8]);
+        assert_eq!(dorian[8], TEMPERLEY_MINOR[9]);
+        assert_eq!(dorian[3], TEMPERLEY_MINOR[3]);
+    }
+
+    #[rstest]
+    #[case(Profile::KrumhanslSchmuckler, &[C, D, E, F, G, A, B, C, G, E, C], "C major")]
+    #[case(Profile::Temperley, &[C, D, E, F, G, A, B, C, G, E, C], "C major")]
+    #[case(Profile::KrumhanslSchmuckler, &[A, B, C, D, E, F, Ab, A, E, C, A], "A minor")]
+    #[case(Profile::Temperley, &[A, B, C, D, E, F, Ab, A, E, C, A], "A minor")]
+    #[case(Profile::KrumhanslSchmuckler, &[Eb, F, G, Ab, Bb, C, D, Eb, Bb, G], "Eb major")]
+    fn unweighted_keys(#[case] profile: Profile, #[case] notes: &[Note], #[case] expected: &str) {
+        assert_eq!(find_key(notes, profile).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn weights_decide_between_relative_keys() {
+        let scale = [C, D, E, F, G, A, B];
+        let mut notes: Vec<(Note, f64)> = scale.iter().map(|n| (*n, 1.0)).collect();
+        notes.extend([(A, 4.0), (E, 2.0), (C, 2.0)]);
+        let ranked = find_keys(&notes, Profile::KrumhanslSchmuckler, &[MAJOR, MINOR]);
+        assert_eq!(ranked[0].key.to_string(), "A minor");
+        assert_eq!(ranked.len(), 24);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn modal_keys() {
+        let notes = [(D, 4.0), (E, 1.0), (F, 2.0), (G, 1.0), (A, 3.0), (B, 2.0), (C, 1.0)];
+        let scales: Vec<Scale> = diatonic::Mode::array()
+            .into_iter()
+            .map(Scale::Diatonic)
+            .chain(harmonic_minor::Mode::array().into_iter().map(Scale::HarmonicMinor))
+            .collect();
+        let ranked = find_keys(&notes, Profile::Temperley, &scales);
+        assert_eq!(ranked[0].key.to_string(), "D dorian");
+    }
+
+    #[test]
+    fn no_notes() {
+        assert!(find_keys(&[], Profile::Temperley, &[MAJOR]).is_empty());
+        assert_eq!(find_key(&[], Profile::Temperley), None);
+    }
+
+    #[test]
+    fn histogram_sums_weights() {
+        let h = histogram(&[(C, 1.0), (C, 0.5), (G, 2.0)]);
+        assert_eq!(h[0], 1.5);
+        assert_eq!(h[7], 2.0);
+        assert_eq!(h.iter().sum::<f64>(), 3.5);
+    }
+}
@@ -0,0 +1,23 @@
+pub use analysis::{analyze, analyze_progression};
+pub use cadence::{cadence, cadences, function, functions, phrase_ends, Cadence, CadencePoint, Function};
+pub use chord_scale::{chord_scales, ChordScale, ScaleFit};
+pub use key_finding::{find_key, find_keys, KeyScore, Profile};
+pub use modulation::{common_tone_links, common_tones, modulations, pivot_chords, Modulation, Pivot};
+pub use negative::{negative_chord, negative_note, negative_progression, NegativeRoot};
+pub use neo_riemannian::{shortest_path, tonnetz, Transformation};
+pub use progression::{parse_progression, realize, realize_progression};
+pub use roman::RomanNumeral;
+pub use substitution::{reharmonize, substitutions, Substitute, Substitution};
+pub use voice_leading::{check, voice_lead, Issue, Voice, VoiceLeading, Voicing};
+
+pub mod analysis;
+pub mod cadence;
+pub mod chord_scale;
+pub mod key_finding;
+pub mod modulation;
+pub mod negative;
+pub mod neo_riemannian;
+pub mod progression;
+pub mod roman;
+pub mod substitution;
+pub mod voice_leading;
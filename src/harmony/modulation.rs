@@ -0,0 +1,271 @@
+use std::fmt;
+
+use crate::chord::ChordSymbol;
+use crate::harmony::analyze;
+use crate::harmony::cadence::{function, Function};
+use crate::harmony::roman::{Quality, RomanNumeral};
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::note::Note;
+
+/// A chord that belongs to both keys, with its numeral in each.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pivot {
+    pub chord: ChordSymbol,
+    pub from: RomanNumeral,
+    pub to: RomanNumeral,
+}
+
+/// Two chords that share notes, like the tonics of C major and E major sharing `E`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommonToneLink {
+    pub from: ChordSymbol,
+    pub to: ChordSymbol,
+    pub tones: Vec<Note>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Technique {
+    /// Reinterpreting a chord of both keys, then cadencing in the new one.
+    PivotChord,
+    /// Going straight to the dominant of the new key, heard as a secondary dominant of the old one.
+    SecondaryDominant,
+    /// Going straight to the dominant of a new key whose tonic is not a degree of the old one.
+    DirectDominant,
+    /// Holding a note that belongs to the tonic of the old key and a chord of the new one.
+    CommonTone,
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Technique::PivotChord => "pivot chord",
+            Technique::SecondaryDominant => "secondary dominant",
+            Technique::DirectDominant => "direct dominant",
+            Technique::CommonTone => "common tone",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A short progression that leaves one key and establishes another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Modulation {
+    pub technique: Technique,
+    pub chords: Vec<ChordSymbol>,
+}
+
+impl fmt::Display for Modulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<String> = self.chords.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}: {}", self.technique, chords.join(" "))
+    }
+}
+
+/// The notes the two keys share, in the order of the first key.
+pub fn common_tones(from: &Key, to: &Key) -> Vec<Note> {
+    from.notes().into_iter().filter(|n| to.contains(*n)).collect()
+}
+
+/// The diatonic triads and seventh chords of `from` that are also diatonic in `to`, triads first, each in scale order.
+pub fn pivot_chords(from: &Key, to: &Key) -> Vec<Pivot> {
+    from.triads()
+        .into_iter()
+        .chain(from.sevenths())
+        .map(|(root, chord)| ChordSymbol::new(root, chord))
+        .filter(|symbol| symbol.notes().iter().all(|n| to.contains(*n)))
+        .map(|chord| Pivot {
+            from: analyze(from, &chord),
+            to: analyze(to, &chord),
+            chord,
+        })
+        .collect()
+}
+
+/// The notes the tonic of `from` shares with the tonic and the dominant seventh of `to`, when they are other chords.
+pub fn common_tone_links(from: &Key, to: &Key) -> Vec<CommonToneLink> {
+    let tonic = tonic(from);
+    [self::tonic(to), dominant(to)]
+        .into_iter()
+        .filter(|target| *target != tonic)
+        .filter_map(|target| {
+            let tones: Vec<Note> = tonic
+                .notes()
+                .into_iter()
+                .filter(|n| target.notes().contains(n))
+                .collect();
+            (!tones.is_empty()).then_some(CommonToneLink {
+                from: tonic.clone(),
+                to: target,
+                tones,
+            })
+        })
+        .collect()
+}
+
+/// Short progressions from the tonic of `from` to the tonic of `to`.
+///
+/// Pivot chords that are subdominants in the new key are used first, each followed by the dominant seventh and the
+/// tonic of the new key. The dominant can also be reached directly, as a secondary dominant when the new tonic is
+/// another degree of the old key, and when the old tonic shares notes with the new tonic or dominant the modulation
+/// can pivot on those notes alone.
+pub fn modulations(from: &Key, to: &Key) -> Vec<Modulation> {
+    let (start, end, dominant) = (tonic(from), tonic(to), dominant(to));
+    let mut pivots: Vec<Pivot> = pivot_chords(from, to)
+        .into_iter()
+        .filter(|p| p.chord.chord.intervals.len() == 3 && p.chord != start && p.chord != end && p.chord != dominant)
+        .filter(|p| function(&p.to) != Function::Dominant)
+        .collect();
+    pivots.sort_by_key(|p| function(&p.to) != Function::Subdominant);
+
+    let mut modulations: Vec<Modulation> = pivots
+        .into_iter()
+        .map(|p| Modulation {
+            technique: Technique::PivotChord,
+            chords: vec![start.clone(), p.chord, dominant.clone(), end.clone()],
+        })
+        .collect();
+    let secondary = to.tonic != from.tonic && from.contains(to.tonic);
+    modulations.push(Modulation {
+        technique: if secondary {
+            Technique::SecondaryDominant
+        } else {
+            Technique::DirectDominant
+        },
+        chords: vec![start.clone(), dominant.clone(), end.clone()],
+    });
+    for link in common_tone_links(from, to) {
+        let chords = if link.to == end {
+            vec![start.clone(), end.clone()]
+        } else {
+            vec![start.clone(), dominant.clone(), end.clone()]
+        };
+        modulations.push(Modulation {
+            technique: Technique::CommonTone,
+            chords,
+        });
+    }
+    modulations
+}
+
+fn tonic(key: &Key) -> ChordSymbol {
+    let (root, chord) = key.triads().remove(0);
+    ChordSymbol::new(root, chord)
+}
+
+/// The dominant seventh on the fifth degree, major whatever the mode.
+fn dominant(key: &Key) -> ChordSymbol {
+    ChordSymbol::new(key.tonic << Interval::PerfectFifth, Quality::Dominant7.chord())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, Scale};
+
+    use super::*;
+
+    fn major(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Ionian))
+    }
+
+    fn minor(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Aeolian))
+    }
+
+    fn strings<T: ToString>(items: &[T]) -> Vec<String> {
+        items.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn common_tones_of_related_keys() {
+        assert_eq!(common_tones(&major(C), &major(G)), vec![C, D, E, G, A, B]);
+        assert_eq!(common_tones(&major(C), &minor(A)).len(), 7);
+    }
+
+    #[test]
+    fn pivots_between_c_and_g() {
+        let pivots = pivot_chords(&major(C), &major(G));
+        let labelled: Vec<String> = pivots
+            .iter()
+            .map(|p| format!("{} {} {}", p.chord, p.from, p.to))
+            .collect();
+        assert_eq!(
+            labelled,
+            vec![
+                "C I IV",
+                "Em iii vi",
+                "G V I",
+                "Am vi ii",
+                "Cmaj7 IM⁷ IVM⁷",
+                "Em7 iii⁷ vi⁷",
+                "Am7 vi⁷ ii⁷",
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(major(C), major(Gb), 0)]
+    #[case(major(C), minor(E), 7)]
+    #[case(Key::new(D, Scale::Diatonic(diatonic::Mode::Dorian)), major(C), 14)]
+    fn pivot_counts(#[case] from: Key, #[case] to: Key, #[case] expected: usize) {
+        assert_eq!(pivot_chords(&from, &to).len(), expected);
+    }
+
+    #[test]
+    fn common_tone_links_between_chromatic_mediants() {
+        let links = common_tone_links(&major(C), &major(E));
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].to.to_string(), "E");
+        assert_eq!(links[0].tones, vec![E]);
+    }
+
+    #[test]
+    fn no_link_between_keys_on_the_same_tonic_chord() {
+        let dorian = Key::new(D, Scale::Diatonic(diatonic::Mode::Dorian));
+        let links = common_tone_links(&dorian, &minor(D));
+        assert_eq!(strings(&links.iter().map(|l| &l.to).collect::<Vec<_>>()), vec!["A7"]);
+        assert!(!strings(&modulations(&dorian, &minor(D))).contains(&"common tone: Dm Dm".to_string()));
+    }
+
+    #[test]
+    fn modulations_from_c_to_g() {
+        assert_eq!(
+            strings(&modulations(&major(C), &major(G))),
+            vec![
+                "pivot chord: C Am D7 G",
+                "pivot chord: C Em D7 G",
+                "secondary dominant: C D7 G",
+                "common tone: C G",
+                "common tone: C D7 G",
+            ]
+        );
+    }
+
+    #[test]
+    fn modulations_to_a_distant_key() {
+        assert_eq!(
+            strings(&modulations(&major(C), &major(E))),
+            vec!["secondary dominant: C B7 E", "common tone: C E"]
+        );
+    }
+
+    #[rstest]
+    #[case(major(C), major(Gb), "direct dominant: C Db7 Gb")]
+    #[case(major(C), major(Eb), "direct dominant: C Bb7 Eb")]
+    #[case(major(C), minor(C), "direct dominant: C G7 Cm")]
+    #[case(major(C), minor(D), "secondary dominant: C A7 Dm")]
+    #[case(minor(A), major(Bb), "direct dominant: Am F7 Bb")]
+    fn dominant_modulations(#[case] from: Key, #[case] to: Key, #[case] expected: &str) {
+        let result = strings(&modulations(&from, &to));
+        assert!(result.contains(&expected.to_string()), "{:?}", result);
+    }
+
+    #[test]
+    fn modulation_to_minor_uses_a_major_dominant() {
+        let result = modulations(&major(C), &minor(E));
+        assert_eq!(result[0].to_string(), "pivot chord: C Am B7 Em");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::chord::symbol::chord_from_semitones;
+use crate::chord::ChordSymbol;
+use crate::interval::Interval;
+use crate::note::Note;
+
+/// Which note of a mirrored chord to call its root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NegativeRoot {
+    /// The mirror of the original fifth, so that a major triad turns into the minor triad with the same notes upside
+    /// down: C → Cm, G7 → Fm6 in C.
+    Fifth,
+    /// The note the mirrored chord stacks up in thirds from: G7 → Dm7b5 in C. Falls back to `Fifth` when the notes
+    /// don't form a stack of thirds.
+    Tertian,
+}
+
+/// Mirror a note around the axis between the tonic and the fifth of a key, so that in C, C and G, D and F, E and Eb
+/// swap places.
+pub fn negative_note(tonic: Note, note: Note) -> Note {
+    let axis = 2 * tonic.semitones_from_c() + Interval::PerfectFifth.semitones();
+    Note::C.transpose(axis as isize - note.semitones_from_c() as isize)
+}
+
+/// Mirror a chord around the tonic–fifth axis of a key. A slash bass is mirrored too.
+pub fn negative_chord(tonic: Note, symbol: &ChordSymbol, root: NegativeRoot) -> ChordSymbol {
+    let notes: Vec<Note> = symbol.notes().iter().map(|n| negative_note(tonic, *n)).collect();
+    let new_root = match root {
+        NegativeRoot::Tertian => tertian_root(&notes).unwrap_or_else(|| mirrored_fifth(tonic, symbol)),
+        NegativeRoot::Fifth => mirrored_fifth(tonic, symbol),
+    };
+    let semitones: Vec<usize> = notes.iter().map(|n| (*n - new_root).semitones()).collect();
+    ChordSymbol {
+        root: new_root,
+        chord: chord_from_semitones(&semitones),
+        bass: symbol.bass.map(|b| negative_note(tonic, b)),
+    }
+}
+
+/// Mirror every chord of a progression around the tonic–fifth axis of a key.
+pub fn negative_progression(tonic: Note, symbols: &[ChordSymbol], root: NegativeRoot) -> Vec<ChordSymbol> {
+    symbols.iter().map(|s| negative_chord(tonic, s, root)).collect()
+}
+
+/// The mirror of the chord's fifth, or of its altered fifth, or of its root when it has neither.
+fn mirrored_fifth(tonic: Note, symbol: &ChordSymbol) -> Note {
+    let semitones = symbol.chord.semitones();
+    let fifth = [7, 6, 8].into_iter().find(|s| semitones.contains(s)).unwrap_or(0);
+    negative_note(tonic, symbol.root.transpose(fifth as isize))
+}
+
+/// The note from which every other note is reached by stacking minor and major thirds.
+fn tertian_root(notes: &[Note]) -> Option<Note> {
+    notes.iter().copied().find(|root| {
+        let mut semitones: Vec<usize> = notes.iter().map(|n| (*n - *root).semitones()).collect();
+        semitones.sort();
+        semitones.dedup();
+        semitones.len() > 2 && semitones.windows(2).all(|w| matches!(w[1] - w[0], 3 | 4))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(C, C, G)]
+    #[case(C, D, F)]
+    #[case(C, E, Eb)]
+    #[case(C, B, Ab)]
+    #[case(C, A, Bb)]
+    #[case(C, Gb, Db)]
+    #[case(D, D, A)]
+    #[case(D, Gb, F)]
+    fn negative_notes(#[case] tonic: Note, #[case] note: Note, #[case] expected: Note) {
+        assert_eq!(negative_note(tonic, note), expected);
+        assert_eq!(negative_note(tonic, expected), note);
+    }
+
+    fn mirrored(tonic: Note, chord: &str, root: NegativeRoot) -> String {
+        negative_chord(tonic, &chord.parse().unwrap(), root).to_string()
+    }
+
+    #[rstest]
+    #[case(C, "C", "Cm", "Cm")]
+    #[case(C, "G7", "Fm6", "Dm7b5")]
+    #[case(C, "G", "Fm", "Fm")]
+    #[case(C, "Dm7", "Bb6", "Gm7")]
+    #[case(C, "Fmaj7", "Gmb6", "Ebmaj7")]
+    #[case(C, "Am", "Eb", "Eb")]
+    #[case(A, "E7", "Dm6", "Bm7b5")]
+    fn negative_chords(#[case] tonic: Note, #[case] chord: &str, #[case] fifth: &str, #[case] tertian: &str) {
+        assert_eq!(mirrored(tonic, chord, NegativeRoot::Fifth), fifth);
+        assert_eq!(mirrored(tonic, chord, NegativeRoot::Tertian), tertian);
+    }
+
+    #[test]
+    fn slash_bass_is_mirrored() {
+        assert_eq!(mirrored(C, "C/E", NegativeRoot::Fifth), "Cm/Eb");
+    }
+
+    #[test]
+    fn negative_ii_v_i() {
+        let progression: Vec<ChordSymbol> = ["Dm7", "G7", "Cmaj7"].iter().map(|c| c.parse().unwrap()).collect();
+        let result: Vec<String> = negative_progression(C, &progression, NegativeRoot::Tertian)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(result, vec!["Gm7", "Dm7b5", "Abmaj7"]);
+    }
+}
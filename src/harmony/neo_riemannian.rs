@@ -0,0 +1,334 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::{ChordSymbol, Triad as Quality};
+use crate::interval::Interval;
+use crate::note::Note;
+
+const CELL_WIDTH: usize = 6;
+
+/// A major or minor triad, the objects neo-Riemannian transformations act on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Triad {
+    pub root: Note,
+    pub quality: Quality,
+}
+
+impl Triad {
+    pub fn major(root: Note) -> Triad {
+        Triad {
+            root,
+            quality: Quality::Major,
+        }
+    }
+
+    pub fn minor(root: Note) -> Triad {
+        Triad {
+            root,
+            quality: Quality::Minor,
+        }
+    }
+
+    /// The triad on a root, if the quality is major or minor.
+    pub fn new(root: Note, quality: Quality) -> Option<Triad> {
+        matches!(quality, Quality::Major | Quality::Minor).then_some(Triad { root, quality })
+    }
+
+    pub fn is_major(&self) -> bool {
+        self.quality == Quality::Major
+    }
+
+    pub fn notes(&self) -> [Note; 3] {
+        self.quality.intervals().map(|i| self.root << i)
+    }
+
+    pub fn apply(&self, transformation: Transformation) -> Triad {
+        match transformation {
+            Transformation::P if self.is_major() => Triad::minor(self.root),
+            Transformation::P => Triad::major(self.root),
+            Transformation::R if self.is_major() => Triad::minor(self.root >> Interval::MinorThird),
+            Transformation::R => Triad::major(self.root << Interval::MinorThird),
+            Transformation::L if self.is_major() => Triad::minor(self.root << Interval::MajorThird),
+            Transformation::L => Triad::major(self.root >> Interval::MajorThird),
+            compound => compound.steps().iter().fold(*self, |triad, step| triad.apply(*step)),
+        }
+    }
+
+    /// Apply a chain of transformations from left to right.
+    pub fn apply_all(&self, transformations: &[Transformation]) -> Triad {
+        transformations.iter().fold(*self, |triad, t| triad.apply(*t))
+    }
+}
+
+impl fmt::Display for Triad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.root, if self.is_major() { "" } else { "m" })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Not a major or minor triad: {0}")]
+pub struct TriadParseError(String);
+
+impl FromStr for Triad {
+    type Err = TriadParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TriadParseError(s.to_string());
+        let symbol: ChordSymbol = s.parse().map_err(|_| error())?;
+        if symbol.bass.is_some() {
+            return Err(error());
+        }
+        match symbol.chord.semitones().as_slice() {
+            [0, 4, 7] => Ok(Triad::major(symbol.root)),
+            [0, 3, 7] => Ok(Triad::minor(symbol.root)),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// The neo-Riemannian transformations. `P`, `L` and `R` move one voice by a semitone or a whole tone while keeping the
+/// other two; the others are compounds of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transformation {
+    /// Parallel: C ↔ Cm.
+    P,
+    /// Leittonwechsel: C ↔ Em.
+    L,
+    /// Relative: C ↔ Am.
+    R,
+    /// Nebenverwandt, `RLP`: C ↔ Fm.
+    N,
+    /// Slide, `LPR`: C ↔ C#m.
+    S,
+    /// Hexatonic pole, `LPL`: C ↔ Abm.
+    H,
+}
+
+impl Transformation {
+    pub fn array() -> [Transformation; 6] {
+        [
+            Transformation::P,
+            Transformation::L,
+            Transformation::R,
+            Transformation::N,
+            Transformation::S,
+            Transformation::H,
+        ]
+    }
+
+    /// The `P`, `L` and `R` steps a transformation is made of, applied from left to right.
+    pub fn steps(&self) -> Vec<Transformation> {
+        use Transformation::*;
+        match self {
+            N => vec![R, L, P],
+            S => vec![L, P, R],
+            H => vec![L, P, L],
+            simple => vec![*simple],
+        }
+    }
+
+    /// Parse a chain of transformations written as letters, like `PLR` or `N S`.
+    pub fn parse_chain(s: &str) -> Result<Vec<Transformation>, TransformationParseError> {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c.to_ascii_uppercase() {
+                'P' => Ok(Transformation::P),
+                'L' => Ok(Transformation::L),
+                'R' => Ok(Transformation::R),
+                'N' => Ok(Transformation::N),
+                'S' => Ok(Transformation::S),
+                'H' => Ok(Transformation::H),
+                _ => Err(TransformationParseError(c)),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid transformation {0}")]
+pub struct TransformationParseError(char);
+
+impl fmt::Display for Transformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The shortest chain of transformations, taken from `allowed`, that leads from one triad to another. Among chains of
+/// the same length the one using transformations earlier in `allowed` wins. Returns `None` when `to` can't be reached.
+pub fn shortest_path(from: Triad, to: Triad, allowed: &[Transformation]) -> Option<Vec<Transformation>> {
+    let mut previous: HashMap<Triad, (Triad, Transformation)> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(triad) = queue.pop_front() {
+        if triad == to {
+            let mut path = vec![];
+            let mut current = to;
+            while current != from {
+                let (before, transformation) = previous[&current];
+                path.push(transformation);
+                current = before;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for transformation in allowed {
+            let next = triad.apply(*transformation);
+            if next != from && !previous.contains_key(&next) {
+                previous.insert(next, (triad, *transformation));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// An ASCII view of the Tonnetz around a triad, with the triad's notes in brackets.
+///
+/// Notes a fifth apart sit side by side on a row. Moving up and to the right is a major third, up and to the left a
+/// minor third down, so every major triad is a triangle pointing up and every minor triad one pointing down. `radius`
+/// is the number of rows shown above and below the triad.
+pub fn tonnetz(triad: Triad, radius: usize) -> String {
+    let radius = radius as isize;
+    let members: [(isize, isize); 3] = if triad.is_major() {
+        [(0, 0), (0, 1), (1, 0)]
+    } else {
+        [(0, 0), (0, 1), (-1, 1)]
+    };
+    let columns = 2 * radius.max(1);
+    let mut lines = vec![];
+    for row in (-radius..=radius).rev() {
+        let mut line = if row.rem_euclid(2) == 1 {
+            " ".repeat(CELL_WIDTH / 2)
+        } else {
+            String::new()
+        };
+        for j in -columns..=columns {
+            let column = j - row.div_euclid(2);
+            let note = triad.root.transpose(7 * column + 4 * row);
+            let cell = if members.contains(&(row, column)) {
+                format!("[{}]", note)
+            } else {
+                format!(" {} ", note)
+            };
+            line.push_str(&format!("{:<width$}", cell, width = CELL_WIDTH));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+    use Transformation::*;
+
+    #[rstest]
+    #[case(Triad::major(C), P, Triad::minor(C))]
+    #[case(Triad::major(C), L, Triad::minor(E))]
+    #[case(Triad::major(C), R, Triad::minor(A))]
+    #[case(Triad::major(C), N, Triad::minor(F))]
+    #[case(Triad::major(C), S, Triad::minor(Db))]
+    #[case(Triad::major(C), H, Triad::minor(Ab))]
+    #[case(Triad::minor(A), R, Triad::major(C))]
+    #[case(Triad::minor(E), L, Triad::major(C))]
+    #[case(Triad::minor(F), N, Triad::major(C))]
+    #[case(Triad::minor(Db), S, Triad::major(C))]
+    #[case(Triad::minor(Ab), H, Triad::major(C))]
+    fn transformations(#[case] triad: Triad, #[case] transformation: Transformation, #[case] expected: Triad) {
+        assert_eq!(triad.apply(transformation), expected);
+    }
+
+    #[test]
+    fn transformations_are_involutions() {
+        for root in [C, Db, D, Eb, E, F, Gb, G, Ab, A, Bb, B] {
+            for triad in [Triad::major(root), Triad::minor(root)] {
+                for transformation in Transformation::array() {
+                    assert_eq!(triad.apply(transformation).apply(transformation), triad);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simple_transformations_keep_two_common_tones() {
+        let triad = Triad::major(Eb);
+        for transformation in [P, L, R] {
+            let result = triad.apply(transformation);
+            let common = result.notes().iter().filter(|n| triad.notes().contains(n)).count();
+            assert_eq!(common, 2);
+        }
+    }
+
+    #[rstest]
+    #[case(Triad::major(C), Triad::major(C), vec![])]
+    #[case(Triad::major(C), Triad::minor(A), vec![R])]
+    #[case(Triad::major(C), Triad::major(Ab), vec![P, L])]
+    #[case(Triad::major(C), Triad::major(E), vec![L, P])]
+    #[case(Triad::major(C), Triad::major(Gb), vec![P, R, P, R])]
+    fn shortest_paths(#[case] from: Triad, #[case] to: Triad, #[case] expected: Vec<Transformation>) {
+        let path = shortest_path(from, to, &[P, L, R]).unwrap();
+        assert_eq!(path, expected);
+        assert_eq!(from.apply_all(&path), to);
+    }
+
+    #[test]
+    fn compound_transformations_shorten_paths() {
+        let path = shortest_path(Triad::major(C), Triad::minor(Db), &Transformation::array()).unwrap();
+        assert_eq!(path, vec![S]);
+    }
+
+    #[test]
+    fn unreachable_triads() {
+        assert_eq!(shortest_path(Triad::major(C), Triad::major(D), &[P]), None);
+    }
+
+    #[rstest]
+    #[case("C", Triad::major(C))]
+    #[case("Ebm", Triad::minor(Eb))]
+    #[case("F#min", Triad::minor(Gb))]
+    fn parse_triads(#[case] s: &str, #[case] expected: Triad) {
+        assert_eq!(s.parse::<Triad>().unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("Cdim")]
+    #[case("C7")]
+    #[case("C/E")]
+    fn invalid_triads(#[case] s: &str) {
+        assert!(s.parse::<Triad>().is_err());
+    }
+
+    #[test]
+    fn parse_chain() {
+        assert_eq!(Transformation::parse_chain("plr N").unwrap(), vec![P, L, R, N]);
+        assert!(Transformation::parse_chain("PX").is_err());
+    }
+
+    #[test]
+    fn tonnetz_around_major_triad() {
+        let expected = [
+            "    A     E    [B]    Gb    Db",
+            " F     C    [G]   [D]    A",
+            "    Ab    Eb    Bb    F     C",
+        ]
+        .join("\n");
+        assert_eq!(tonnetz(Triad::major(G), 1), expected);
+    }
+
+    #[test]
+    fn tonnetz_around_minor_triad() {
+        let expected = [
+            "    Db    Ab    Eb    Bb    F",
+            " A     E    [B]   [Gb]   Db",
+            "    C     G    [D]    A     E",
+        ]
+        .join("\n");
+        assert_eq!(tonnetz(Triad::minor(B), 1), expected);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::chord::ChordSymbol;
+use crate::harmony::roman::{Kind, RomanNumeral, RomanNumeralParseError};
+use crate::key::Key;
+use crate::note::Note;
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+/// Parse a whitespace separated progression like `ii7 V7 Imaj7`. Bar lines (`|`) and dashes are ignored.
+pub fn parse_progression(s: &str) -> Result<Vec<RomanNumeral>, RomanNumeralParseError> {
+    s.split(|c: char| c.is_whitespace() || c == '|' || c == '-')
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse())
+        .collect()
+}
+
+/// The concrete chord a Roman numeral stands for in a key.
+///
+/// Roots without an accidental are taken from the key's own scale, except that a diminished chord on the seventh
+/// degree of a natural minor key uses the raised leading tone. Applied chords are measured from the root of their
+/// target, in the major scale for major targets and the harmonic minor for minor ones.
+pub fn realize(key: &Key, numeral: &RomanNumeral) -> ChordSymbol {
+    let root = match (&numeral.target, numeral.kind) {
+        (_, Kind::AugmentedSixth(_)) => key.tonic.transpose(8),
+        (_, Kind::Neapolitan) => key.tonic.transpose(1),
+        (Some(target), _) => {
+            let target_root = realize(key, target).root;
+            let scale = if target.quality().is_major() {
+                Scale::Diatonic(diatonic::Mode::Ionian)
+            } else {
+                Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor)
+            };
+            degree_root(&Key::new(target_root, scale), numeral)
+        }
+        (None, _) => {
+            let leading_tone = numeral.degree == 7 && numeral.accidental == 0 && numeral.quality().is_diminished();
+            if leading_tone && key.scale == Scale::Diatonic(diatonic::Mode::Aeolian) {
+                key.tonic.transpose(11)
+            } else {
+                degree_root(key, numeral)
+            }
+        }
+    };
+    let bass = numeral
+        .chord
+        .intervals
+        .get(numeral.inversion)
+        .filter(|_| numeral.inversion > 0)
+        .map(|interval| interval.apply_to_note(root));
+    ChordSymbol {
+        root,
+        chord: numeral.chord.clone(),
+        bass,
+    }
+}
+
+/// Parse a progression and realize every numeral in a key.
+pub fn realize_progression(key: &Key, progression: &str) -> Result<Vec<ChordSymbol>, RomanNumeralParseError> {
+    Ok(parse_progression(progression)?
+        .iter()
+        .map(|n| realize(key, n))
+        .collect())
+}
+
+fn degree_root(key: &Key, numeral: &RomanNumeral) -> Note {
+    let semitones = key.scale.semitones()[(numeral.degree + 6) % 7] as isize;
+    key.tonic.transpose(semitones + numeral.accidental)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::notation::SpelledChord;
+    use crate::note::Note::*;
+
+    use super::*;
+
+    fn key(tonic: Note, scale: Scale) -> Key {
+        Key::new(tonic, scale)
+    }
+
+    const MAJOR: Scale = Scale::Diatonic(diatonic::Mode::Ionian);
+    const MINOR: Scale = Scale::Diatonic(diatonic::Mode::Aeolian);
+    const DORIAN: Scale = Scale::Diatonic(diatonic::Mode::Dorian);
+    const HARMONIC_MINOR: Scale = Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor);
+
+    fn chords(key: Key, progression: &str) -> Vec<String> {
+        realize_progression(&key, progression)
+            .unwrap()
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(key(C, MAJOR), "ii7 V7 Imaj7", vec!["Dm7", "G7", "Cmaj7"])]
+    #[case(key(Bb, MAJOR), "ii7 V7 Imaj7", vec!["Cm7", "F7", "Bbmaj7"])]
+    #[case(key(G, MAJOR), "I vi IV V", vec!["G", "Em", "C", "D"])]
+    #[case(key(A, MINOR), "i iv V7/V V7 i", vec!["Am", "Dm", "B7", "E7", "Am"])]
+    #[case(key(C, MAJOR), "bVII IV I", vec!["Bb", "F", "C"])]
+    #[case(key(D, MAJOR), "I | V6 | vi | iii64", vec!["D", "A/Db", "Bm", "Gbm/Db"])]
+    #[case(key(C, MAJOR), "vii°7/ii ii", vec!["Dbdim7", "Dm"])]
+    #[case(key(C, MINOR), "i vii°7 i", vec!["Cm", "Bdim7", "Cm"])]
+    #[case(key(C, HARMONIC_MINOR), "iiø7 V7 i", vec!["Dm7b5", "G7", "Cm"])]
+    #[case(key(D, DORIAN), "i7 IV7", vec!["Dm7", "G7"])]
+    #[case(key(C, MAJOR), "N6 V7 I", vec!["Db/F", "G7", "C"])]
+    #[case(key(A, MINOR), "Ger+6 V", vec!["F7", "E"])]
+    fn realized(#[case] key: Key, #[case] progression: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(chords(key, progression), expected);
+    }
+
+    #[test]
+    fn realizing_an_analysis_gives_back_the_chords() {
+        let key = key(Eb, MAJOR);
+        let progression = ["Cm7", "F7", "Bb7", "Ebmaj7", "Ab", "Db", "D7/Gb"];
+        for chord in progression {
+            let symbol: ChordSymbol = chord.parse().unwrap();
+            let numeral = crate::harmony::analyze(&key, &symbol);
+            assert_eq!(realize(&key, &numeral).to_string(), chord);
+        }
+    }
+
+    #[rstest]
+    #[case(key(E, MAJOR), "ii7 V7 Imaj7", vec!["F#m7", "B7", "Emaj7"])]
+    #[case(key(B, MAJOR), "I V7/V vi", vec!["B", "C#7", "G#m"])]
+    #[case(key(Gb, MINOR), "i iv V7", vec!["F#m", "Bm", "C#7"])]
+    #[case(key(Gb, MAJOR), "I IV V", vec!["Gb", "Cb", "Db"])]
+    #[case(key(D, MAJOR), "bVII IV I", vec!["C", "G", "D"])]
+    fn spelled_in_the_key(#[case] key: Key, #[case] progression: &str, #[case] expected: Vec<&str>) {
+        let chords: Vec<String> = realize_progression(&key, progression)
+            .unwrap()
+            .iter()
+            .map(|c| SpelledChord::new(c, Some(&key)).to_string())
+            .collect();
+        assert_eq!(chords, expected);
+    }
+
+    #[test]
+    fn invalid_progression() {
+        assert!(parse_progression("I IV X").is_err());
+    }
+}
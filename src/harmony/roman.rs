@@ -0,0 +1,462 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::symbol::chord_from_semitones;
+use crate::chord::Chord;
+use crate::interval::{canonical_interval, Interval};
+
+const TRIAD_FIGURES: [&str; 3] = ["", "⁶", "⁶₄"];
+const SEVENTH_FIGURES: [&str; 4] = ["⁷", "⁶₅", "⁴₃", "⁴₂"];
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// The tertian quality of a chord as far as Roman numeral analysis cares: tensions beyond the seventh are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Dominant7,
+    Major7,
+    Minor7,
+    MinorMajor7,
+    HalfDiminished7,
+    Diminished7,
+}
+
+impl Quality {
+    pub fn of(chord: &Chord) -> Quality {
+        let semitones = chord.semitones();
+        let has = |s: usize| semitones.contains(&s);
+        if has(4) {
+            match () {
+                _ if has(10) => Quality::Dominant7,
+                _ if has(11) => Quality::Major7,
+                _ if has(8) && !has(7) => Quality::Augmented,
+                _ => Quality::Major,
+            }
+        } else if has(3) {
+            match () {
+                _ if has(6) && !has(7) && has(9) && !has(10) => Quality::Diminished7,
+                _ if has(6) && !has(7) && has(10) => Quality::HalfDiminished7,
+                _ if has(6) && !has(7) => Quality::Diminished,
+                _ if has(10) => Quality::Minor7,
+                _ if has(11) => Quality::MinorMajor7,
+                _ => Quality::Minor,
+            }
+        } else if has(10) {
+            Quality::Dominant7
+        } else {
+            Quality::Major
+        }
+    }
+
+    pub fn chord(&self) -> Chord {
+        let semitones: &[usize] = match self {
+            Quality::Major => &[0, 4, 7],
+            Quality::Minor => &[0, 3, 7],
+            Quality::Diminished => &[0, 3, 6],
+            Quality::Augmented => &[0, 4, 8],
+            Quality::Dominant7 => &[0, 4, 7, 10],
+            Quality::Major7 => &[0, 4, 7, 11],
+            Quality::Minor7 => &[0, 3, 7, 10],
+            Quality::MinorMajor7 => &[0, 3, 7, 11],
+            Quality::HalfDiminished7 => &[0, 3, 6, 10],
+            Quality::Diminished7 => &[0, 3, 6, 9],
+        };
+        chord_from_semitones(semitones)
+    }
+
+    /// Whether the numeral is written in upper case, i.e. the chord has a major third.
+    pub fn is_major(&self) -> bool {
+        matches!(
+            self,
+            Quality::Major | Quality::Augmented | Quality::Dominant7 | Quality::Major7
+        )
+    }
+
+    pub fn is_diminished(&self) -> bool {
+        matches!(
+            self,
+            Quality::Diminished | Quality::HalfDiminished7 | Quality::Diminished7
+        )
+    }
+
+    pub fn has_seventh(&self) -> bool {
+        !matches!(
+            self,
+            Quality::Major | Quality::Minor | Quality::Diminished | Quality::Augmented
+        )
+    }
+
+    fn marker(&self) -> &'static str {
+        match self {
+            Quality::Diminished | Quality::Diminished7 => "°",
+            Quality::HalfDiminished7 => "ø",
+            Quality::Augmented => "+",
+            Quality::Major7 | Quality::MinorMajor7 => "M",
+            _ => "",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AugmentedSixth {
+    Italian,
+    French,
+    German,
+}
+
+impl AugmentedSixth {
+    /// The chord tones measured from the lowered sixth degree, which is the bass of the chord.
+    pub fn chord(&self) -> Chord {
+        let semitones: &[usize] = match self {
+            AugmentedSixth::Italian => &[0, 4, 10],
+            AugmentedSixth::French => &[0, 4, 6, 10],
+            AugmentedSixth::German => &[0, 4, 7, 10],
+        };
+        Chord {
+            intervals: semitones
+                .iter()
+                .map(|s| match s {
+                    10 => Interval::AugmentedSixth,
+                    6 => Interval::AugmentedFourth,
+                    s => canonical_interval(*s),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// How a chord relates to the key it is analysed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Diatonic,
+    /// A secondary (applied) dominant or leading-tone chord, like `V⁷/V`.
+    Secondary,
+    /// A chord borrowed from the parallel major or minor, like `bVI` in a major key.
+    Borrowed,
+    Neapolitan,
+    AugmentedSixth(AugmentedSixth),
+    Chromatic,
+}
+
+/// A chord labelled by the scale degree of its root, like `ii⁶₅`, `V⁷/V` or `bVI`.
+///
+/// `degree` counts from 1 and `accidental` raises or lowers the root relative to that degree of the key's scale.
+/// `inversion` is 0 for root position, 1 for first inversion and so on. `target` is the chord a secondary dominant or
+/// leading-tone chord is applied to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RomanNumeral {
+    pub accidental: isize,
+    pub degree: usize,
+    pub chord: Chord,
+    pub inversion: usize,
+    pub target: Option<Box<RomanNumeral>>,
+    pub kind: Kind,
+}
+
+impl RomanNumeral {
+    pub fn quality(&self) -> Quality {
+        Quality::of(&self.chord)
+    }
+
+    fn figure(&self) -> &'static str {
+        if self.quality().has_seventh() {
+            SEVENTH_FIGURES[self.inversion % 4]
+        } else {
+            TRIAD_FIGURES.get(self.inversion).copied().unwrap_or("")
+        }
+    }
+}
+
+impl fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Kind::AugmentedSixth(AugmentedSixth::Italian) => return write!(f, "It+6"),
+            Kind::AugmentedSixth(AugmentedSixth::French) => return write!(f, "Fr+6"),
+            Kind::AugmentedSixth(AugmentedSixth::German) => return write!(f, "Ger+6"),
+            Kind::Neapolitan => return write!(f, "N{}", self.figure()),
+            _ => {}
+        }
+        let quality = self.quality();
+        let accidental = if self.accidental < 0 { "b" } else { "#" }.repeat(self.accidental.unsigned_abs());
+        let numeral = NUMERALS[(self.degree + 6) % 7];
+        let numeral = if quality.is_major() {
+            numeral.to_string()
+        } else {
+            numeral.to_lowercase()
+        };
+        write!(f, "{}{}{}{}", accidental, numeral, quality.marker(), self.figure())?;
+        if let Some(target) = &self.target {
+            write!(f, "/{}", target)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid Roman numeral {0}")]
+pub struct RomanNumeralParseError(String);
+
+/// Parses numerals like `ii7`, `V⁶₅/V`, `bVII`, `viiø7`, `IM7`, `N6` or `Ger+6`. Figures may be written with plain
+/// digits (`V65`) or with superscripts. Since no key is involved, the kind of a parsed numeral only reflects its
+/// notation: applied chords are `Secondary`, altered roots `Chromatic` and everything else `Diatonic`.
+impl FromStr for RomanNumeral {
+    type Err = RomanNumeralParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RomanNumeralParseError(s.to_string());
+        let normalized: String = s
+            .chars()
+            .map(|c| match c {
+                '⁷' => '7',
+                '⁶' => '6',
+                '⁴' => '4',
+                '₅' => '5',
+                '₄' => '4',
+                '₃' => '3',
+                '₂' => '2',
+                '♭' => 'b',
+                '♯' => '#',
+                c => c,
+            })
+            .collect();
+
+        if let Some(special) = parse_special(&normalized) {
+            return Ok(special);
+        }
+
+        let (head, target) = match normalized.split_once('/') {
+            Some((head, target)) => (
+                head,
+                Some(Box::new(target.parse::<RomanNumeral>().map_err(|_| error())?)),
+            ),
+            None => (normalized.as_str(), None),
+        };
+
+        let accidentals = head.len() - head.trim_start_matches(['b', '#']).len();
+        let accidental: isize = head[..accidentals].chars().map(|c| if c == 'b' { -1 } else { 1 }).sum();
+        let head = &head[accidentals..];
+
+        let letters = head.len() - head.trim_start_matches(['I', 'V', 'i', 'v']).len();
+        let (numeral, rest) = head.split_at(letters);
+        let upper = numeral.chars().all(|c| c.is_ascii_uppercase());
+        if !upper && !numeral.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(error());
+        }
+        let degree = NUMERALS
+            .iter()
+            .position(|n| *n == numeral.to_uppercase())
+            .ok_or_else(error)?
+            + 1;
+
+        let markers = [
+            ("dim", "°"),
+            ("°", "°"),
+            ("o", "°"),
+            ("ø", "ø"),
+            ("%", "ø"),
+            ("aug", "+"),
+            ("+", "+"),
+            ("maj", "M"),
+            ("M", "M"),
+            ("Δ", "M"),
+        ];
+        let (marker, figure) = markers
+            .iter()
+            .find(|(spelling, _)| rest.starts_with(spelling))
+            .map(|(spelling, marker)| (*marker, &rest[spelling.len()..]))
+            .unwrap_or(("", rest));
+        let (seventh, inversion) = match figure {
+            "" => (false, 0),
+            "6" => (false, 1),
+            "64" => (false, 2),
+            "7" => (true, 0),
+            "65" => (true, 1),
+            "43" => (true, 2),
+            "42" | "2" => (true, 3),
+            _ => return Err(error()),
+        };
+        let seventh = seventh || matches!(marker, "ø" | "M");
+
+        let chord = match (marker, upper, seventh) {
+            ("°", _, false) => Quality::Diminished.chord(),
+            ("°", _, true) => Quality::Diminished7.chord(),
+            ("ø", _, _) => Quality::HalfDiminished7.chord(),
+            ("+", _, false) => Quality::Augmented.chord(),
+            ("+", _, true) => chord_from_semitones(&[0, 4, 8, 10]),
+            ("M", true, _) => Quality::Major7.chord(),
+            ("M", false, _) => Quality::MinorMajor7.chord(),
+            (_, true, false) => Quality::Major.chord(),
+            (_, true, true) => Quality::Dominant7.chord(),
+            (_, false, false) => Quality::Minor.chord(),
+            (_, false, true) => Quality::Minor7.chord(),
+        };
+        let kind = match (&target, accidental) {
+            (Some(_), _) => Kind::Secondary,
+            (None, 0) => Kind::Diatonic,
+            (None, _) => Kind::Chromatic,
+        };
+        Ok(RomanNumeral {
+            accidental,
+            degree,
+            chord,
+            inversion,
+            target,
+            kind,
+        })
+    }
+}
+
+fn parse_special(s: &str) -> Option<RomanNumeral> {
+    let numeral = |accidental, degree, chord, inversion, kind| RomanNumeral {
+        accidental,
+        degree,
+        chord,
+        inversion,
+        target: None,
+        kind,
+    };
+    let sixth = |name: &str| {
+        ["", "6", "+6", "+"]
+            .iter()
+            .any(|suffix| s == format!("{}{}", name, suffix))
+    };
+    let augmented_sixth = if sixth("It") {
+        Some(AugmentedSixth::Italian)
+    } else if sixth("Fr") {
+        Some(AugmentedSixth::French)
+    } else if sixth("Ger") || sixth("Gr") {
+        Some(AugmentedSixth::German)
+    } else {
+        None
+    };
+    if let Some(sixth) = augmented_sixth {
+        return Some(numeral(-1, 6, sixth.chord(), 0, Kind::AugmentedSixth(sixth)));
+    }
+    let inversion = match s {
+        "N" => 0,
+        "N6" => 1,
+        "N64" => 2,
+        _ => return None,
+    };
+    Some(numeral(-1, 2, Quality::Major.chord(), inversion, Kind::Neapolitan))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::symbol::parse_suffix;
+
+    use super::*;
+
+    fn numeral(accidental: isize, degree: usize, quality: Quality, inversion: usize) -> RomanNumeral {
+        RomanNumeral {
+            accidental,
+            degree,
+            chord: quality.chord(),
+            inversion,
+            target: None,
+            kind: Kind::Diatonic,
+        }
+    }
+
+    #[rstest]
+    #[case("", Quality::Major)]
+    #[case("m", Quality::Minor)]
+    #[case("dim", Quality::Diminished)]
+    #[case("aug", Quality::Augmented)]
+    #[case("7", Quality::Dominant7)]
+    #[case("9", Quality::Dominant7)]
+    #[case("7sus4", Quality::Dominant7)]
+    #[case("maj7", Quality::Major7)]
+    #[case("m7", Quality::Minor7)]
+    #[case("mMaj7", Quality::MinorMajor7)]
+    #[case("m7b5", Quality::HalfDiminished7)]
+    #[case("dim7", Quality::Diminished7)]
+    fn quality_of_chord(#[case] suffix: &str, #[case] expected: Quality) {
+        assert_eq!(Quality::of(&parse_suffix(suffix).unwrap()), expected);
+        assert_eq!(Quality::of(&expected.chord()), expected);
+    }
+
+    #[rstest]
+    #[case(numeral(0, 1, Quality::Major, 0), "I")]
+    #[case(numeral(0, 2, Quality::Minor7, 1), "ii⁶₅")]
+    #[case(numeral(0, 5, Quality::Dominant7, 3), "V⁴₂")]
+    #[case(numeral(0, 7, Quality::Diminished, 1), "vii°⁶")]
+    #[case(numeral(0, 7, Quality::HalfDiminished7, 0), "viiø⁷")]
+    #[case(numeral(0, 1, Quality::Major7, 0), "IM⁷")]
+    #[case(numeral(0, 3, Quality::Augmented, 0), "III+")]
+    #[case(numeral(-1, 6, Quality::Major, 0), "bVI")]
+    #[case(numeral(1, 4, Quality::Diminished7, 0), "#iv°⁷")]
+    #[case(numeral(0, 1, Quality::Major, 2), "I⁶₄")]
+    fn display(#[case] numeral: RomanNumeral, #[case] expected: &str) {
+        assert_eq!(numeral.to_string(), expected);
+    }
+
+    #[test]
+    fn display_special_chords() {
+        let mut applied = numeral(0, 7, Quality::Diminished7, 0);
+        applied.target = Some(Box::new(numeral(0, 2, Quality::Minor, 0)));
+        assert_eq!(applied.to_string(), "vii°⁷/ii");
+
+        let mut neapolitan = numeral(-1, 2, Quality::Major, 1);
+        neapolitan.kind = Kind::Neapolitan;
+        assert_eq!(neapolitan.to_string(), "N⁶");
+
+        let mut german = numeral(-1, 6, Quality::Dominant7, 0);
+        german.kind = Kind::AugmentedSixth(AugmentedSixth::German);
+        assert_eq!(german.to_string(), "Ger+6");
+    }
+
+    #[rstest]
+    #[case("I", "I")]
+    #[case("ii7", "ii⁷")]
+    #[case("ii65", "ii⁶₅")]
+    #[case("ii⁶₅", "ii⁶₅")]
+    #[case("V7/V", "V⁷/V")]
+    #[case("V42/IV", "V⁴₂/IV")]
+    #[case("vii°7/ii", "vii°⁷/ii")]
+    #[case("viio7", "vii°⁷")]
+    #[case("viiø7", "viiø⁷")]
+    #[case("Imaj7", "IM⁷")]
+    #[case("IM7", "IM⁷")]
+    #[case("iM7", "iM⁷")]
+    #[case("III+", "III+")]
+    #[case("bVII", "bVII")]
+    #[case("♭VI", "bVI")]
+    #[case("#iv°", "#iv°")]
+    #[case("I64", "I⁶₄")]
+    #[case("N6", "N⁶")]
+    #[case("It+6", "It+6")]
+    #[case("Fr6", "Fr+6")]
+    #[case("Ger+6", "Ger+6")]
+    fn parse_round_trip(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(input.parse::<RomanNumeral>().unwrap().to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("V7", 5, Quality::Dominant7, 0)]
+    #[case("ii6", 2, Quality::Minor, 1)]
+    #[case("vi", 6, Quality::Minor, 0)]
+    #[case("IV43", 4, Quality::Dominant7, 2)]
+    fn parse_fields(#[case] input: &str, #[case] degree: usize, #[case] quality: Quality, #[case] inversion: usize) {
+        let numeral: RomanNumeral = input.parse().unwrap();
+        assert_eq!(numeral.degree, degree);
+        assert_eq!(numeral.quality(), quality);
+        assert_eq!(numeral.inversion, inversion);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("Iv")]
+    #[case("VIII")]
+    #[case("X")]
+    #[case("V8")]
+    #[case("V/")]
+    fn parse_error(#[case] input: &str) {
+        assert!(input.parse::<RomanNumeral>().is_err());
+    }
+}
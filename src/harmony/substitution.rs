@@ -0,0 +1,310 @@
+use std::fmt;
+
+use crate::chord::ChordSymbol;
+use crate::harmony::roman::Quality;
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::note::Note;
+
+/// The kinds of substitution the engine knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Substitution {
+    /// A dominant seventh replaced by the dominant a tritone away, which shares its third and seventh.
+    TritoneSubstitute,
+    /// The chord approached by its own dominant seventh.
+    SecondaryDominant,
+    /// The chord approached by the ii–V of its own key. A dominant chord only gets the ii.
+    RelatedTwo,
+    /// The tonic approached by the dominant on the lowered seventh degree.
+    Backdoor,
+    /// The chord approached by the diminished seventh a half step below it.
+    DiminishedPassing,
+    /// A major chord replaced by its relative minor or a minor chord by its relative major.
+    Relative,
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Substitution::TritoneSubstitute => "tritone substitute",
+            Substitution::SecondaryDominant => "secondary dominant",
+            Substitution::RelatedTwo => "related ii",
+            Substitution::Backdoor => "backdoor dominant",
+            Substitution::DiminishedPassing => "diminished passing chord",
+            Substitution::Relative => "relative",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A candidate replacement for one chord: the chords to play in its place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Substitute {
+    pub kind: Substitution,
+    pub chords: Vec<ChordSymbol>,
+}
+
+impl fmt::Display for Substitute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<String> = self.chords.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}: {}", self.kind, chords.join(" "))
+    }
+}
+
+fn chord(root: Note, quality: Quality) -> ChordSymbol {
+    ChordSymbol::new(root, quality.chord())
+}
+
+fn dominant_of(target: Note) -> ChordSymbol {
+    chord(target << Interval::PerfectFifth, Quality::Dominant7)
+}
+
+/// The ii7 of a target chord's key: minor seventh for major targets, half-diminished for minor ones.
+fn related_two(target: Note, quality: Quality) -> ChordSymbol {
+    let two = if quality.is_major() {
+        Quality::Minor7
+    } else {
+        Quality::HalfDiminished7
+    };
+    chord(target << Interval::MajorSecond, two)
+}
+
+/// The quality of the diatonic triad on a note of the key, taken as major for notes outside it.
+fn diatonic_quality(key: &Key, note: Note) -> Quality {
+    key.triads()
+        .into_iter()
+        .find(|(root, _)| *root == note)
+        .map_or(Quality::Major, |(_, chord)| Quality::of(&chord))
+}
+
+/// Whether `chord` is a dominant resolving down a fifth to `target`.
+fn resolves_to(chord: &ChordSymbol, target: &ChordSymbol) -> bool {
+    Quality::of(&chord.chord) == Quality::Dominant7 && target.root - chord.root == Interval::PerfectFourth
+}
+
+/// Every substitution that applies to a chord in a key.
+///
+/// Dominant sevenths get a tritone substitute and their related ii. Other chords, except diminished ones, can be
+/// approached by their secondary dominant, its ii–V or a diminished seventh a half step below. The major tonic can
+/// also be approached from the backdoor, and chords can be swapped for their relative as long as it stays in the key.
+pub fn substitutions(key: &Key, symbol: &ChordSymbol) -> Vec<Substitute> {
+    let quality = Quality::of(&symbol.chord);
+    let root = symbol.root;
+    let mut substitutes = vec![];
+    let mut add = |kind: Substitution, chords: Vec<ChordSymbol>| substitutes.push(Substitute { kind, chords });
+
+    if quality == Quality::Dominant7 {
+        add(
+            Substitution::TritoneSubstitute,
+            vec![chord(root << Interval::Tritone, Quality::Dominant7)],
+        );
+        let target = root << Interval::PerfectFourth;
+        add(
+            Substitution::RelatedTwo,
+            vec![related_two(target, diatonic_quality(key, target)), symbol.clone()],
+        );
+        return substitutes;
+    }
+    if quality.is_diminished() {
+        return substitutes;
+    }
+
+    add(Substitution::SecondaryDominant, vec![dominant_of(root), symbol.clone()]);
+    add(
+        Substitution::RelatedTwo,
+        vec![related_two(root, quality), dominant_of(root), symbol.clone()],
+    );
+    if quality.is_major() && root == key.tonic {
+        add(
+            Substitution::Backdoor,
+            vec![
+                chord(root << Interval::MinorSeventh, Quality::Dominant7),
+                symbol.clone(),
+            ],
+        );
+    }
+    add(
+        Substitution::DiminishedPassing,
+        vec![
+            chord(root >> Interval::MinorSecond, Quality::Diminished7),
+            symbol.clone(),
+        ],
+    );
+
+    let relative = match quality {
+        Quality::Major => Some(chord(root >> Interval::MinorThird, Quality::Minor)),
+        Quality::Major7 => Some(chord(root >> Interval::MinorThird, Quality::Minor7)),
+        Quality::Minor => Some(chord(root << Interval::MinorThird, Quality::Major)),
+        Quality::Minor7 => Some(chord(root << Interval::MinorThird, Quality::Major7)),
+        _ => None,
+    };
+    if let Some(relative) = relative.filter(|r| r.notes().iter().all(|n| key.contains(*n))) {
+        add(Substitution::Relative, vec![relative]);
+    }
+    substitutes
+}
+
+/// Reharmonize a progression in a key.
+///
+/// A dominant seventh that resolves down a fifth is replaced by its tritone substitute and preceded by its related ii
+/// unless that chord is already there. Every other chord after the first that is not approached by a dominant gets one:
+/// the backdoor dominant for the tonic of a major key, its secondary dominant otherwise. Diminished chords are kept as
+/// they are.
+pub fn reharmonize(key: &Key, progression: &[ChordSymbol]) -> Vec<ChordSymbol> {
+    let mut result: Vec<ChordSymbol> = vec![];
+    for (i, symbol) in progression.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|p| &progression[p]);
+        let next = progression.get(i + 1);
+        let quality = Quality::of(&symbol.chord);
+
+        if let Some(target) = next.filter(|n| resolves_to(symbol, n)) {
+            let two = related_two(target.root, Quality::of(&target.chord));
+            let has_two = previous.is_some_and(|p| p.root == two.root && !Quality::of(&p.chord).is_major());
+            if !has_two {
+                result.push(two);
+            }
+            result.push(chord(symbol.root << Interval::Tritone, Quality::Dominant7));
+            continue;
+        }
+
+        let approached = previous.is_some_and(|p| resolves_to(p, symbol));
+        if i > 0 && !approached && !quality.is_diminished() && quality != Quality::Dominant7 {
+            if symbol.root == key.tonic && key.scale.is_major() && quality.is_major() {
+                result.push(chord(symbol.root << Interval::MinorSeventh, Quality::Dominant7));
+            } else {
+                result.push(dominant_of(symbol.root));
+            }
+        }
+        result.push(symbol.clone());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::notation::SpelledChord;
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, Scale};
+
+    use super::*;
+
+    fn major(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Ionian))
+    }
+
+    fn minor(tonic: Note) -> Key {
+        Key::new(tonic, Scale::Diatonic(diatonic::Mode::Aeolian))
+    }
+
+    fn candidates(key: Key, chord: &str) -> Vec<String> {
+        substitutions(&key, &chord.parse().unwrap())
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn spelled(key: &Key, chords: &[ChordSymbol]) -> String {
+        let names: Vec<String> = chords
+            .iter()
+            .map(|c| SpelledChord::new(c, Some(key)).to_string())
+            .collect();
+        names.join(" ")
+    }
+
+    fn chords(progression: &str) -> Vec<ChordSymbol> {
+        progression.split_whitespace().map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn dominant_substitutions() {
+        assert_eq!(
+            candidates(major(C), "G7"),
+            vec!["tritone substitute: Db7", "related ii: Dm7 G7"]
+        );
+    }
+
+    #[test]
+    fn tonic_substitutions() {
+        assert_eq!(
+            candidates(major(C), "Cmaj7"),
+            vec![
+                "secondary dominant: G7 Cmaj7",
+                "related ii: Dm7 G7 Cmaj7",
+                "backdoor dominant: Bb7 Cmaj7",
+                "diminished passing chord: Bdim7 Cmaj7",
+                "relative: Am7",
+            ]
+        );
+    }
+
+    #[test]
+    fn minor_chord_substitutions() {
+        assert_eq!(
+            candidates(major(C), "Dm"),
+            vec![
+                "secondary dominant: A7 Dm",
+                "related ii: Em7b5 A7 Dm",
+                "diminished passing chord: Dbdim7 Dm",
+                "relative: F",
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(major(C), "Ab", None)]
+    #[case(minor(A), "Am", Some("C"))]
+    #[case(major(C), "Dm7", Some("Fmaj7"))]
+    fn relative_swaps_stay_in_the_key(#[case] key: Key, #[case] chord: &str, #[case] expected: Option<&str>) {
+        let relative = substitutions(&key, &chord.parse().unwrap())
+            .into_iter()
+            .find(|s| s.kind == Substitution::Relative)
+            .map(|s| s.chords[0].to_string());
+        assert_eq!(relative.as_deref(), expected);
+    }
+
+    #[test]
+    fn spelled_in_a_sharp_key() {
+        let key = major(E);
+        let candidates: Vec<String> = substitutions(&key, &"C#m7".parse().unwrap())
+            .iter()
+            .map(|s| format!("{}: {}", s.kind, spelled(&key, &s.chords)))
+            .collect();
+        assert_eq!(
+            candidates,
+            vec![
+                "secondary dominant: G#7 C#m7",
+                "related ii: D#m7b5 G#7 C#m7",
+                "diminished passing chord: Cdim7 C#m7",
+                "relative: Emaj7",
+            ]
+        );
+        let reharmonized = reharmonize(&key, &chords("E C#m F#m B7 E"));
+        assert_eq!(spelled(&key, &reharmonized), "E G#7 C#m C#7 F#m F7 E");
+    }
+
+    #[test]
+    fn related_ii_of_a_dominant_to_a_minor_chord() {
+        assert_eq!(candidates(minor(A), "E7")[1], "related ii: Bm7b5 E7");
+    }
+
+    #[test]
+    fn diminished_chords_have_no_substitutions() {
+        assert!(candidates(major(C), "Bdim7").is_empty());
+    }
+
+    #[rstest]
+    #[case(major(C), "C Am Dm G7 C", "C E7 Am A7 Dm Db7 C")]
+    #[case(major(C), "C F C", "C C7 F Bb7 C")]
+    #[case(major(C), "C G7 C", "C Dm7 Db7 C")]
+    #[case(major(Bb), "Cm7 F7 Bbmaj7", "Cm7 B7 Bbmaj7")]
+    #[case(minor(A), "Am Dm E7 Am", "Am A7 Dm Bm7b5 Bb7 Am")]
+    fn reharmonized(#[case] key: Key, #[case] progression: &str, #[case] expected: &str) {
+        let result: Vec<String> = reharmonize(&key, &chords(progression))
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(result.join(" "), expected);
+    }
+}
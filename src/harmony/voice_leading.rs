@@ -0,0 +1,623 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::note::Note;
+use crate::pitch::Pitch;
+
+const PARALLEL_PENALTY: i32 = 100;
+const TENDENCY_PENALTY: i32 = 30;
+const OVERLAP_PENALTY: i32 = 20;
+const COMMON_TONE_PENALTY: i32 = 2;
+const DOUBLED_THIRD_PENALTY: i32 = 4;
+
+/// The four voices of a chorale texture, from lowest to highest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Voice {
+    Bass,
+    Tenor,
+    Alto,
+    Soprano,
+}
+
+impl Voice {
+    pub fn array() -> [Voice; 4] {
+        [Voice::Bass, Voice::Tenor, Voice::Alto, Voice::Soprano]
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Voice::Bass => 0,
+            Voice::Tenor => 1,
+            Voice::Alto => 2,
+            Voice::Soprano => 3,
+        }
+    }
+
+    /// The usual SATB range of the voice, as inclusive MIDI note numbers.
+    fn range(&self) -> (i32, i32) {
+        match self {
+            Voice::Bass => (40, 60),
+            Voice::Tenor => (48, 67),
+            Voice::Alto => (55, 74),
+            Voice::Soprano => (60, 79),
+        }
+    }
+
+    fn is_inner(&self) -> bool {
+        matches!(self, Voice::Tenor | Voice::Alto)
+    }
+}
+
+/// A four-part voicing of a chord, indexed from bass to soprano.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Voicing(pub [Pitch; 4]);
+
+impl Voicing {
+    pub fn pitch(&self, voice: Voice) -> Pitch {
+        self.0[voice.index()]
+    }
+
+    fn midi(&self) -> [i32; 4] {
+        self.0.map(|p| p.midi())
+    }
+}
+
+/// A part-writing problem found in a progression. `at` is the index of the chord where the problem shows up; for
+/// motion problems this is the chord being moved into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Issue {
+    ParallelFifths { at: usize, voices: (Voice, Voice) },
+    ParallelOctaves { at: usize, voices: (Voice, Voice) },
+    VoiceCrossing { at: usize, voices: (Voice, Voice) },
+    VoiceOverlap { at: usize, voices: (Voice, Voice) },
+    UnresolvedLeadingTone { at: usize, voice: Voice },
+    UnresolvedSeventh { at: usize, voice: Voice },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::ParallelFifths { at, voices: (a, b) } => {
+                write!(f, "chord {}: parallel fifths between {:?} and {:?}", at + 1, a, b)
+            }
+            Issue::ParallelOctaves { at, voices: (a, b) } => {
+                write!(f, "chord {}: parallel octaves between {:?} and {:?}", at + 1, a, b)
+            }
+            Issue::VoiceCrossing { at, voices: (a, b) } => {
+                write!(f, "chord {}: {:?} crosses above {:?}", at + 1, a, b)
+            }
+            Issue::VoiceOverlap { at, voices: (a, b) } => {
+                write!(f, "chord {}: {:?} and {:?} overlap", at + 1, a, b)
+            }
+            Issue::UnresolvedLeadingTone { at, voice } => {
+                write!(f, "chord {}: leading tone in {:?} does not resolve", at + 1, voice)
+            }
+            Issue::UnresolvedSeventh { at, voice } => {
+                write!(
+                    f,
+                    "chord {}: seventh in {:?} does not resolve down by step",
+                    at + 1,
+                    voice
+                )
+            }
+        }
+    }
+}
+
+/// The result of voice leading a progression: one voicing per chord and any problems left in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiceLeading {
+    pub voicings: Vec<Voicing>,
+    pub issues: Vec<Issue>,
+}
+
+impl VoiceLeading {
+    /// The melodic line sung by a single voice across the whole progression.
+    pub fn line(&self, voice: Voice) -> Vec<Pitch> {
+        self.voicings.iter().map(|v| v.pitch(voice)).collect()
+    }
+}
+
+impl fmt::Display for VoiceLeading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for voice in Voice::array().iter().rev() {
+            let line: Vec<String> = self
+                .line(*voice)
+                .iter()
+                .map(|p| format!("{:<4}", p.to_string()))
+                .collect();
+            writeln!(f, "{:<9}{}", format!("{:?}:", voice), line.join(" ").trim_end())?;
+        }
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find four-part voicings for a progression of `(root, chord)` pairs in a key that minimise the total movement of the
+/// voices, keeping common tones, resolving leading tones and sevenths and avoiding parallel perfect intervals where
+/// possible.
+///
+/// Chords are voiced in root position. Returns `None` if some chord can't be voiced within the SATB ranges.
+pub fn voice_lead(key: &Key, progression: &[(Note, Chord)]) -> Option<VoiceLeading> {
+    let candidates: Vec<Vec<Voicing>> = progression.iter().map(|(root, chord)| voicings(*root, chord)).collect();
+    if candidates.iter().any(|c| c.is_empty()) {
+        return None;
+    }
+    if candidates.is_empty() {
+        return Some(VoiceLeading {
+            voicings: vec![],
+            issues: vec![],
+        });
+    }
+
+    let mut costs: Vec<i32> = candidates[0]
+        .iter()
+        .map(|v| static_cost(progression[0].0, &progression[0].1, v) + spread_cost(v))
+        .collect();
+    let mut back: Vec<Vec<usize>> = vec![];
+
+    for i in 1..progression.len() {
+        let mut next_costs = vec![];
+        let mut pointers = vec![];
+        for to in &candidates[i] {
+            let (best, cost) = candidates[i - 1]
+                .iter()
+                .enumerate()
+                .map(|(j, from)| {
+                    (
+                        j,
+                        costs[j] + transition_cost(key, &progression[i - 1], &progression[i], from, to),
+                    )
+                })
+                .min_by_key(|(_, cost)| *cost)?;
+            next_costs.push(cost + static_cost(progression[i].0, &progression[i].1, to));
+            pointers.push(best);
+        }
+        costs = next_costs;
+        back.push(pointers);
+    }
+
+    let mut index = (0..costs.len()).min_by_key(|i| costs[*i])?;
+    let mut chosen = vec![candidates[progression.len() - 1][index]];
+    for (i, pointers) in back.iter().enumerate().rev() {
+        index = pointers[index];
+        chosen.push(candidates[i][index]);
+    }
+    chosen.reverse();
+
+    let issues = check(key, progression, &chosen);
+    Some(VoiceLeading {
+        voicings: chosen,
+        issues,
+    })
+}
+
+/// Check an existing four-part realisation of a progression in a key for part-writing problems.
+pub fn check(key: &Key, progression: &[(Note, Chord)], voicings: &[Voicing]) -> Vec<Issue> {
+    let mut issues = vec![];
+    for (at, voicing) in voicings.iter().enumerate() {
+        for (lower, upper) in voice_pairs() {
+            if voicing.pitch(lower) > voicing.pitch(upper) {
+                issues.push(Issue::VoiceCrossing {
+                    at,
+                    voices: (lower, upper),
+                });
+            }
+        }
+        if at == 0 {
+            continue;
+        }
+        let from = &voicings[at - 1];
+        issues.extend(motion_issues(at, from, voicing));
+        if let (Some(prev), Some(next)) = (progression.get(at - 1), progression.get(at)) {
+            issues.extend(tendency_issues(key, at, prev, next, from, voicing));
+        }
+    }
+    issues
+}
+
+fn voice_pairs() -> Vec<(Voice, Voice)> {
+    let voices = Voice::array();
+    let mut pairs = vec![];
+    for (i, lower) in voices.iter().enumerate() {
+        for upper in &voices[i + 1..] {
+            pairs.push((*lower, *upper));
+        }
+    }
+    pairs
+}
+
+fn motion_issues(at: usize, from: &Voicing, to: &Voicing) -> Vec<Issue> {
+    let mut issues = vec![];
+    let (a, b) = (from.midi(), to.midi());
+    for (lower, upper) in voice_pairs() {
+        let (l, u) = (lower.index(), upper.index());
+        let moved = a[l] != b[l] && a[u] != b[u];
+        let before = (a[u] - a[l]).rem_euclid(12);
+        let after = (b[u] - b[l]).rem_euclid(12);
+        if moved && before == after {
+            match after {
+                7 => issues.push(Issue::ParallelFifths {
+                    at,
+                    voices: (lower, upper),
+                }),
+                0 => issues.push(Issue::ParallelOctaves {
+                    at,
+                    voices: (lower, upper),
+                }),
+                _ => {}
+            }
+        }
+        if b[l] > a[u] || b[u] < a[l] {
+            issues.push(Issue::VoiceOverlap {
+                at,
+                voices: (lower, upper),
+            });
+        }
+    }
+    issues
+}
+
+fn tendency_issues(
+    key: &Key,
+    at: usize,
+    prev: &(Note, Chord),
+    next: &(Note, Chord),
+    from: &Voicing,
+    to: &Voicing,
+) -> Vec<Issue> {
+    let mut issues = vec![];
+    let (prev_root, prev_chord) = prev;
+    if *prev_root == next.0 {
+        return issues;
+    }
+    let leading_tone = leading_tone(key, prev, next);
+    let sevenths = sevenths(*prev_root, prev_chord);
+
+    for voice in Voice::array() {
+        let (a, b) = (from.pitch(voice).midi(), to.pitch(voice).midi());
+        let pc = a.rem_euclid(12) as usize;
+        if Some(pc) == leading_tone {
+            let resolved = b - a == 1 || (voice.is_inner() && (3..=4).contains(&(a - b)));
+            if !resolved {
+                issues.push(Issue::UnresolvedLeadingTone { at, voice });
+            }
+        } else if sevenths.contains(&pc) && !(1..=2).contains(&(a - b)) {
+            issues.push(Issue::UnresolvedSeventh { at, voice });
+        }
+    }
+    issues
+}
+
+/// The pitch class of the key's leading tone, a semitone below the tonic and so raised in minor keys, when it should
+/// rise to the tonic: it is the third of a dominant or the root of a leading-tone chord, and the next chord holds the
+/// tonic.
+fn leading_tone(key: &Key, (root, chord): &(Note, Chord), (next_root, next_chord): &(Note, Chord)) -> Option<usize> {
+    let tonic = key.tonic.semitones_from_c();
+    let leading_tone = (tonic + 11) % 12;
+    let dominant = matches!((root.semitones_from_c() + 12 - tonic) % 12, 7 | 11);
+    let resolves = dominant
+        && pitch_classes(*root, chord).contains(&leading_tone)
+        && pitch_classes(*next_root, next_chord).contains(&tonic);
+    resolves.then_some(leading_tone)
+}
+
+fn sevenths(root: Note, chord: &Chord) -> Vec<usize> {
+    chord
+        .intervals
+        .iter()
+        .filter(|i| {
+            matches!(
+                i,
+                Interval::MinorSeventh | Interval::MajorSeventh | Interval::DiminishedSeventh
+            )
+        })
+        .map(|i| (root.semitones_from_c() + i.semitones()) % 12)
+        .collect()
+}
+
+fn pitch_classes(root: Note, chord: &Chord) -> Vec<usize> {
+    let mut pcs = vec![];
+    for pc in chord.apply_to(root).iter().map(|n| n.semitones_from_c()) {
+        if !pcs.contains(&pc) {
+            pcs.push(pc);
+        }
+    }
+    pcs
+}
+
+/// The pitch classes a voicing must contain: every chord tone, except that the fifth may be left out of chords with
+/// four or more tones and extensions beyond the fourth tone are dropped.
+fn required_pitch_classes(root: Note, pcs: &[usize]) -> Vec<usize> {
+    let fifth = (root.semitones_from_c() + 7) % 12;
+    let mut required: Vec<usize> = pcs.to_vec();
+    if required.len() >= 4 {
+        required.retain(|pc| *pc != fifth);
+    }
+    required.truncate(4);
+    required
+}
+
+fn voicings(root: Note, chord: &Chord) -> Vec<Voicing> {
+    let pcs = pitch_classes(root, chord);
+    let required = required_pitch_classes(root, &pcs);
+    let allowed: Vec<usize> = if pcs.len() > 4 { required.clone() } else { pcs.clone() };
+    let in_chord = |midi: i32| allowed.contains(&(midi.rem_euclid(12) as usize));
+    let range = |voice: Voice| {
+        let (low, high) = voice.range();
+        low..=high
+    };
+
+    let mut result = vec![];
+    for b in range(Voice::Bass).filter(|m| m.rem_euclid(12) as usize == root.semitones_from_c()) {
+        for t in range(Voice::Tenor).filter(|m| *m >= b && in_chord(*m)) {
+            for a in range(Voice::Alto).filter(|m| *m >= t && *m - t <= 12 && in_chord(*m)) {
+                for s in range(Voice::Soprano).filter(|m| *m >= a && *m - a <= 12 && in_chord(*m)) {
+                    let midi = [b, t, a, s];
+                    let covered = required
+                        .iter()
+                        .all(|pc| midi.iter().any(|m| m.rem_euclid(12) as usize == *pc));
+                    if covered {
+                        result.push(Voicing(midi.map(Pitch::from_midi)));
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+fn static_cost(root: Note, chord: &Chord, voicing: &Voicing) -> i32 {
+    let third = chord
+        .intervals
+        .iter()
+        .find(|i| (3..=4).contains(&i.semitones()))
+        .map(|i| (root.semitones_from_c() + i.semitones()) % 12);
+    let doubled_third = third.is_some_and(|pc| {
+        voicing
+            .midi()
+            .iter()
+            .filter(|m| m.rem_euclid(12) as usize == pc)
+            .count()
+            > 1
+    });
+    if doubled_third {
+        DOUBLED_THIRD_PENALTY
+    } else {
+        0
+    }
+}
+
+/// Prefer an opening voicing with every voice near the middle of its range.
+fn spread_cost(voicing: &Voicing) -> i32 {
+    Voice::array()
+        .iter()
+        .map(|v| {
+            let (low, high) = v.range();
+            (voicing.pitch(*v).midi() - (low + high) / 2).abs()
+        })
+        .sum()
+}
+
+fn transition_cost(key: &Key, prev: &(Note, Chord), next: &(Note, Chord), from: &Voicing, to: &Voicing) -> i32 {
+    let (a, b) = (from.midi(), to.midi());
+    let movement: i32 = a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum();
+
+    let next_pcs = pitch_classes(next.0, &next.1);
+    let dropped_common_tones = a
+        .iter()
+        .zip(b.iter())
+        .filter(|(x, y)| next_pcs.contains(&(x.rem_euclid(12) as usize)) && x != y)
+        .count() as i32;
+
+    let penalties: i32 = motion_issues(1, from, to)
+        .iter()
+        .chain(tendency_issues(key, 1, prev, next, from, to).iter())
+        .map(|issue| match issue {
+            Issue::ParallelFifths { .. } | Issue::ParallelOctaves { .. } => PARALLEL_PENALTY,
+            Issue::VoiceOverlap { .. } | Issue::VoiceCrossing { .. } => OVERLAP_PENALTY,
+            Issue::UnresolvedLeadingTone { .. } | Issue::UnresolvedSeventh { .. } => TENDENCY_PENALTY,
+        })
+        .sum();
+
+    movement + dropped_common_tones * COMMON_TONE_PENALTY + penalties
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::{Tetrad, Triad};
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, Scale};
+
+    use super::*;
+
+    const C_MAJOR: Key = Key {
+        tonic: C,
+        scale: Scale::Diatonic(diatonic::Mode::Ionian),
+    };
+
+    fn triad(root: Note, triad: Triad) -> (Note, Chord) {
+        (
+            root,
+            Chord {
+                intervals: triad.intervals().to_vec(),
+            },
+        )
+    }
+
+    fn tetrad(root: Note, tetrad: Tetrad) -> (Note, Chord) {
+        (
+            root,
+            Chord {
+                intervals: tetrad.intervals().to_vec(),
+            },
+        )
+    }
+
+    fn voicing(pitches: [(Note, i8); 4]) -> Voicing {
+        Voicing(pitches.map(|(n, o)| Pitch::new(n, o)))
+    }
+
+    #[test]
+    fn cadence_is_voiced_without_issues() {
+        let progression = vec![
+            triad(C, Triad::Major),
+            triad(F, Triad::Major),
+            tetrad(G, Tetrad::Dominant),
+            triad(C, Triad::Major),
+        ];
+        let result = voice_lead(&C_MAJOR, &progression).unwrap();
+        assert_eq!(result.voicings.len(), 4);
+        assert_eq!(result.issues, vec![]);
+        assert!(result
+            .line(Voice::Bass)
+            .iter()
+            .zip([C, F, G, C])
+            .all(|(p, n)| p.note == n));
+    }
+
+    #[test]
+    fn seventh_and_leading_tone_resolve() {
+        let progression = vec![tetrad(G, Tetrad::Dominant), triad(C, Triad::Major)];
+        let result = voice_lead(&C_MAJOR, &progression).unwrap();
+        let (from, to) = (result.voicings[0], result.voicings[1]);
+        for voice in Voice::array() {
+            let (a, b) = (from.pitch(voice), to.pitch(voice));
+            if a.note == F {
+                assert_eq!(b, a.transpose(-1));
+            }
+            if a.note == B && !voice.is_inner() {
+                assert_eq!(b, a.transpose(1));
+            }
+        }
+    }
+
+    #[test]
+    fn common_tones_are_kept() {
+        let progression = vec![triad(C, Triad::Major), triad(A, Triad::Minor)];
+        let result = voice_lead(&C_MAJOR, &progression).unwrap();
+        let (from, to) = (result.voicings[0], result.voicings[1]);
+        let upper = [Voice::Tenor, Voice::Alto, Voice::Soprano];
+        for voice in upper {
+            if matches!(from.pitch(voice).note, C | E) {
+                assert_eq!(from.pitch(voice), to.pitch(voice));
+            }
+        }
+    }
+
+    #[test]
+    fn every_voice_stays_in_range() {
+        let progression = vec![
+            tetrad(D, Tetrad::Minor7),
+            tetrad(G, Tetrad::Dominant),
+            tetrad(C, Tetrad::Major7),
+        ];
+        let result = voice_lead(&C_MAJOR, &progression).unwrap();
+        for voicing in &result.voicings {
+            for voice in Voice::array() {
+                let (low, high) = voice.range();
+                assert!((low..=high).contains(&voicing.pitch(voice).midi()));
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(
+        voicing([(C, 3), (G, 3), (C, 4), (E, 4)]),
+        voicing([(D, 3), (A, 3), (D, 4), (F, 4)]),
+        vec![
+            Issue::ParallelFifths { at: 1, voices: (Voice::Bass, Voice::Tenor) },
+            Issue::ParallelOctaves { at: 1, voices: (Voice::Bass, Voice::Alto) },
+        ]
+    )]
+    #[case(
+        voicing([(C, 3), (G, 3), (C, 4), (E, 4)]),
+        voicing([(C, 3), (D, 4), (E, 4), (G, 4)]),
+        vec![Issue::VoiceOverlap { at: 1, voices: (Voice::Tenor, Voice::Alto) }]
+    )]
+    fn motion_problems(#[case] from: Voicing, #[case] to: Voicing, #[case] expected: Vec<Issue>) {
+        let progression = vec![triad(C, Triad::Major), triad(D, Triad::Minor)];
+        let issues = check(&C_MAJOR, &progression, &[from, to]);
+        assert_eq!(issues, expected);
+    }
+
+    #[test]
+    fn crossing_is_reported() {
+        let progression = vec![triad(C, Triad::Major)];
+        let issues = check(&C_MAJOR, &progression, &[voicing([(C, 3), (E, 4), (G, 3), (C, 5)])]);
+        assert_eq!(
+            issues,
+            vec![Issue::VoiceCrossing {
+                at: 0,
+                voices: (Voice::Tenor, Voice::Alto)
+            }]
+        );
+    }
+
+    #[test]
+    fn unresolved_tendency_tones_are_reported() {
+        let progression = vec![tetrad(G, Tetrad::Dominant), triad(C, Triad::Major)];
+        let from = voicing([(G, 2), (F, 3), (B, 3), (D, 4)]);
+        let to = voicing([(C, 3), (G, 3), (C, 4), (E, 4)]);
+        assert_eq!(
+            check(&C_MAJOR, &progression, &[from, to]),
+            vec![Issue::UnresolvedSeventh {
+                at: 1,
+                voice: Voice::Tenor
+            }]
+        );
+
+        let from = voicing([(G, 2), (F, 3), (D, 4), (B, 4)]);
+        let to = voicing([(C, 3), (E, 3), (C, 4), (G, 4)]);
+        assert_eq!(
+            check(&C_MAJOR, &progression, &[from, to]),
+            vec![Issue::UnresolvedLeadingTone {
+                at: 1,
+                voice: Voice::Soprano
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case(
+        C_MAJOR,
+        vec![tetrad(D, Tetrad::Dominant), triad(G, Triad::Major)],
+        voicing([(D, 3), (A, 3), (C, 4), (Gb, 4)]),
+        voicing([(G, 2), (B, 3), (B, 3), (D, 4)]),
+        vec![]
+    )]
+    #[case(
+        Key::new(A, Scale::Diatonic(diatonic::Mode::Aeolian)),
+        vec![tetrad(E, Tetrad::Dominant), triad(A, Triad::Minor)],
+        voicing([(E, 3), (B, 3), (D, 4), (Ab, 4)]),
+        voicing([(A, 2), (C, 4), (C, 4), (E, 4)]),
+        vec![Issue::UnresolvedLeadingTone { at: 1, voice: Voice::Soprano }]
+    )]
+    fn leading_tone_of_the_key(
+        #[case] key: Key,
+        #[case] progression: Vec<(Note, Chord)>,
+        #[case] from: Voicing,
+        #[case] to: Voicing,
+        #[case] expected: Vec<Issue>,
+    ) {
+        assert_eq!(check(&key, &progression, &[from, to]), expected);
+    }
+
+    #[test]
+    fn display_prints_one_line_per_voice() {
+        let result = VoiceLeading {
+            voicings: vec![voicing([(C, 3), (G, 3), (C, 4), (E, 4)])],
+            issues: vec![],
+        };
+        assert_eq!(
+            result.to_string(),
+            "Soprano: E4\nAlto:    C4\nTenor:   G3\nBass:    C3\n"
+        );
+    }
+}
@@ -84,6 +84,57 @@ impl Interval {
         }
     }
 
+    /// The number of letter names the interval spans, counting both ends: 1 for a unison, 3 for any third. The tritone
+    /// counts as an augmented fourth.
+    pub fn number(&self) -> usize {
+        match self {
+            Interval::PerfectUnison | Interval::AugmentedUnison => 1,
+            Interval::MinorSecond | Interval::MajorSecond | Interval::DiminishedSecond | Interval::AugmentedSecond => 2,
+            Interval::MinorThird | Interval::MajorThird | Interval::DiminishedThird | Interval::AugmentedThird => 3,
+            Interval::PerfectFourth | Interval::Tritone | Interval::DiminishedFourth | Interval::AugmentedFourth => 4,
+            Interval::PerfectFifth | Interval::DiminishedFifth | Interval::AugmentedFifth => 5,
+            Interval::MinorSixth | Interval::MajorSixth | Interval::DiminishedSixth | Interval::AugmentedSixth => 6,
+            Interval::MinorSeventh
+            | Interval::MajorSeventh
+            | Interval::DiminishedSeventh
+            | Interval::AugmentedSeventh => 7,
+            Interval::PerfectOctave | Interval::DiminishedOctave => 8,
+        }
+    }
+
+    /// The short name of the interval, like `m3`, `P5` or `A4`. The tritone is `TT`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Interval::PerfectUnison => "P1",
+            Interval::MinorSecond => "m2",
+            Interval::MajorSecond => "M2",
+            Interval::MinorThird => "m3",
+            Interval::MajorThird => "M3",
+            Interval::PerfectFourth => "P4",
+            Interval::PerfectFifth => "P5",
+            Interval::MinorSixth => "m6",
+            Interval::MajorSixth => "M6",
+            Interval::MinorSeventh => "m7",
+            Interval::MajorSeventh => "M7",
+            Interval::PerfectOctave => "P8",
+            Interval::Tritone => "TT",
+            Interval::DiminishedSecond => "d2",
+            Interval::DiminishedThird => "d3",
+            Interval::DiminishedFourth => "d4",
+            Interval::DiminishedFifth => "d5",
+            Interval::DiminishedSixth => "d6",
+            Interval::DiminishedSeventh => "d7",
+            Interval::DiminishedOctave => "d8",
+            Interval::AugmentedUnison => "A1",
+            Interval::AugmentedSecond => "A2",
+            Interval::AugmentedThird => "A3",
+            Interval::AugmentedFourth => "A4",
+            Interval::AugmentedFifth => "A5",
+            Interval::AugmentedSixth => "A6",
+            Interval::AugmentedSeventh => "A7",
+        }
+    }
+
     pub fn apply_to_note(&self, note: Note) -> Note {
         note.transpose(self.semitones() as isize)
     }
@@ -150,6 +201,28 @@ mod tests {
         assert_eq!(interv.apply_to_note(C), expected);
     }
 
+    #[rstest]
+    #[case(Interval::PerfectUnison, 1)]
+    #[case(Interval::AugmentedSecond, 2)]
+    #[case(Interval::DiminishedFourth, 4)]
+    #[case(Interval::Tritone, 4)]
+    #[case(Interval::DiminishedFifth, 5)]
+    #[case(Interval::DiminishedSeventh, 7)]
+    #[case(Interval::PerfectOctave, 8)]
+    fn interval_number(#[case] interval: Interval, #[case] expected: usize) {
+        assert_eq!(interval.number(), expected);
+    }
+
+    #[rstest]
+    #[case(Interval::MinorThird, "m3")]
+    #[case(Interval::PerfectFifth, "P5")]
+    #[case(Interval::AugmentedFourth, "A4")]
+    #[case(Interval::Tritone, "TT")]
+    #[case(Interval::DiminishedSeventh, "d7")]
+    fn interval_abbreviation(#[case] interval: Interval, #[case] expected: &str) {
+        assert_eq!(interval.abbreviation(), expected);
+    }
+
     #[rstest]
     #[case(C, Interval::MajorThird, E)]
     #[case(C, Interval::PerfectFifth, G)]
@@ -0,0 +1,72 @@
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::note::Note;
+use crate::scales::{harmonize, Scale};
+
+/// A tonal centre: a tonic note together with the scale built on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub tonic: Note,
+    pub scale: Scale,
+}
+
+impl Key {
+    pub fn new(tonic: Note, scale: Scale) -> Key {
+        Key { tonic, scale }
+    }
+
+    /// The seven notes of the key, starting from the tonic.
+    pub fn notes(&self) -> [Note; 7] {
+        self.scale.intervals().map(|i| i.apply_to_note(self.tonic))
+    }
+
+    pub fn contains(&self, note: Note) -> bool {
+        self.notes().contains(&note)
+    }
+
+    /// The scale degree (1 to 7) of a note, if it belongs to the key.
+    pub fn degree_of(&self, note: Note) -> Option<usize> {
+        self.notes().iter().position(|n| *n == note).map(|i| i + 1)
+    }
+
+    pub fn triads(&self) -> Vec<(Note, Chord)> {
+        harmonize::triads(self.tonic, &self.scale.intervals())
+    }
+
+    pub fn sevenths(&self) -> Vec<(Note, Chord)> {
+        harmonize::sevenths(self.tonic, &self.scale.intervals())
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.tonic, self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, harmonic_minor};
+
+    use super::*;
+
+    #[test]
+    fn key_notes() {
+        let key = Key::new(D, Scale::Diatonic(diatonic::Mode::Ionian));
+        assert_eq!(key.notes(), [D, E, Gb, G, A, B, Db]);
+        assert_eq!(key.to_string(), "D major");
+    }
+
+    #[rstest]
+    #[case(Key::new(C, Scale::Diatonic(diatonic::Mode::Ionian)), G, Some(5))]
+    #[case(Key::new(C, Scale::Diatonic(diatonic::Mode::Ionian)), Ab, None)]
+    #[case(Key::new(A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor)), Ab, Some(7))]
+    fn degree_of(#[case] key: Key, #[case] note: Note, #[case] expected: Option<usize>) {
+        assert_eq!(key.degree_of(note), expected);
+        assert_eq!(key.contains(note), expected.is_some());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::note::Note;
+use crate::note::Note::*;
+
+/// The white keys of an octave from C.
+const WHITE: [Note; 7] = [C, D, E, F, G, A, B];
+
+/// The black key right of each white key, if there is one.
+const BLACK: [Option<Note>; 7] = [Some(Db), Some(Eb), None, Some(Gb), Some(Ab), Some(Bb), None];
+
+/// The columns taken by a white key and its left edge.
+const KEY_WIDTH: usize = 6;
+
+/// Highlighted keys: the first marked note, usually the tonic or root, and the other ones.
+const FIRST: &str = "\x1b[30;43m";
+const MARKED: &str = "\x1b[30;46m";
+const RESET: &str = "\x1b[0m";
+
+/// How to draw a keyboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    /// Box drawing characters instead of `+`, `-`, `|` and `#`.
+    pub unicode: bool,
+    /// Highlight the marked keys with ANSI colors.
+    pub color: bool,
+}
+
+/// A cell of the drawing and the color behind it.
+type Cell = (char, Option<&'static str>);
+
+/// Draw `octaves` octaves of a piano keyboard from C with a label on every key of the marked notes, like
+///
+/// ```text
+/// +---+---+-+---+---+---+---+-+---+-+---+---+
+/// |   |###| |   |   |   |###| |###| |###|   |
+/// |   |###| | 3 |   |   |###| |###| |###|   |
+/// |   +-+-+ +-+-+   |   +-+-+ +-+-+ +-+-+   |
+/// |  1  |     |     |     |  5  |     |     |
+/// +-----+-----+-----+-----+-----+-----+-----+
+/// ```
+///
+/// Labels are cut to five characters on white keys and three on black keys, and an empty label is drawn as `*`. With
+/// color, the first marked note stands out from the others.
+pub fn keyboard(marks: &[(Note, String)], octaves: usize, style: Style) -> String {
+    let keys = 7 * octaves.max(1);
+    let width = KEY_WIDTH * keys + 1;
+    let mut rows: Vec<Vec<Cell>> = vec![vec![(' ', None); width]; 6];
+    rows[0] = vec![('─', None); width];
+    rows[5] = vec![('─', None); width];
+    let mark = |note: Note| marks.iter().position(|(n, _)| *n == note);
+    let fill = |note: Note| {
+        mark(note)
+            .filter(|_| style.color)
+            .map(|index| if index == 0 { FIRST } else { MARKED })
+    };
+
+    for key in 0..keys {
+        let note = WHITE[key % 7];
+        let left = KEY_WIDTH * key;
+        rows[0][left].0 = '┬';
+        rows[5][left].0 = '┴';
+        for row in &mut rows[1..5] {
+            row[left].0 = '│';
+            for cell in &mut row[left + 1..left + KEY_WIDTH] {
+                *cell = (' ', fill(note));
+            }
+        }
+        if let Some(index) = mark(note) {
+            write_label(&mut rows[4][left + 1..left + KEY_WIDTH], &marks[index].1);
+        }
+    }
+    for row in &mut rows[1..5] {
+        row[width - 1].0 = '│';
+    }
+    rows[0][0].0 = '┌';
+    rows[0][width - 1].0 = '┐';
+    rows[5][0].0 = '└';
+    rows[5][width - 1].0 = '┘';
+
+    for key in 0..keys {
+        let Some(note) = BLACK[key % 7] else {
+            continue;
+        };
+        let left = KEY_WIDTH * key + 4;
+        rows[0][left].0 = '┬';
+        rows[0][left + 2].0 = '─';
+        rows[0][left + 4].0 = '┬';
+        for row in &mut rows[1..3] {
+            row[left] = ('│', None);
+            row[left + 4] = ('│', None);
+            for cell in &mut row[left + 1..left + 4] {
+                *cell = match mark(note) {
+                    Some(_) => (' ', fill(note)),
+                    None => ('█', None),
+                };
+            }
+        }
+        for (x, c) in (left..=left + 4).zip(['└', '─', '┬', '─', '┘']) {
+            rows[3][x] = (c, None);
+        }
+        if let Some(index) = mark(note) {
+            write_label(&mut rows[2][left + 1..left + 4], &marks[index].1);
+        }
+    }
+
+    let mut lines = vec![];
+    for row in rows {
+        let mut line = String::new();
+        let mut current = None;
+        for (c, color) in row {
+            if color != current {
+                line += color.unwrap_or(RESET);
+                current = color;
+            }
+            line.push(if style.unicode { c } else { ascii(c) });
+        }
+        if current.is_some() {
+            line += RESET;
+        }
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Center a label in the cells, keeping their colors.
+fn write_label(cells: &mut [Cell], label: &str) {
+    let label = if label.is_empty() { "●" } else { label };
+    let label: String = label.chars().take(cells.len()).collect();
+    let label = format!("{:^width$}", label, width = cells.len());
+    for (cell, c) in cells.iter_mut().zip(label.chars()) {
+        cell.0 = c;
+    }
+}
+
+fn ascii(c: char) -> char {
+    match c {
+        '┌' | '┐' | '└' | '┘' | '┬' | '┴' => '+',
+        '─' => '-',
+        '│' => '|',
+        '█' => '#',
+        '●' => '*',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn marks(notes: &[(Note, &str)]) -> Vec<(Note, String)> {
+        notes.iter().map(|(note, label)| (*note, label.to_string())).collect()
+    }
+
+    #[test]
+    fn c_minor_triad() {
+        let expected = "\
++---+---+-+---+---+---+---+-+---+-+---+---+
+|   |###| |   |   |   |###| |###| |###|   |
+|   |###| | 3 |   |   |###| |###| |###|   |
+|   +-+-+ +-+-+   |   +-+-+ +-+-+ +-+-+   |
+|  1  |     |     |     |  5  |     |     |
++-----+-----+-----+-----+-----+-----+-----+
+";
+        let marks = marks(&[(C, "1"), (Eb, "3"), (G, "5")]);
+        assert_eq!(keyboard(&marks, 1, Style::default()), expected);
+    }
+
+    #[test]
+    fn unicode() {
+        let expected = "\
+┌───┬───┬─┬───┬───┬───┬───┬─┬───┬─┬───┬───┐
+│   │███│ │███│   │   │███│ │███│ │███│   │
+│   │███│ │███│   │   │███│ │███│ │███│   │
+│   └─┬─┘ └─┬─┘   │   └─┬─┘ └─┬─┘ └─┬─┘   │
+│  ●  │     │     │     │     │     │     │
+└─────┴─────┴─────┴─────┴─────┴─────┴─────┘
+";
+        let style = Style {
+            unicode: true,
+            color: false,
+        };
+        assert_eq!(keyboard(&marks(&[(C, "")]), 1, style), expected);
+    }
+
+    #[rstest]
+    #[case(1, 43)]
+    #[case(2, 85)]
+    #[case(0, 43)]
+    fn width_of_octaves(#[case] octaves: usize, #[case] width: usize) {
+        let drawing = keyboard(&[], octaves, Style::default());
+        assert!(drawing.lines().all(|line| line.chars().count() == width), "{}", drawing);
+    }
+
+    #[test]
+    fn long_labels() {
+        let drawing = keyboard(&marks(&[(Db, "Db major"), (E, "E major")]), 1, Style::default());
+        assert!(drawing.contains("|   |Db | |"), "{}", drawing);
+        assert!(drawing.contains("|E maj|"), "{}", drawing);
+    }
+
+    #[test]
+    fn colors() {
+        let style = Style {
+            unicode: false,
+            color: true,
+        };
+        let drawing = keyboard(&marks(&[(D, "1"), (Gb, "3")]), 1, style);
+        let lines: Vec<&str> = drawing.lines().collect();
+        assert_eq!(
+            lines[4],
+            "|     |\x1b[30;43m  1  \x1b[0m|     |     |     |     |     |"
+        );
+        assert!(lines[2].contains("|\x1b[30;46m 3 \x1b[0m|"), "{}", lines[2]);
+        assert!(!lines[0].contains('\x1b'));
+    }
+}
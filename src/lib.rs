@@ -1,6 +1,15 @@
+pub mod audio;
 pub mod chord;
 pub mod cli;
+pub mod fretboard;
+pub mod harmony;
 pub mod interval;
+pub mod key;
+pub mod keyboard;
+pub mod midi;
+pub mod notation;
 pub mod note;
 mod ops;
+pub mod pitch;
 pub mod scales;
+pub mod tuning;
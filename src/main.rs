@@ -1,20 +1,51 @@
 use clap::Command;
 
-use musicionist::cli::scales::{handle, handle_interactive, scale_subcommand};
+use musicionist::cli::{
+    analyze, analyze_abc, analyze_midi, analyze_musicxml, chord, chord_scale, chordpro, fretboard, key, modulate,
+    negative, progression, reharm, scales, tonnetz,
+};
 
 pub fn cli() -> Command {
     Command::new("musicionist")
         .about("A command line music theory tool")
-        .subcommand(scale_subcommand())
+        .subcommand(scales::scale_subcommand())
+        .subcommand(chord::chord_subcommand())
+        .subcommand(analyze::analyze_subcommand())
+        .subcommand(progression::progression_subcommand())
+        .subcommand(chord_scale::chord_scale_subcommand())
+        .subcommand(reharm::reharm_subcommand())
+        .subcommand(tonnetz::tonnetz_subcommand())
+        .subcommand(negative::negative_subcommand())
+        .subcommand(modulate::modulate_subcommand())
+        .subcommand(key::key_subcommand())
+        .subcommand(analyze_midi::analyze_midi_subcommand())
+        .subcommand(analyze_musicxml::analyze_musicxml_subcommand())
+        .subcommand(analyze_abc::analyze_abc_subcommand())
+        .subcommand(chordpro::chordpro_subcommand())
+        .subcommand(fretboard::fretboard_subcommand())
 }
 
 fn main() -> anyhow::Result<()> {
     match cli().get_matches().subcommand() {
-        Some(("scale", m)) => handle(m)?,
+        Some(("scale", m)) => scales::handle(m)?,
+        Some(("chord", m)) => chord::handle(m)?,
+        Some(("analyze", m)) => analyze::handle(m)?,
+        Some(("progression", m)) => progression::handle(m)?,
+        Some(("chord-scale", m)) => chord_scale::handle(m)?,
+        Some(("reharm", m)) => reharm::handle(m)?,
+        Some(("tonnetz", m)) => tonnetz::handle(m)?,
+        Some(("negative", m)) => negative::handle(m)?,
+        Some(("modulate", m)) => modulate::handle(m)?,
+        Some(("key", m)) => key::handle(m)?,
+        Some(("analyze-midi", m)) => analyze_midi::handle(m)?,
+        Some(("analyze-musicxml", m)) => analyze_musicxml::handle(m)?,
+        Some(("analyze-abc", m)) => analyze_abc::handle(m)?,
+        Some(("chordpro", m)) => chordpro::handle(m)?,
+        Some(("fretboard", m)) => fretboard::handle(m)?,
         _ => {
             let choice = inquire::Select::new("What do you want to explore?", vec!["scale"]).prompt()?;
             match choice {
-                "scale" => handle_interactive()?,
+                "scale" => scales::handle_interactive()?,
                 _ => unreachable!(),
             }
         }
@@ -0,0 +1,266 @@
+use crate::chord::symbol::identify;
+use crate::chord::ChordSymbol;
+use crate::harmony::key_finding::histogram;
+use crate::harmony::{find_keys, KeyScore, Profile};
+use crate::key::Key;
+use crate::midi::import::NoteEvent;
+use crate::note::Note;
+use crate::scales::Scale;
+
+/// Notes with less than this share of the weight of the most heard note in a span are passing notes, left out of its
+/// chord.
+const CHORD_TONE_SHARE: f64 = 0.25;
+
+/// How finely a piece is cut up to name its chords.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Beat,
+    Bar,
+}
+
+/// A bar, or a beat of one, numbered from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub bar: usize,
+    pub beat: Option<usize>,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The chord heard during a span, if any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChordSpan {
+    pub span: Span,
+    pub chord: Option<ChordSymbol>,
+}
+
+/// How much of a piece's notes, weighted by duration, fit in a key, from 0 to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaleMatch {
+    pub key: Key,
+    pub coverage: f64,
+}
+
+/// Cut the ticks up to `end` into bars or beats, following the meters as found by
+/// [`time_signatures`](crate::midi::import::time_signatures). A new meter takes effect at the next bar line. Beats too
+/// short for the ticks per quarter last a tick.
+pub fn spans(meters: &[(u32, (u8, u8))], ticks_per_quarter: u16, end: u32, resolution: Resolution) -> Vec<Span> {
+    let mut spans = vec![];
+    let (mut tick, mut bar) = (0, 1);
+    while tick < end {
+        let (numerator, denominator) = meters
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= tick)
+            .map_or((4, 4), |(_, meter)| *meter);
+        let beat = (ticks_per_quarter as u32 * 4 / denominator.max(1) as u32).max(1);
+        let length = beat * numerator.max(1) as u32;
+        match resolution {
+            Resolution::Bar => spans.push(Span {
+                bar,
+                beat: None,
+                start: tick,
+                end: tick.saturating_add(length),
+            }),
+            Resolution::Beat => spans.extend((0..numerator as u32).map(|i| Span {
+                bar,
+                beat: Some(i as usize + 1),
+                start: tick.saturating_add(i * beat),
+                end: tick.saturating_add((i + 1) * beat),
+            })),
+        }
+        tick = tick.saturating_add(length);
+        bar += 1;
+    }
+    spans
+}
+
+/// Name the chord of each span from the pitched notes sounding in it, each weighted by how long it sounds there. The
+/// lowest of the notes kept is the bass.
+pub fn chords(notes: &[NoteEvent], spans: &[Span]) -> Vec<ChordSpan> {
+    spans
+        .iter()
+        .map(|span| {
+            let heard: Vec<(&NoteEvent, u32)> = notes
+                .iter()
+                .filter(|n| !n.is_percussion())
+                .map(|n| (n, n.end.min(span.end).saturating_sub(n.start.max(span.start))))
+                .filter(|(_, overlap)| *overlap > 0)
+                .collect();
+            let weights = histogram(
+                &heard
+                    .iter()
+                    .map(|(n, overlap)| (n.pitch.note, *overlap as f64))
+                    .collect::<Vec<_>>(),
+            );
+            let threshold = weights.iter().copied().fold(0.0, f64::max) * CHORD_TONE_SHARE;
+
+            let mut kept: Vec<&NoteEvent> = heard
+                .iter()
+                .map(|(n, _)| *n)
+                .filter(|n| weights[n.pitch.note.semitones_from_c()] >= threshold)
+                .collect();
+            kept.sort_by_key(|n| n.pitch);
+            let chord = kept.first().and_then(|bass| {
+                let tones: Vec<Note> = kept.iter().map(|n| n.pitch.note).collect();
+                identify(&tones, bass.pitch.note)
+            });
+            ChordSpan { span: *span, chord }
+        })
+        .collect()
+}
+
+/// Rank the keys of the pitched notes, weighted by duration, as [`find_keys`] does.
+pub fn keys(notes: &[NoteEvent], profile: Profile, scales: &[Scale]) -> Vec<KeyScore> {
+    find_keys(&weighted(notes), profile, scales)
+}
+
+/// Rank the keys on every tonic and each of `scales` by how much of the pitched notes they contain, breaking ties by how
+/// much the tonic is heard.
+pub fn scale_matches(notes: &[NoteEvent], scales: &[Scale]) -> Vec<ScaleMatch> {
+    let weights = histogram(&weighted(notes));
+    let total: f64 = weights.iter().sum();
+    if total == 0.0 {
+        return vec![];
+    }
+    let mut matches: Vec<ScaleMatch> = scales
+        .iter()
+        .flat_map(|scale| (0..12).map(move |tonic| Key::new(Note::C.transpose(tonic), *scale)))
+        .map(|key| {
+            let inside: f64 = key.notes().iter().map(|n| weights[n.semitones_from_c()]).sum();
+            ScaleMatch {
+                key,
+                coverage: inside / total,
+            }
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        let tonic = |m: &ScaleMatch| weights[m.key.tonic.semitones_from_c()];
+        b.coverage.total_cmp(&a.coverage).then(tonic(b).total_cmp(&tonic(a)))
+    });
+    matches
+}
+
+fn weighted(notes: &[NoteEvent]) -> Vec<(Note, f64)> {
+    notes
+        .iter()
+        .filter(|n| !n.is_percussion())
+        .map(|n| (n.pitch.note, n.duration() as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::midi::export;
+    use crate::midi::export::{progression, to_smf, Direction, Settings};
+    use crate::midi::import::{notes, time_signatures};
+    use crate::midi::smf::{Format, Smf};
+    use crate::pitch::Pitch;
+    use crate::scales::{diatonic, harmonic_minor};
+
+    use super::*;
+
+    fn read_back(smf: &Smf) -> Vec<NoteEvent> {
+        notes(&Smf::parse(&smf.to_bytes()).unwrap())
+    }
+
+    #[rstest]
+    #[case(Resolution::Bar, vec![(1, None, 0, 1920), (2, None, 1920, 3840)])]
+    #[case(Resolution::Beat, vec![(1, Some(1), 0, 480), (1, Some(2), 480, 960), (1, Some(3), 960, 1440), (1, Some(4), 1440, 1920), (2, Some(1), 1920, 2400)])]
+    fn spans_in_four_four(#[case] resolution: Resolution, #[case] expected: Vec<(usize, Option<usize>, u32, u32)>) {
+        let result: Vec<(usize, Option<usize>, u32, u32)> = spans(&[(0, (4, 4))], 480, 2000, resolution)
+            .iter()
+            .map(|s| (s.bar, s.beat, s.start, s.end))
+            .collect();
+        assert_eq!(result[..expected.len()], expected[..]);
+    }
+
+    #[test]
+    fn meter_changes_at_the_next_bar() {
+        let bars: Vec<(u32, u32)> = spans(&[(0, (3, 4)), (1000, (6, 8))], 480, 3000, Resolution::Bar)
+            .iter()
+            .map(|s| (s.start, s.end))
+            .collect();
+        assert_eq!(bars, vec![(0, 1440), (1440, 2880), (2880, 4320)]);
+    }
+
+    #[rstest]
+    #[case(0, (4, 4))]
+    #[case(1, (3, 8))]
+    fn spans_of_zero_ticks(#[case] ticks_per_quarter: u16, #[case] meter: (u8, u8)) {
+        let bars = spans(&[(0, meter)], ticks_per_quarter, 10, Resolution::Beat);
+        assert!(bars.iter().all(|s| s.end > s.start));
+        assert!(bars.last().is_some_and(|s| s.end >= 10));
+    }
+
+    #[test]
+    fn chords_of_a_progression() {
+        let settings = Settings::default();
+        let symbols: Vec<ChordSymbol> = ["Dm7", "G7", "Cmaj7", "Am/E"]
+            .iter()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let smf = to_smf(&progression(&symbols, &settings), &settings, Format::MultiTrack);
+        let notes = read_back(&smf);
+        let end = notes.iter().map(|n| n.end).max().unwrap();
+        let bars = spans(&time_signatures(&smf), smf.ticks_per_quarter, end, Resolution::Bar);
+        let names: Vec<String> = chords(&notes, &bars)
+            .iter()
+            .map(|c| c.chord.as_ref().map_or("-".to_string(), |c| c.to_string()))
+            .collect();
+        assert_eq!(names, vec!["Dm7", "G7", "Cmaj7", "Am/E"]);
+    }
+
+    #[test]
+    fn beats_without_notes_have_no_chord() {
+        let settings = Settings::default();
+        let symbols: Vec<ChordSymbol> = vec!["C".parse().unwrap()];
+        let notes = read_back(&to_smf(
+            &progression(&symbols, &settings),
+            &settings,
+            Format::SingleTrack,
+        ));
+        let beats = spans(&[(0, (4, 4))], 480, 3840, Resolution::Beat);
+        let result = chords(&notes, &beats);
+        assert_eq!(result.len(), 8);
+        assert!(result[..4].iter().all(|c| c.chord.as_ref().unwrap().to_string() == "C"));
+        assert!(result[4..].iter().all(|c| c.chord.is_none()));
+    }
+
+    fn scale_file(tonic: Note, scale: Scale) -> Vec<NoteEvent> {
+        let settings = Settings::default();
+        let part = export::scale(Pitch::new(tonic, 4), &scale.intervals(), Direction::Both, &settings);
+        read_back(&to_smf(&[part], &settings, Format::SingleTrack))
+    }
+
+    #[test]
+    fn scale_of_a_scale() {
+        let notes = scale_file(Note::D, Scale::Diatonic(diatonic::Mode::Dorian));
+        let matches = scale_matches(&notes, &Scale::all());
+        assert_eq!(matches[0].key.to_string(), "D dorian");
+        assert_eq!(matches[0].coverage, 1.0);
+        // The other modes of C major contain the same notes.
+        assert_eq!(matches.iter().filter(|m| m.coverage == 1.0).count(), 7);
+    }
+
+    #[test]
+    fn key_of_a_scale() {
+        let notes = scale_file(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+        let scales = [
+            Scale::Diatonic(diatonic::Mode::Ionian),
+            Scale::Diatonic(diatonic::Mode::Aeolian),
+        ];
+        assert_eq!(
+            keys(&notes, Profile::KrumhanslSchmuckler, &scales)[0].key.to_string(),
+            "A minor"
+        );
+    }
+
+    #[test]
+    fn nothing_to_analyze() {
+        assert!(scale_matches(&[], &Scale::all()).is_empty());
+        assert!(keys(&[], Profile::Temperley, &Scale::all()).is_empty());
+    }
+}
@@ -0,0 +1,400 @@
+use crate::chord::{Chord, ChordSymbol};
+use crate::interval::Interval;
+use crate::midi::smf::{Event, Format, Smf, TrackEvent};
+use crate::pitch::Pitch;
+
+/// How the notes are played back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings {
+    /// Quarter notes per minute.
+    pub tempo: u32,
+    /// Beats per bar and the note value of a beat, like `(6, 8)`. The denominator must be a power of two.
+    pub meter: (u8, u8),
+    pub velocity: u8,
+    /// The MIDI channel from 0 to 15, shown as 1 to 16 by most software.
+    pub channel: u8,
+    pub ticks_per_quarter: u16,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            tempo: 120,
+            meter: (4, 4),
+            velocity: 96,
+            channel: 0,
+            ticks_per_quarter: 480,
+        }
+    }
+}
+
+impl Settings {
+    pub fn ticks_per_beat(&self) -> u32 {
+        self.ticks_per_quarter as u32 * 4 / self.meter.1 as u32
+    }
+
+    pub fn ticks_per_bar(&self) -> u32 {
+        self.ticks_per_beat() * self.meter.0 as u32
+    }
+}
+
+/// A pitch with its start and length in ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedNote {
+    pub pitch: Pitch,
+    pub start: u32,
+    pub duration: u32,
+}
+
+/// A named line of notes, written to its own track in type 1 files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Part {
+    pub name: String,
+    pub notes: Vec<TimedNote>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+    /// Up to the octave and back down.
+    Both,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChordStyle {
+    /// All notes together for a bar.
+    Block,
+    /// One note per beat, from the bottom up.
+    Arpeggio,
+}
+
+/// The scale from `tonic` to the octave above, in the given direction.
+pub fn scale_line(tonic: Pitch, intervals: &[Interval], direction: Direction) -> Vec<Pitch> {
+    let mut up: Vec<Pitch> = intervals
+        .iter()
+        .map(|i| tonic.transpose(i.semitones() as i32))
+        .collect();
+    up.push(tonic.transpose(12));
+    match direction {
+        Direction::Ascending => up,
+        Direction::Descending => up.into_iter().rev().collect(),
+        Direction::Both => {
+            let down: Vec<Pitch> = up.iter().rev().skip(1).copied().collect();
+            up.extend(down);
+            up
+        }
+    }
+}
+
+/// The chord in close position, stacked up from `root`.
+pub fn close_voicing(root: Pitch, chord: &Chord) -> Vec<Pitch> {
+    chord.semitones().iter().map(|s| root.transpose(*s as i32)).collect()
+}
+
+/// A scale as a line of beats.
+pub fn scale(tonic: Pitch, intervals: &[Interval], direction: Direction, settings: &Settings) -> Part {
+    let beat = settings.ticks_per_beat();
+    let notes = scale_line(tonic, intervals, direction)
+        .into_iter()
+        .enumerate()
+        .map(|(i, pitch)| TimedNote {
+            pitch,
+            start: i as u32 * beat,
+            duration: beat,
+        })
+        .collect();
+    Part {
+        name: "Scale".to_string(),
+        notes,
+    }
+}
+
+/// A chord in close position, either held for a bar or arpeggiated a beat per note.
+pub fn chord(root: Pitch, chord: &Chord, style: ChordStyle, settings: &Settings) -> Part {
+    let pitches = close_voicing(root, chord);
+    let notes = match style {
+        ChordStyle::Block => pitches
+            .into_iter()
+            .map(|pitch| TimedNote {
+                pitch,
+                start: 0,
+                duration: settings.ticks_per_bar(),
+            })
+            .collect(),
+        ChordStyle::Arpeggio => {
+            let beat = settings.ticks_per_beat();
+            pitches
+                .into_iter()
+                .enumerate()
+                .map(|(i, pitch)| TimedNote {
+                    pitch,
+                    start: i as u32 * beat,
+                    duration: beat,
+                })
+                .collect()
+        }
+    };
+    Part {
+        name: "Chord".to_string(),
+        notes,
+    }
+}
+
+/// A progression with a chord per bar: a part with the chords in close position around middle C, and a bass part
+/// with their root or slash bass.
+pub fn progression(symbols: &[ChordSymbol], settings: &Settings) -> Vec<Part> {
+    let bar = settings.ticks_per_bar();
+    let mut chords = vec![];
+    let mut bass = vec![];
+    for (i, symbol) in symbols.iter().enumerate() {
+        let start = i as u32 * bar;
+        // Roots from F3 to E4 keep the voicings within an octave of middle C.
+        let root = Pitch::from_midi(53 + (symbol.root.semitones_from_c() as i32 + 7) % 12);
+        chords.extend(close_voicing(root, &symbol.chord).into_iter().map(|pitch| TimedNote {
+            pitch,
+            start,
+            duration: bar,
+        }));
+        bass.push(TimedNote {
+            pitch: Pitch::new(symbol.bass_note(), 2),
+            start,
+            duration: bar,
+        });
+    }
+    vec![
+        Part {
+            name: "Chords".to_string(),
+            notes: chords,
+        },
+        Part {
+            name: "Bass".to_string(),
+            notes: bass,
+        },
+    ]
+}
+
+/// Write parts to a Standard MIDI File. Type 0 files merge everything into one track, type 1 files start with a track
+/// holding the tempo and meter, followed by a track per part.
+pub fn to_smf(parts: &[Part], settings: &Settings, format: Format) -> Smf {
+    let conductor = vec![
+        (0, Event::Tempo(60_000_000 / settings.tempo)),
+        (
+            0,
+            Event::TimeSignature {
+                numerator: settings.meter.0,
+                denominator: settings.meter.1,
+            },
+        ),
+    ];
+    let tracks = match format {
+        Format::SingleTrack => {
+            let mut events = conductor;
+            for part in parts {
+                events.extend(note_events(&part.notes, settings));
+            }
+            vec![track(events)]
+        }
+        Format::MultiTrack => {
+            let mut tracks = vec![track(conductor)];
+            for part in parts {
+                let mut events = vec![(0, Event::TrackName(part.name.clone()))];
+                events.extend(note_events(&part.notes, settings));
+                tracks.push(track(events));
+            }
+            tracks
+        }
+    };
+    Smf {
+        format,
+        ticks_per_quarter: settings.ticks_per_quarter,
+        tracks,
+    }
+}
+
+fn note_events(notes: &[TimedNote], settings: &Settings) -> Vec<(u32, Event)> {
+    notes
+        .iter()
+        .flat_map(|note| {
+            let key = note.pitch.midi().clamp(0, 127) as u8;
+            [
+                (
+                    note.start,
+                    Event::NoteOn {
+                        channel: settings.channel,
+                        key,
+                        velocity: settings.velocity,
+                    },
+                ),
+                (
+                    note.start + note.duration,
+                    Event::NoteOff {
+                        channel: settings.channel,
+                        key,
+                        velocity: 0,
+                    },
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// Order events by time, with notes ending before the ones starting at the same tick, and close the track.
+fn track(mut events: Vec<(u32, Event)>) -> Vec<TrackEvent> {
+    events.sort_by_key(|(tick, event)| (*tick, matches!(event, Event::NoteOn { .. })));
+    let mut previous = 0;
+    let mut track: Vec<TrackEvent> = events
+        .into_iter()
+        .map(|(tick, event)| {
+            let delta = tick - previous;
+            previous = tick;
+            TrackEvent { delta, event }
+        })
+        .collect();
+    track.push(TrackEvent {
+        delta: 0,
+        event: Event::EndOfTrack,
+    });
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, Scale};
+
+    use super::*;
+
+    /// The notes of a file as (start tick, key, length) triples, read back from its bytes.
+    fn notes_of(smf: &Smf) -> Vec<(u32, u8, u32)> {
+        let parsed = Smf::parse(&smf.to_bytes()).unwrap();
+        let mut notes = vec![];
+        for track in &parsed.tracks {
+            let mut tick = 0;
+            let mut started: Vec<(u8, u32)> = vec![];
+            for event in track {
+                tick += event.delta;
+                match event.event {
+                    Event::NoteOn { key, .. } => started.push((key, tick)),
+                    Event::NoteOff { key, .. } => {
+                        let at = started.iter().position(|(k, _)| *k == key).unwrap();
+                        let (_, start) = started.remove(at);
+                        notes.push((start, key, tick - start));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        notes.sort();
+        notes
+    }
+
+    fn major() -> Vec<Interval> {
+        Scale::Diatonic(diatonic::Mode::Ionian).intervals().to_vec()
+    }
+
+    #[rstest]
+    #[case(Direction::Ascending, vec![60, 62, 64, 65, 67, 69, 71, 72])]
+    #[case(Direction::Descending, vec![72, 71, 69, 67, 65, 64, 62, 60])]
+    #[case(Direction::Both, vec![60, 62, 64, 65, 67, 69, 71, 72, 71, 69, 67, 65, 64, 62, 60])]
+    fn scale_lines(#[case] direction: Direction, #[case] expected: Vec<i32>) {
+        let line: Vec<i32> = scale_line(Pitch::new(C, 4), &major(), direction)
+            .iter()
+            .map(|p| p.midi())
+            .collect();
+        assert_eq!(line, expected);
+    }
+
+    #[test]
+    fn scale_round_trip() {
+        let settings = Settings::default();
+        let part = scale(Pitch::new(D, 4), &major(), Direction::Ascending, &settings);
+        let smf = to_smf(&[part], &settings, Format::SingleTrack);
+        let notes = notes_of(&smf);
+        assert_eq!(notes.len(), 8);
+        assert_eq!(notes[0], (0, 62, 480));
+        assert_eq!(notes[7], (3360, 74, 480));
+    }
+
+    #[test]
+    fn tempo_meter_velocity_and_channel() {
+        let settings = Settings {
+            tempo: 90,
+            meter: (6, 8),
+            velocity: 70,
+            channel: 9,
+            ticks_per_quarter: 96,
+        };
+        let part = chord(
+            Pitch::new(C, 4),
+            &"Cmaj7".parse::<ChordSymbol>().unwrap().chord,
+            ChordStyle::Block,
+            &settings,
+        );
+        let smf = Smf::parse(&to_smf(&[part], &settings, Format::MultiTrack).to_bytes()).unwrap();
+        assert_eq!(smf.format, Format::MultiTrack);
+        assert_eq!(smf.ticks_per_quarter, 96);
+        assert_eq!(smf.tracks.len(), 2);
+        assert_eq!(smf.tracks[0][0].event, Event::Tempo(666_666));
+        assert_eq!(
+            smf.tracks[0][1].event,
+            Event::TimeSignature {
+                numerator: 6,
+                denominator: 8
+            }
+        );
+        assert_eq!(smf.tracks[1][0].event, Event::TrackName("Chord".to_string()));
+        assert_eq!(
+            smf.tracks[1][1].event,
+            Event::NoteOn {
+                channel: 9,
+                key: 60,
+                velocity: 70
+            }
+        );
+        // A bar of 6/8 is three quarter notes.
+        assert!(notes_of(&smf)
+            .iter()
+            .all(|(start, _, length)| *start == 0 && *length == 288));
+    }
+
+    #[test]
+    fn arpeggio() {
+        let settings = Settings::default();
+        let symbol: ChordSymbol = "Am7".parse().unwrap();
+        let part = chord(Pitch::new(A, 3), &symbol.chord, ChordStyle::Arpeggio, &settings);
+        assert_eq!(
+            notes_of(&to_smf(&[part], &settings, Format::SingleTrack)),
+            vec![(0, 57, 480), (480, 60, 480), (960, 64, 480), (1440, 67, 480)]
+        );
+    }
+
+    #[test]
+    fn progression_round_trip() {
+        let settings = Settings::default();
+        let symbols: Vec<ChordSymbol> = ["C", "Am/E", "G7"].iter().map(|c| c.parse().unwrap()).collect();
+        let parts = progression(&symbols, &settings);
+        let notes = notes_of(&to_smf(&parts, &settings, Format::MultiTrack));
+        let bass: Vec<(u32, u8, u32)> = notes.iter().copied().filter(|(_, key, _)| *key < 48).collect();
+        assert_eq!(bass, vec![(0, 36, 1920), (1920, 40, 1920), (3840, 43, 1920)]);
+        let first: Vec<u8> = notes
+            .iter()
+            .filter(|(start, key, _)| *start == 0 && *key >= 48)
+            .map(|(_, key, _)| *key)
+            .collect();
+        assert_eq!(first, vec![60, 64, 67]);
+        assert_eq!(notes.len(), 3 + 3 + 4 + 3);
+    }
+
+    #[test]
+    fn single_track_merges_parts() {
+        let settings = Settings::default();
+        let symbols: Vec<ChordSymbol> = ["F", "C"].iter().map(|c| c.parse().unwrap()).collect();
+        let smf = to_smf(&progression(&symbols, &settings), &settings, Format::SingleTrack);
+        assert_eq!(smf.tracks.len(), 1);
+        assert_eq!(notes_of(&smf).len(), 8);
+        assert_eq!(smf.tracks[0].last().unwrap().event, Event::EndOfTrack);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::midi::smf::{Event, Smf};
+use crate::pitch::Pitch;
+
+/// The General MIDI channel reserved for percussion, counting from 0.
+pub const PERCUSSION_CHANNEL: u8 = 9;
+
+/// A note read from a file, timed in ticks from the start and in seconds through the tempo map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoteEvent {
+    pub pitch: Pitch,
+    pub velocity: u8,
+    pub channel: u8,
+    pub track: usize,
+    pub start: u32,
+    pub end: u32,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+impl NoteEvent {
+    pub fn duration(&self) -> u32 {
+        self.end - self.start
+    }
+
+    pub fn is_percussion(&self) -> bool {
+        self.channel == PERCUSSION_CHANNEL
+    }
+}
+
+/// The tempo changes of a file, from all its tracks, used to turn ticks into seconds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TempoMap {
+    ticks_per_quarter: u16,
+    /// Ticks at which a tempo starts and its microseconds per quarter note, in order and starting at tick 0.
+    changes: Vec<(u32, u32)>,
+}
+
+impl TempoMap {
+    /// The default tempo of a file without tempo events is 120 quarter notes per minute.
+    const DEFAULT: u32 = 500_000;
+
+    pub fn new(smf: &Smf) -> TempoMap {
+        let changes = absolute_events(smf)
+            .filter_map(|(tick, _, event)| match event {
+                Event::Tempo(tempo) => Some((tick, *tempo)),
+                _ => None,
+            })
+            .collect();
+        TempoMap::with_changes(smf.ticks_per_quarter, changes)
+    }
+
+    /// A tempo map from the ticks at which tempos start and their microseconds per quarter note, in any order.
+    pub fn with_changes(ticks_per_quarter: u16, mut changes: Vec<(u32, u32)>) -> TempoMap {
+        changes.sort_by_key(|(tick, _)| *tick);
+        if changes.first().is_none_or(|(tick, _)| *tick > 0) {
+            changes.insert(0, (0, TempoMap::DEFAULT));
+        }
+        TempoMap {
+            ticks_per_quarter,
+            changes,
+        }
+    }
+
+    pub fn seconds(&self, tick: u32) -> f64 {
+        let mut seconds = 0.0;
+        for (i, (start, tempo)) in self.changes.iter().enumerate() {
+            if tick <= *start {
+                break;
+            }
+            let end = self.changes.get(i + 1).map_or(tick, |(next, _)| tick.min(*next));
+            seconds += (end - start) as f64 * *tempo as f64 / 1_000_000.0 / self.ticks_per_quarter as f64;
+        }
+        seconds
+    }
+}
+
+/// The meters of a file with the tick each starts at, in order. Files without a time signature are in 4/4.
+pub fn time_signatures(smf: &Smf) -> Vec<(u32, (u8, u8))> {
+    let mut meters: Vec<(u32, (u8, u8))> = absolute_events(smf)
+        .filter_map(|(tick, _, event)| match event {
+            Event::TimeSignature { numerator, denominator } => Some((tick, (*numerator, *denominator))),
+            _ => None,
+        })
+        .collect();
+    meters.sort_by_key(|(tick, _)| *tick);
+    if meters.first().is_none_or(|(tick, _)| *tick > 0) {
+        meters.insert(0, (0, (4, 4)));
+    }
+    meters
+}
+
+/// Every note of every track, by start time then pitch.
+///
+/// A note on with velocity 0 ends a note, as a note off does. Notes of the same key and channel that overlap are ended
+/// first in first out, and notes still sounding at the end of their track end there.
+pub fn notes(smf: &Smf) -> Vec<NoteEvent> {
+    let tempo = TempoMap::new(smf);
+    let mut notes = vec![];
+    for (index, track) in smf.tracks.iter().enumerate() {
+        let mut tick: u32 = 0;
+        let mut sounding: Vec<(u8, u8, u8, u32)> = vec![];
+        let mut finish = |channel: u8, key: u8, velocity: u8, start: u32, end: u32| {
+            notes.push(NoteEvent {
+                pitch: Pitch::from_midi(key as i32),
+                velocity,
+                channel,
+                track: index,
+                start,
+                end,
+                start_seconds: tempo.seconds(start),
+                end_seconds: tempo.seconds(end),
+            })
+        };
+        for event in track {
+            tick = tick.saturating_add(event.delta);
+            match event.event {
+                Event::NoteOn { channel, key, velocity } if velocity > 0 => {
+                    sounding.push((channel, key, velocity, tick))
+                }
+                Event::NoteOn { channel, key, .. } | Event::NoteOff { channel, key, .. } => {
+                    if let Some(at) = sounding.iter().position(|(c, k, _, _)| *c == channel && *k == key) {
+                        let (_, _, velocity, start) = sounding.remove(at);
+                        finish(channel, key, velocity, start, tick);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (channel, key, velocity, start) in sounding {
+            finish(channel, key, velocity, start, tick);
+        }
+    }
+    notes.sort_by_key(|n| (n.start, n.pitch));
+    notes
+}
+
+/// The events of all tracks with their tick from the start and their track.
+fn absolute_events(smf: &Smf) -> impl Iterator<Item = (u32, usize, &Event)> {
+    smf.tracks.iter().enumerate().flat_map(|(index, track)| {
+        track.iter().scan(0u32, move |tick, event| {
+            *tick = (*tick).saturating_add(event.delta);
+            Some((*tick, index, &event.event))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::midi::smf::{Format, TrackEvent};
+    use crate::note::Note::*;
+
+    use super::*;
+
+    fn event(delta: u32, event: Event) -> TrackEvent {
+        TrackEvent { delta, event }
+    }
+
+    fn on(key: u8, velocity: u8) -> Event {
+        Event::NoteOn {
+            channel: 0,
+            key,
+            velocity,
+        }
+    }
+
+    fn off(key: u8) -> Event {
+        Event::NoteOff {
+            channel: 0,
+            key,
+            velocity: 0,
+        }
+    }
+
+    fn smf(tracks: Vec<Vec<TrackEvent>>) -> Smf {
+        Smf {
+            format: Format::MultiTrack,
+            ticks_per_quarter: 100,
+            tracks,
+        }
+    }
+
+    #[rstest]
+    #[case(0, 0.0)]
+    #[case(100, 0.5)]
+    #[case(200, 1.0)]
+    #[case(300, 1.25)]
+    #[case(400, 1.5)]
+    fn tempo_map(#[case] tick: u32, #[case] seconds: f64) {
+        let file = smf(vec![
+            vec![event(200, Event::Tempo(250_000)), event(0, Event::EndOfTrack)],
+            vec![event(0, on(60, 100)), event(400, off(60))],
+        ]);
+        assert_eq!(TempoMap::new(&file).seconds(tick), seconds);
+    }
+
+    #[test]
+    fn notes_across_tracks() {
+        let file = smf(vec![
+            vec![event(0, Event::Tempo(1_000_000)), event(0, Event::EndOfTrack)],
+            vec![event(0, on(60, 90)), event(100, on(60, 0)), event(0, on(64, 80))],
+            vec![
+                event(
+                    50,
+                    Event::NoteOn {
+                        channel: 9,
+                        key: 36,
+                        velocity: 127,
+                    },
+                ),
+                event(
+                    10,
+                    Event::NoteOff {
+                        channel: 9,
+                        key: 36,
+                        velocity: 0,
+                    },
+                ),
+            ],
+        ]);
+        let notes = notes(&file);
+        assert_eq!(notes.len(), 3);
+
+        assert_eq!(notes[0].pitch, Pitch::new(C, 4));
+        assert_eq!((notes[0].start, notes[0].end, notes[0].velocity), (0, 100, 90));
+        assert_eq!((notes[0].start_seconds, notes[0].end_seconds), (0.0, 1.0));
+
+        assert!(notes[1].is_percussion());
+        assert_eq!((notes[1].track, notes[1].start, notes[1].duration()), (2, 50, 10));
+
+        // Still sounding at the end of its track.
+        assert_eq!(notes[2].pitch, Pitch::new(E, 4));
+        assert_eq!((notes[2].start, notes[2].end), (100, 100));
+    }
+
+    #[test]
+    fn overlapping_notes_of_the_same_key() {
+        let file = smf(vec![vec![
+            event(0, on(60, 100)),
+            event(10, on(60, 50)),
+            event(10, off(60)),
+            event(10, off(60)),
+        ]]);
+        let spans: Vec<(u32, u32, u8)> = notes(&file).iter().map(|n| (n.start, n.end, n.velocity)).collect();
+        assert_eq!(spans, vec![(0, 20, 100), (10, 30, 50)]);
+    }
+
+    #[test]
+    fn ticks_stop_at_the_largest_time() {
+        // The longest delta a file can hold, more times than a tick count can add up.
+        let mut track = vec![event(0, on(60, 100))];
+        track.extend((0..18).map(|_| event(0x0FFF_FFFF, Event::Tempo(500_000))));
+        track.push(event(0x0FFF_FFFF, off(60)));
+        let file = smf(vec![track]);
+        let spans: Vec<(u32, u32)> = notes(&file).iter().map(|n| (n.start, n.end)).collect();
+        assert_eq!(spans, vec![(0, u32::MAX)]);
+        assert_eq!(time_signatures(&file), vec![(0, (4, 4))]);
+    }
+
+    #[test]
+    fn meters() {
+        let file = smf(vec![vec![
+            event(
+                400,
+                Event::TimeSignature {
+                    numerator: 3,
+                    denominator: 4,
+                },
+            ),
+            event(0, Event::EndOfTrack),
+        ]]);
+        assert_eq!(time_signatures(&file), vec![(0, (4, 4)), (400, (3, 4))]);
+    }
+}
@@ -0,0 +1,11 @@
+pub use analysis::{chords, keys, scale_matches, spans, ChordSpan, Resolution, ScaleMatch, Span};
+pub use export::{
+    chord, close_voicing, progression, scale, scale_line, to_smf, ChordStyle, Direction, Part, Settings, TimedNote,
+};
+pub use import::{notes, time_signatures, NoteEvent, TempoMap};
+pub use smf::{Event, Format, MidiParseError, Smf, TrackEvent};
+
+pub mod analysis;
+pub mod export;
+pub mod import;
+pub mod smf;
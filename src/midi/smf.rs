@@ -0,0 +1,534 @@
+use std::io;
+
+/// The layout of a Standard MIDI File's tracks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Type 0: everything in a single track.
+    SingleTrack,
+    /// Type 1: several tracks played together, the first one usually holding the tempo map.
+    MultiTrack,
+}
+
+/// The events the library knows about. Anything else is kept as raw bytes so that it survives a round trip.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    NoteOff {
+        channel: u8,
+        key: u8,
+        velocity: u8,
+    },
+    NoteOn {
+        channel: u8,
+        key: u8,
+        velocity: u8,
+    },
+    PolyPressure {
+        channel: u8,
+        key: u8,
+        pressure: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    ChannelPressure {
+        channel: u8,
+        pressure: u8,
+    },
+    PitchBend {
+        channel: u8,
+        value: u16,
+    },
+    /// Microseconds per quarter note.
+    Tempo(u32),
+    /// A meter like 6/8, with the denominator as a note value rather than a power of two.
+    TimeSignature {
+        numerator: u8,
+        denominator: u8,
+    },
+    /// A key signature as a number of sharps (negative for flats).
+    KeySignature {
+        sharps: i8,
+        minor: bool,
+    },
+    TrackName(String),
+    EndOfTrack,
+    /// Any other meta event, by its type byte.
+    Meta {
+        kind: u8,
+        data: Vec<u8>,
+    },
+    /// A system exclusive message, from its `F0` or `F7` status byte on.
+    SysEx {
+        status: u8,
+        data: Vec<u8>,
+    },
+}
+
+/// An event and the number of ticks since the previous one on the same track.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackEvent {
+    pub delta: u32,
+    pub event: Event,
+}
+
+/// A Standard MIDI File, with time counted in ticks per quarter note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Smf {
+    pub format: Format,
+    pub ticks_per_quarter: u16,
+    pub tracks: Vec<Vec<TrackEvent>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid MIDI file: {0}")]
+pub struct MidiParseError(String);
+
+impl Smf {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend(6u32.to_be_bytes());
+        let format: u16 = match self.format {
+            Format::SingleTrack => 0,
+            Format::MultiTrack => 1,
+        };
+        bytes.extend(format.to_be_bytes());
+        bytes.extend((self.tracks.len() as u16).to_be_bytes());
+        bytes.extend(self.ticks_per_quarter.to_be_bytes());
+
+        for track in &self.tracks {
+            let mut data = vec![];
+            for event in track {
+                write_varlen(&mut data, event.delta);
+                write_event(&mut data, &event.event);
+            }
+            bytes.extend(b"MTrk");
+            bytes.extend((data.len() as u32).to_be_bytes());
+            bytes.extend(data);
+        }
+        bytes
+    }
+
+    pub fn write(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Parse a file with any number of tracks. Chunks other than the header and tracks are skipped, and so are tracks
+    /// of type 2 files, which are read as type 1. SMPTE time divisions aren't supported.
+    pub fn parse(bytes: &[u8]) -> Result<Smf, MidiParseError> {
+        let mut reader = Reader { bytes, at: 0 };
+        let (kind, header) = reader.chunk()?;
+        if kind != *b"MThd" || header.len() < 6 {
+            return Err(MidiParseError("missing header chunk".to_string()));
+        }
+        let format = match u16::from_be_bytes([header[0], header[1]]) {
+            0 => Format::SingleTrack,
+            1 | 2 => Format::MultiTrack,
+            other => return Err(MidiParseError(format!("unknown format {}", other))),
+        };
+        let track_count = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let division = u16::from_be_bytes([header[4], header[5]]);
+        if division & 0x8000 != 0 {
+            return Err(MidiParseError("SMPTE time division".to_string()));
+        }
+        if division == 0 {
+            return Err(MidiParseError("time division of 0 ticks".to_string()));
+        }
+
+        let mut tracks = vec![];
+        while tracks.len() < track_count && reader.at < bytes.len() {
+            let (kind, data) = reader.chunk()?;
+            if kind == *b"MTrk" {
+                tracks.push(parse_track(data)?);
+            }
+        }
+        Ok(Smf {
+            format,
+            ticks_per_quarter: division,
+            tracks,
+        })
+    }
+}
+
+fn write_varlen(bytes: &mut Vec<u8>, mut value: u32) {
+    let mut groups = vec![(value & 0x7f) as u8];
+    value >>= 7;
+    while value > 0 {
+        groups.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.extend(groups.iter().rev());
+}
+
+fn write_meta(bytes: &mut Vec<u8>, kind: u8, data: &[u8]) {
+    bytes.extend([0xff, kind]);
+    write_varlen(bytes, data.len() as u32);
+    bytes.extend(data);
+}
+
+fn write_event(bytes: &mut Vec<u8>, event: &Event) {
+    match event {
+        Event::NoteOff { channel, key, velocity } => bytes.extend([0x80 | channel, *key, *velocity]),
+        Event::NoteOn { channel, key, velocity } => bytes.extend([0x90 | channel, *key, *velocity]),
+        Event::PolyPressure { channel, key, pressure } => bytes.extend([0xa0 | channel, *key, *pressure]),
+        Event::ControlChange {
+            channel,
+            controller,
+            value,
+        } => bytes.extend([0xb0 | channel, *controller, *value]),
+        Event::ProgramChange { channel, program } => bytes.extend([0xc0 | channel, *program]),
+        Event::ChannelPressure { channel, pressure } => bytes.extend([0xd0 | channel, *pressure]),
+        Event::PitchBend { channel, value } => {
+            bytes.extend([0xe0 | channel, (value & 0x7f) as u8, (value >> 7 & 0x7f) as u8])
+        }
+        Event::Tempo(tempo) => write_meta(bytes, 0x51, &tempo.to_be_bytes()[1..]),
+        Event::TimeSignature { numerator, denominator } => {
+            let power = denominator.trailing_zeros() as u8;
+            write_meta(bytes, 0x58, &[*numerator, power, 24, 8])
+        }
+        Event::KeySignature { sharps, minor } => write_meta(bytes, 0x59, &[*sharps as u8, *minor as u8]),
+        Event::TrackName(name) => write_meta(bytes, 0x03, name.as_bytes()),
+        Event::EndOfTrack => write_meta(bytes, 0x2f, &[]),
+        Event::Meta { kind, data } => write_meta(bytes, *kind, data),
+        Event::SysEx { status, data } => {
+            bytes.push(*status);
+            write_varlen(bytes, data.len() as u32);
+            bytes.extend(data);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], MidiParseError> {
+        let end = self.at + count;
+        let taken = self
+            .bytes
+            .get(self.at..end)
+            .ok_or_else(|| MidiParseError("unexpected end of data".to_string()))?;
+        self.at = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, MidiParseError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varlen(&mut self) -> Result<u32, MidiParseError> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.byte()?;
+            value = value << 7 | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(MidiParseError(
+            "variable-length quantity longer than 4 bytes".to_string(),
+        ))
+    }
+
+    fn chunk(&mut self) -> Result<([u8; 4], &'a [u8]), MidiParseError> {
+        let kind: [u8; 4] = self.take(4)?.try_into().expect("4 bytes were taken");
+        let length = u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes were taken"));
+        Ok((kind, self.take(length as usize)?))
+    }
+}
+
+/// Parse the events of a track, where a channel message may leave out its status byte to reuse the previous one
+/// (running status).
+fn parse_track(data: &[u8]) -> Result<Vec<TrackEvent>, MidiParseError> {
+    let mut reader = Reader { bytes: data, at: 0 };
+    let mut events = vec![];
+    let mut running_status: Option<u8> = None;
+    while reader.at < data.len() {
+        let delta = reader.varlen()?;
+        let mut status = reader.byte()?;
+        let first = if status < 0x80 {
+            let data = status;
+            status = running_status.ok_or_else(|| MidiParseError("data byte without a status".to_string()))?;
+            Some(data)
+        } else {
+            None
+        };
+
+        let event = match status {
+            0xff => {
+                running_status = None;
+                let kind = reader.byte()?;
+                let length = reader.varlen()? as usize;
+                meta_event(kind, reader.take(length)?)
+            }
+            0xf0 | 0xf7 => {
+                running_status = None;
+                let length = reader.varlen()? as usize;
+                Event::SysEx {
+                    status,
+                    data: reader.take(length)?.to_vec(),
+                }
+            }
+            0x80..=0xef => {
+                running_status = Some(status);
+                let first = match first {
+                    Some(byte) => byte,
+                    None => reader.byte()?,
+                };
+                channel_event(status, first, &mut reader)?
+            }
+            other => return Err(MidiParseError(format!("unexpected status byte {:#04x}", other))),
+        };
+        let end = event == Event::EndOfTrack;
+        events.push(TrackEvent { delta, event });
+        if end {
+            break;
+        }
+    }
+    Ok(events)
+}
+
+fn channel_event(status: u8, first: u8, reader: &mut Reader) -> Result<Event, MidiParseError> {
+    let channel = status & 0x0f;
+    Ok(match status & 0xf0 {
+        0x80 => Event::NoteOff {
+            channel,
+            key: first,
+            velocity: reader.byte()?,
+        },
+        0x90 => Event::NoteOn {
+            channel,
+            key: first,
+            velocity: reader.byte()?,
+        },
+        0xa0 => Event::PolyPressure {
+            channel,
+            key: first,
+            pressure: reader.byte()?,
+        },
+        0xb0 => Event::ControlChange {
+            channel,
+            controller: first,
+            value: reader.byte()?,
+        },
+        0xc0 => Event::ProgramChange {
+            channel,
+            program: first,
+        },
+        0xd0 => Event::ChannelPressure {
+            channel,
+            pressure: first,
+        },
+        _ => Event::PitchBend {
+            channel,
+            value: first as u16 | (reader.byte()? as u16) << 7,
+        },
+    })
+}
+
+fn meta_event(kind: u8, data: &[u8]) -> Event {
+    match (kind, data) {
+        (0x51, [a, b, c]) => Event::Tempo(u32::from_be_bytes([0, *a, *b, *c])),
+        (0x58, [numerator, power, ..]) if *power < 8 => Event::TimeSignature {
+            numerator: *numerator,
+            denominator: 1 << power,
+        },
+        (0x59, [sharps, minor]) => Event::KeySignature {
+            sharps: *sharps as i8,
+            minor: *minor != 0,
+        },
+        (0x03, name) => Event::TrackName(String::from_utf8_lossy(name).into_owned()),
+        (0x2f, []) => Event::EndOfTrack,
+        _ => Event::Meta {
+            kind,
+            data: data.to_vec(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn event(delta: u32, event: Event) -> TrackEvent {
+        TrackEvent { delta, event }
+    }
+
+    #[rstest]
+    #[case(0, vec![0x00])]
+    #[case(0x40, vec![0x40])]
+    #[case(0x7f, vec![0x7f])]
+    #[case(0x80, vec![0x81, 0x00])]
+    #[case(0x2000, vec![0xc0, 0x00])]
+    #[case(0x3fff, vec![0xff, 0x7f])]
+    #[case(0x0fffffff, vec![0xff, 0xff, 0xff, 0x7f])]
+    fn variable_length_quantities(#[case] value: u32, #[case] expected: Vec<u8>) {
+        let mut bytes = vec![];
+        write_varlen(&mut bytes, value);
+        assert_eq!(bytes, expected);
+        assert_eq!(Reader { bytes: &bytes, at: 0 }.varlen().unwrap(), value);
+    }
+
+    #[test]
+    fn header_and_track_chunks() {
+        let smf = Smf {
+            format: Format::SingleTrack,
+            ticks_per_quarter: 480,
+            tracks: vec![vec![
+                event(
+                    0,
+                    Event::NoteOn {
+                        channel: 0,
+                        key: 60,
+                        velocity: 100,
+                    },
+                ),
+                event(
+                    480,
+                    Event::NoteOff {
+                        channel: 0,
+                        key: 60,
+                        velocity: 0,
+                    },
+                ),
+                event(0, Event::EndOfTrack),
+            ]],
+        };
+        assert_eq!(
+            smf.to_bytes(),
+            vec![
+                b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xe0, //
+                b'M', b'T', b'r', b'k', 0, 0, 0, 13, //
+                0x00, 0x90, 60, 100, //
+                0x83, 0x60, 0x80, 60, 0, //
+                0x00, 0xff, 0x2f, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let smf = Smf {
+            format: Format::MultiTrack,
+            ticks_per_quarter: 96,
+            tracks: vec![
+                vec![
+                    event(0, Event::TrackName("Conductor".to_string())),
+                    event(0, Event::Tempo(500_000)),
+                    event(
+                        0,
+                        Event::TimeSignature {
+                            numerator: 6,
+                            denominator: 8,
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::KeySignature {
+                            sharps: -3,
+                            minor: true,
+                        },
+                    ),
+                    event(0, Event::EndOfTrack),
+                ],
+                vec![
+                    event(
+                        0,
+                        Event::ProgramChange {
+                            channel: 2,
+                            program: 41,
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::ControlChange {
+                            channel: 2,
+                            controller: 7,
+                            value: 90,
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::PitchBend {
+                            channel: 2,
+                            value: 8192,
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::NoteOn {
+                            channel: 2,
+                            key: 64,
+                            velocity: 80,
+                        },
+                    ),
+                    event(
+                        200,
+                        Event::NoteOff {
+                            channel: 2,
+                            key: 64,
+                            velocity: 64,
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::SysEx {
+                            status: 0xf0,
+                            data: vec![0x7e, 0x7f, 0x09, 0x01, 0xf7],
+                        },
+                    ),
+                    event(
+                        0,
+                        Event::Meta {
+                            kind: 0x01,
+                            data: b"text".to_vec(),
+                        },
+                    ),
+                    event(0, Event::EndOfTrack),
+                ],
+            ],
+        };
+        assert_eq!(Smf::parse(&smf.to_bytes()).unwrap(), smf);
+    }
+
+    #[test]
+    fn running_status() {
+        let bytes = [
+            b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0, 96, //
+            b'M', b'T', b'r', b'k', 0, 0, 0, 17, //
+            0x00, 0x90, 60, 100, //
+            0x00, 64, 100, //
+            0x60, 60, 0, //
+            0x00, 64, 0, //
+            0x00, 0xff, 0x2f, 0x00,
+        ];
+        let smf = Smf::parse(&bytes).unwrap();
+        let keys: Vec<(u32, u8, u8)> = smf.tracks[0]
+            .iter()
+            .filter_map(|e| match e.event {
+                Event::NoteOn { key, velocity, .. } => Some((e.delta, key, velocity)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(keys, vec![(0, 60, 100), (0, 64, 100), (96, 60, 0), (0, 64, 0)]);
+    }
+
+    #[rstest]
+    #[case(b"RIFF".to_vec())]
+    #[case(vec![b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1])]
+    #[case(vec![b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0xe7, 0x28])]
+    #[case(vec![b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0, 0])]
+    #[case(vec![b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0, 96, b'M', b'T', b'r', b'k', 0, 0, 0, 3, 0x00, 60, 100])]
+    fn invalid_files(#[case] bytes: Vec<u8>) {
+        assert!(Smf::parse(&bytes).is_err());
+    }
+}
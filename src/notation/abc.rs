@@ -0,0 +1,1028 @@
+//! ABC notation, the plain text format folk musicians keep their tune books in.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::harmony::Profile;
+use crate::key::Key;
+use crate::midi::analysis::{keys, scale_matches};
+use crate::midi::import::{NoteEvent, TempoMap};
+use crate::notation::musicxml::import::ImportedNote;
+use crate::notation::score::Score;
+use crate::notation::spelling::{signature_notes, spell_scale, tonic_signature, Letter, SpelledNote, SpelledPitch};
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+/// Ticks per quarter note of a parsed tune.
+pub const TICKS_PER_QUARTER: u16 = 480;
+
+const WHOLE: u64 = 4 * TICKS_PER_QUARTER as u64;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid ABC tune: {0}")]
+pub struct AbcParseError(String);
+
+/// A tune read from ABC, its notes timed in ticks of [`TICKS_PER_QUARTER`].
+///
+/// Repeats are read through once, and grace notes, decorations, chord symbols and lyrics are left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbcTune {
+    /// The `X:` reference number.
+    pub reference: Option<u32>,
+    /// The first `T:` title.
+    pub title: Option<String>,
+    /// The `M:` meter of the header, none for free meter.
+    pub meter: Option<(u8, u8)>,
+    /// The `L:` unit note length as a fraction of a whole note.
+    pub unit: (u32, u32),
+    /// Ticks at which a `Q:` tempo starts and its quarter notes per minute.
+    pub tempos: Vec<(u32, f64)>,
+    /// The `K:` key of the header, none for `K:none`.
+    pub key: Option<AbcKey>,
+    /// The notes of all voices, tied notes merged into one.
+    pub notes: Vec<ImportedNote>,
+}
+
+/// A `K:` field: a tonic and a diatonic mode, with accidentals added to its signature or replacing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbcKey {
+    pub tonic: SpelledNote,
+    pub mode: diatonic::Mode,
+    /// Accidentals written after the mode, like the `^g` of `K:D ^g`.
+    pub accidentals: Vec<SpelledNote>,
+    /// Whether the accidentals are the whole signature, as with `K:D exp ^f`.
+    pub explicit: bool,
+}
+
+impl AbcKey {
+    pub fn key(&self) -> Key {
+        Key::new(self.tonic.note(), Scale::Diatonic(self.mode))
+    }
+
+    /// The key signature of the mode in sharps, negative for flats, without the added accidentals.
+    pub fn fifths(&self) -> i8 {
+        spell_scale(self.tonic, Scale::Diatonic(self.mode))
+            .iter()
+            .map(|n| n.alter)
+            .sum()
+    }
+
+    /// The alteration of each letter from C to B under the signature.
+    pub fn signature(&self) -> [i8; 7] {
+        let mut alters = [0; 7];
+        if !self.explicit {
+            for note in spell_scale(self.tonic, Scale::Diatonic(self.mode)) {
+                alters[note.letter.index()] = note.alter;
+            }
+        }
+        for note in &self.accidentals {
+            alters[note.letter.index()] = note.alter;
+        }
+        alters
+    }
+}
+
+impl FromStr for AbcKey {
+    type Err = AbcParseError;
+
+    /// Parse a key like `G`, `Ador`, `F# minor`, `Bbmix` or `D exp ^f ^c`. The Highland pipes keys `HP` and `Hp` are
+    /// read as A mixolydian.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().peekable();
+        let first = words.next().ok_or_else(|| AbcParseError("empty key".to_string()))?;
+        if first == "HP" || first == "Hp" {
+            return Ok(AbcKey {
+                tonic: SpelledNote::new(Letter::A, 0),
+                mode: diatonic::Mode::Mixolydian,
+                accidentals: vec![],
+                explicit: false,
+            });
+        }
+        let mut chars = first.chars();
+        let letter = chars
+            .next()
+            .and_then(|c| letter(c.to_ascii_uppercase()))
+            .ok_or_else(|| AbcParseError(format!("unknown key {}", s)))?;
+        let rest = chars.as_str();
+        let (alter, rest) = match rest.strip_prefix('#') {
+            Some(rest) => (1, rest),
+            None => match rest.strip_prefix('b') {
+                Some(rest) => (-1, rest),
+                None => (0, rest),
+            },
+        };
+        let mode = match rest {
+            "" => words.next_if(|w| mode(w).is_some()).and_then(mode),
+            rest => Some(mode(rest).ok_or_else(|| AbcParseError(format!("unknown mode {}", rest)))?),
+        };
+        let mut key = AbcKey {
+            tonic: SpelledNote::new(letter, alter),
+            mode: mode.unwrap_or(diatonic::Mode::Ionian),
+            accidentals: vec![],
+            explicit: false,
+        };
+        // Clefs and transpositions like `clef=bass` don't change the key.
+        for word in words {
+            match word {
+                "exp" => key.explicit = true,
+                word if word.contains('=') && !word.starts_with('=') => {}
+                word => key.accidentals.push(
+                    accidental_note(word).ok_or_else(|| AbcParseError(format!("unknown key accidental {}", word)))?,
+                ),
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl AbcTune {
+    /// Parse a single tune, from its `X:` field or first header field, through its `K:` field, to the end of its body.
+    pub fn parse(text: &str) -> Result<AbcTune, AbcParseError> {
+        let mut parser = Parser::default();
+        let mut body = false;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('%').next().unwrap_or_default().trim_end();
+            if line.trim().is_empty() {
+                if body {
+                    break;
+                }
+                continue;
+            }
+            let result = match field(line) {
+                Some(('K', value)) if !body => {
+                    body = true;
+                    parser.header_key(value)
+                }
+                Some((name, value)) => parser.field(name, value),
+                None if body => parser.music(line),
+                None => Err(AbcParseError("music before the K: field".to_string())),
+            };
+            result.map_err(|AbcParseError(e)| AbcParseError(format!("{} in line {}", e, number + 1)))?;
+        }
+        if !body {
+            return Err(AbcParseError("no K: field".to_string()));
+        }
+        let unit = parser.unit();
+        Ok(AbcTune {
+            reference: parser.reference,
+            title: parser.title,
+            meter: parser.header_meter,
+            unit,
+            tempos: parser.tempos,
+            key: parser.key,
+            notes: parser.notes,
+        })
+    }
+
+    /// The notes as MIDI notes, timed in seconds through the tempo, 120 quarter notes per minute if there is none.
+    pub fn note_events(&self) -> Vec<NoteEvent> {
+        let tempo = TempoMap::with_changes(
+            TICKS_PER_QUARTER,
+            self.tempos
+                .iter()
+                .map(|(tick, bpm)| (*tick, (60_000_000.0 / bpm).round() as u32))
+                .collect(),
+        );
+        let mut events: Vec<NoteEvent> = self
+            .notes
+            .iter()
+            .map(|note| NoteEvent {
+                pitch: note.pitch.pitch(),
+                velocity: 64,
+                channel: 0,
+                track: 0,
+                start: note.start,
+                end: note.start + note.duration,
+                start_seconds: tempo.seconds(note.start),
+                end_seconds: tempo.seconds(note.start + note.duration),
+            })
+            .collect();
+        events.sort_by_key(|n| (n.start, n.pitch));
+        events
+    }
+
+    /// The diatonic mode the tune is in, found from its notes alone. Of the modes that hold the most of the notes, it's
+    /// the one on the note the tune ends on, the written mode settling gapped scales; failing that, the one the key
+    /// profiles rank highest.
+    pub fn mode(&self, profile: Profile) -> Option<Key> {
+        let notes = self.note_events();
+        let scales = diatonic::Mode::array().map(Scale::Diatonic);
+        let matches = scale_matches(&notes, &scales);
+        let best = matches.first()?.coverage;
+        let candidates: Vec<Key> = matches
+            .iter()
+            .filter(|m| m.coverage >= best - 1e-9)
+            .map(|m| m.key)
+            .collect();
+        let end = notes.iter().map(|n| n.end).max()?;
+        let last = notes
+            .iter()
+            .filter(|n| n.end == end)
+            .min_by_key(|n| n.pitch)?
+            .pitch
+            .note;
+        let written = self.key.as_ref().map(AbcKey::key);
+        let on_last: Vec<&Key> = candidates.iter().filter(|k| k.tonic == last).collect();
+        if let Some(key) = on_last.iter().find(|k| Some(***k) == written).or(on_last.first()) {
+            return Some(**key);
+        }
+        keys(&notes, profile, &scales)
+            .into_iter()
+            .map(|k| k.key)
+            .find(|k| candidates.contains(k))
+    }
+}
+
+/// Every tune of a tune book, each starting at an `X:` field and ending at a blank line. Fields in the file header
+/// before the first tune apply to all tunes. A text without `X:` fields is read as a single tune.
+pub fn tunes(text: &str) -> Result<Vec<AbcTune>, AbcParseError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(first) = lines.iter().position(|l| l.starts_with("X:")) else {
+        return Ok(vec![AbcTune::parse(text)?]);
+    };
+    let defaults: Vec<&str> = lines[..first].iter().filter(|l| field(l).is_some()).copied().collect();
+    let mut tunes = vec![];
+    let mut tune: Option<Vec<&str>> = None;
+    for line in &lines[first..] {
+        if line.starts_with("X:") {
+            tunes.extend(tune.replace(defaults.iter().chain([line]).copied().collect()));
+        } else if line.trim().is_empty() {
+            tunes.extend(tune.take());
+        } else if let Some(tune) = &mut tune {
+            tune.push(line);
+        }
+    }
+    tunes.extend(tune);
+    tunes.iter().map(|lines| AbcTune::parse(&lines.join("\n"))).collect()
+}
+
+/// Write a score as an ABC tune in eighth note units, four bars to a line, with its chord symbols and its labels as
+/// lyrics.
+pub fn to_abc(score: &Score) -> String {
+    let mut abc = format!(
+        "X:1\nT:{}\nM:{}/{}\nL:1/8\nK:{}\n",
+        score.title,
+        score.meter.0,
+        score.meter.1,
+        key_field(score.key.zip(score.tonic))
+    );
+    let signature = signature_notes(score.fifths());
+    let measures = score.measures();
+    for (line, bars) in measures.chunks(4).enumerate() {
+        let mut words = vec![];
+        let mut music: Vec<String> = vec![];
+        for measure in bars {
+            // Accidentals last until the end of the bar.
+            let mut alters: HashMap<(Letter, i8), i8> = HashMap::new();
+            let events: Vec<String> = measure
+                .iter()
+                .map(|event| {
+                    let symbol = event.harmony.as_ref().map(|h| format!("\"{}\"", h)).unwrap_or_default();
+                    let length = match event.eighths {
+                        1 => String::new(),
+                        eighths => eighths.to_string(),
+                    };
+                    let notes: Vec<String> = event
+                        .pitches
+                        .iter()
+                        .map(|p| pitch_name(p, &mut alters, &signature))
+                        .collect();
+                    if !event.is_rest() {
+                        words.push(event.lyric.clone().unwrap_or("*".to_string()));
+                    }
+                    match &notes[..] {
+                        [] => format!("{}z{}", symbol, length),
+                        [note] => format!("{}{}{}", symbol, note, length),
+                        notes => format!("{}[{}]{}", symbol, notes.concat(), length),
+                    }
+                })
+                .collect();
+            music.push(events.join(" "));
+        }
+        let end = if (line + 1) * 4 >= measures.len() { "|]" } else { "|" };
+        abc.push_str(&format!("{} {}\n", music.join(" | "), end));
+        if bars.iter().flatten().any(|e| e.lyric.is_some()) {
+            abc.push_str(&format!("w:{}\n", words.join(" ")));
+        }
+    }
+    abc
+}
+
+/// The `K:` field of a key on a spelled tonic. Scales that aren't diatonic modes are written in the major key of their
+/// signature, except for the harmonic minor which is written in its minor key.
+fn key_field(key: Option<(Key, SpelledNote)>) -> String {
+    let Some((key, tonic)) = key else {
+        return "C".to_string();
+    };
+    match key.scale {
+        Scale::Diatonic(mode) => {
+            let mode = match mode {
+                diatonic::Mode::Ionian => "",
+                diatonic::Mode::Dorian => "dor",
+                diatonic::Mode::Phrygian => "phr",
+                diatonic::Mode::Lydian => "lyd",
+                diatonic::Mode::Mixolydian => "mix",
+                diatonic::Mode::Aeolian => "m",
+                diatonic::Mode::Locrian => "loc",
+            };
+            format!("{}{}", tonic, mode)
+        }
+        Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor) => format!("{}m", tonic),
+        _ => signature_notes(tonic_signature(tonic, key.scale))[0].to_string(),
+    }
+}
+
+/// A pitch in ABC, `C` being middle C and `c` the octave above, with an accidental where the signature and the
+/// accidentals earlier in the bar don't give its alteration.
+fn pitch_name(pitch: &SpelledPitch, alters: &mut HashMap<(Letter, i8), i8>, signature: &[SpelledNote; 7]) -> String {
+    let key = (pitch.note.letter, pitch.octave);
+    let current = alters.get(&key).copied().unwrap_or_else(|| {
+        signature
+            .iter()
+            .find(|n| n.letter == pitch.note.letter)
+            .map_or(0, |n| n.alter)
+    });
+    let accidental = match pitch.note.alter {
+        alter if alter == current => "",
+        -2 => "__",
+        -1 => "_",
+        0 => "=",
+        1 => "^",
+        _ => "^^",
+    };
+    alters.insert(key, pitch.note.alter);
+    let letter = pitch.note.letter.to_string();
+    match pitch.octave {
+        octave if octave >= 5 => format!(
+            "{}{}{}",
+            accidental,
+            letter.to_lowercase(),
+            "'".repeat((octave - 5) as usize)
+        ),
+        octave => format!("{}{}{}", accidental, letter, ",".repeat((4 - octave) as usize)),
+    }
+}
+
+/// The name and value of a field line like `T:The Kesh`.
+fn field(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+    match (chars.next(), chars.next()) {
+        (Some(name), Some(':')) if name.is_ascii_alphabetic() => Some((name, line[2..].trim())),
+        _ => None,
+    }
+}
+
+fn letter(c: char) -> Option<Letter> {
+    Letter::array().into_iter().find(|l| l.to_string().starts_with(c))
+}
+
+/// A mode name, only its first three letters counting: `m`, `min`, `minor`, `Dor`, `mixolydian`.
+fn mode(word: &str) -> Option<diatonic::Mode> {
+    let word = word.to_lowercase();
+    if word == "m" {
+        return Some(diatonic::Mode::Aeolian);
+    }
+    match word.get(..3)? {
+        "maj" | "ion" => Some(diatonic::Mode::Ionian),
+        "dor" => Some(diatonic::Mode::Dorian),
+        "phr" => Some(diatonic::Mode::Phrygian),
+        "lyd" => Some(diatonic::Mode::Lydian),
+        "mix" => Some(diatonic::Mode::Mixolydian),
+        "min" | "aeo" => Some(diatonic::Mode::Aeolian),
+        "loc" => Some(diatonic::Mode::Locrian),
+        _ => None,
+    }
+}
+
+/// A note with an accidental, like `^f` or `=b`, in any octave.
+fn accidental_note(word: &str) -> Option<SpelledNote> {
+    let alter = match word.get(..word.len().checked_sub(1)?)? {
+        "^^" => 2,
+        "^" => 1,
+        "=" => 0,
+        "_" => -1,
+        "__" => -2,
+        _ => return None,
+    };
+    let letter = letter(word.chars().last()?.to_ascii_uppercase())?;
+    Some(SpelledNote::new(letter, alter))
+}
+
+/// A note length multiplier like `3`, `/`, `3/2` or `//`, as a fraction of the unit note length.
+fn length(chars: &[char], i: &mut usize) -> Result<(u32, u32), AbcParseError> {
+    let number = |i: &mut usize| -> Result<Option<u32>, AbcParseError> {
+        let start = *i;
+        while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+        match chars[start..*i].iter().collect::<String>() {
+            digits if digits.is_empty() => Ok(None),
+            digits => digits.parse().map(Some).map_err(|_| too_long()),
+        }
+    };
+    let numerator = number(i)?.unwrap_or(1);
+    let mut denominator: u32 = 1;
+    while chars.get(*i) == Some(&'/') {
+        *i += 1;
+        denominator = denominator.checked_mul(number(i)?.unwrap_or(2)).ok_or_else(too_long)?;
+    }
+    if numerator == 0 || denominator == 0 {
+        return Err(AbcParseError("note length of zero".to_string()));
+    }
+    Ok((numerator, denominator))
+}
+
+/// The error for a length or a time past the last tick a `u32` can hold.
+fn too_long() -> AbcParseError {
+    AbcParseError("note length too long".to_string())
+}
+
+/// The place just after the closing `end`, or just after `start` if it isn't closed.
+fn skip_past(chars: &[char], start: usize, end: char) -> usize {
+    chars[start + 1..]
+        .iter()
+        .position(|c| *c == end)
+        .map_or(start + 1, |at| start + at + 2)
+}
+
+/// The state of reading a tune, line by line.
+#[derive(Default)]
+struct Parser {
+    reference: Option<u32>,
+    title: Option<String>,
+    header_meter: Option<(u8, u8)>,
+    key: Option<AbcKey>,
+    meter: Option<(u8, u8)>,
+    unit: Option<(u32, u32)>,
+    tempos: Vec<(u32, f64)>,
+    signature: [i8; 7],
+    notes: Vec<ImportedNote>,
+    time: u32,
+    /// The time reached in each voice but the current one.
+    voices: HashMap<String, u32>,
+    voice: String,
+    /// Accidentals written earlier in the bar.
+    bar: HashMap<(Letter, i8), i8>,
+    /// Notes tied to the next note.
+    tied: Vec<usize>,
+    /// The notes and length of the last note, chord or rest.
+    previous: (Vec<usize>, u32),
+    /// The length factor of the next note after a broken rhythm.
+    broken: Option<(u32, u32)>,
+    /// The notes left in a tuplet, and the `p` notes it puts in the time of `q`.
+    tuplet: Option<(u32, u32, u32)>,
+}
+
+impl Parser {
+    /// The unit note length, by default an eighth, or a sixteenth in meters shorter than 3/4.
+    fn unit(&self) -> (u32, u32) {
+        self.unit.unwrap_or(match self.meter {
+            Some((beats, value)) if (beats as u32) * 4 < 3 * value as u32 => (1, 16),
+            _ => (1, 8),
+        })
+    }
+
+    fn ticks(&self, (numerator, denominator): (u32, u32)) -> Result<u32, AbcParseError> {
+        let (unit_numerator, unit_denominator) = self.unit();
+        let whole = WHOLE as u128 * unit_numerator as u128 * numerator as u128;
+        let denominator = unit_denominator as u128 * denominator as u128;
+        u32::try_from((whole + denominator / 2) / denominator).map_err(|_| too_long())
+    }
+
+    fn header_key(&mut self, value: &str) -> Result<(), AbcParseError> {
+        self.header_meter = self.meter;
+        self.field('K', value)?;
+        self.key = match value.split_whitespace().next() {
+            None | Some("none") => None,
+            Some(_) => Some(value.parse()?),
+        };
+        Ok(())
+    }
+
+    fn field(&mut self, name: char, value: &str) -> Result<(), AbcParseError> {
+        match name {
+            'X' => self.reference = value.parse().ok(),
+            'T' if self.title.is_none() => self.title = Some(value.to_string()),
+            'M' => self.meter = meter(value)?,
+            'L' => {
+                self.unit =
+                    Some(fraction(value).ok_or_else(|| AbcParseError(format!("unknown unit note length {}", value)))?)
+            }
+            'Q' => {
+                if let Some(bpm) = self.tempo(value) {
+                    self.tempos.push((self.time, bpm));
+                }
+            }
+            'K' => {
+                self.signature = match value.split_whitespace().next() {
+                    None | Some("none") => [0; 7],
+                    Some(_) => value.parse::<AbcKey>()?.signature(),
+                }
+            }
+            'V' => {
+                let voice = value.split_whitespace().next().unwrap_or_default().to_string();
+                let time = self.voices.remove(&voice).unwrap_or(0);
+                self.voices.insert(std::mem::replace(&mut self.voice, voice), self.time);
+                self.time = time;
+                self.bar.clear();
+                self.tied.clear();
+                self.previous = (vec![], 0);
+                (self.broken, self.tuplet) = (None, None);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// A tempo like `1/4=120`, `3/8=60`, `"Allegro" 1/4=132` or the old `120`, in quarter notes per minute.
+    fn tempo(&self, value: &str) -> Option<f64> {
+        let value: String = value.split('"').step_by(2).collect();
+        let (beats, bpm) = match value.split_once('=') {
+            Some((beats, bpm)) => {
+                let beats: Option<Vec<(u32, u32)>> = beats.split_whitespace().map(fraction).collect();
+                (beats?, bpm)
+            }
+            None => (vec![self.unit()], value.as_str()),
+        };
+        let bpm: f64 = bpm.trim().parse().ok()?;
+        let quarters: f64 = beats.iter().map(|(n, d)| 4.0 * *n as f64 / *d as f64).sum();
+        Some(bpm * quarters).filter(|bpm| *bpm > 0.0)
+    }
+
+    fn music(&mut self, line: &str) -> Result<(), AbcParseError> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                // Chord symbols, annotations, decorations and grace notes.
+                '"' => i = skip_past(&chars, i, '"'),
+                '!' => i = skip_past(&chars, i, '!'),
+                '+' => i = skip_past(&chars, i, '+'),
+                '{' => i = skip_past(&chars, i, '}'),
+                '|' | ':' => {
+                    while chars.get(i).is_some_and(|c| matches!(c, '|' | ':' | ']')) {
+                        i += 1;
+                    }
+                    // Repeat endings like `|1` or `:|2`.
+                    while chars
+                        .get(i)
+                        .is_some_and(|c| c.is_ascii_digit() || matches!(c, ',' | '-'))
+                    {
+                        i += 1;
+                    }
+                    self.bar.clear();
+                }
+                '[' => match (chars.get(i + 1), chars.get(i + 2)) {
+                    (Some('|'), _) => i += 1,
+                    (Some(c), _) if c.is_ascii_digit() => i += 1,
+                    (Some(name), Some(':')) if name.is_ascii_alphabetic() => {
+                        let end = skip_past(&chars, i, ']');
+                        let value: String = chars[i + 3..end.saturating_sub(1).max(i + 3)].iter().collect();
+                        self.field(*name, value.trim())?;
+                        i = end;
+                    }
+                    _ => self.chord(&chars, &mut i)?,
+                },
+                '(' => {
+                    i += 1;
+                    if chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                        self.tuplet(&chars, &mut i);
+                    }
+                }
+                '>' | '<' => {
+                    let start = i;
+                    while chars.get(i) == Some(&chars[start]) {
+                        i += 1;
+                    }
+                    self.broken_rhythm(chars[start] == '>', i - start)?;
+                }
+                'z' | 'x' => {
+                    i += 1;
+                    let length = length(&chars, &mut i)?;
+                    self.play(vec![], length)?;
+                }
+                'Z' | 'X' => {
+                    i += 1;
+                    let (bars, _) = length(&chars, &mut i)?;
+                    let (beats, value) = self.meter.unwrap_or((4, 4));
+                    let bar = beats as u64 * 4 * TICKS_PER_QUARTER as u64 / value as u64;
+                    self.time = u32::try_from(self.time as u64 + bars as u64 * bar).map_err(|_| too_long())?;
+                    self.previous = (vec![], 0);
+                    self.bar.clear();
+                }
+                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                    let (pitch, length, tie) = self.note(&chars, &mut i)?;
+                    self.play(vec![(pitch, tie)], length)?;
+                }
+                _ => i += 1,
+            }
+        }
+        Ok(())
+    }
+
+    /// A note with its accidental, octave marks, length and tie.
+    fn note(&mut self, chars: &[char], i: &mut usize) -> Result<(SpelledPitch, (u32, u32), bool), AbcParseError> {
+        let start = *i;
+        while let Some('^' | '_' | '=') = chars.get(*i) {
+            *i += 1;
+        }
+        let accidental = match chars[start..*i].iter().collect::<String>().as_str() {
+            "" => None,
+            "^" => Some(1),
+            "^^" => Some(2),
+            "_" => Some(-1),
+            "__" => Some(-2),
+            "=" => Some(0),
+            other => return Err(AbcParseError(format!("unknown accidental {}", other))),
+        };
+        let c = *chars
+            .get(*i)
+            .ok_or_else(|| AbcParseError("accidental without a note".to_string()))?;
+        let letter = letter(c.to_ascii_uppercase()).ok_or_else(|| AbcParseError(format!("unexpected {}", c)))?;
+        let mut octave: i8 = if c.is_ascii_uppercase() { 4 } else { 5 };
+        *i += 1;
+        while let Some(mark @ ('\'' | ',')) = chars.get(*i) {
+            octave = octave
+                .checked_add(if *mark == '\'' { 1 } else { -1 })
+                .ok_or_else(|| AbcParseError("octave out of range".to_string()))?;
+            *i += 1;
+        }
+        let length = length(chars, i)?;
+        let tie = chars.get(*i) == Some(&'-');
+        if tie {
+            *i += 1;
+        }
+        let alter = match accidental {
+            Some(alter) => {
+                self.bar.insert((letter, octave), alter);
+                alter
+            }
+            None => self
+                .bar
+                .get(&(letter, octave))
+                .copied()
+                .unwrap_or(self.signature[letter.index()]),
+        };
+        Ok((SpelledPitch::new(SpelledNote::new(letter, alter), octave), length, tie))
+    }
+
+    /// A chord like `[CEG]2`, as long as its first note times its own length.
+    fn chord(&mut self, chars: &[char], i: &mut usize) -> Result<(), AbcParseError> {
+        *i += 1;
+        let mut pitches = vec![];
+        let mut first: Option<(u32, u32)> = None;
+        while let Some(c) = chars.get(*i) {
+            match c {
+                ']' => break,
+                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                    let (pitch, length, tie) = self.note(chars, i)?;
+                    first.get_or_insert(length);
+                    pitches.push((pitch, tie));
+                }
+                _ => *i += 1,
+            }
+        }
+        *i += 1;
+        let (numerator, denominator) = length(chars, i)?;
+        if chars.get(*i) == Some(&'-') {
+            *i += 1;
+            pitches.iter_mut().for_each(|(_, tie)| *tie = true);
+        }
+        let (first_numerator, first_denominator) = first.unwrap_or((1, 1));
+        let length = (
+            first_numerator.checked_mul(numerator).ok_or_else(too_long)?,
+            first_denominator.checked_mul(denominator).ok_or_else(too_long)?,
+        );
+        self.play(pitches, length)
+    }
+
+    /// A tuplet `(p:q:r`: the next `r` notes, `p` of them in the time of `q`.
+    fn tuplet(&mut self, chars: &[char], i: &mut usize) {
+        let mut numbers = [None; 3];
+        for (n, number) in numbers.iter_mut().enumerate() {
+            if n > 0 {
+                if chars.get(*i) != Some(&':') {
+                    break;
+                }
+                *i += 1;
+            }
+            let start = *i;
+            while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+                *i += 1;
+            }
+            *number = chars[start..*i].iter().collect::<String>().parse::<u32>().ok();
+        }
+        let p = numbers[0].unwrap_or(3).max(1);
+        let compound = self.meter.is_some_and(|(beats, _)| beats % 3 == 0 && beats > 3);
+        let q = numbers[1].unwrap_or(match p {
+            2 | 4 | 8 => 3,
+            3 | 6 => 2,
+            _ if compound => 3,
+            _ => 2,
+        });
+        self.tuplet = Some((numbers[2].unwrap_or(p), p, q));
+    }
+
+    /// `>` dots the note before and halves the one after, `>>` double dots it, and `<` goes the other way.
+    fn broken_rhythm(&mut self, first_longer: bool, count: usize) -> Result<(), AbcParseError> {
+        let denominator: u32 = u32::try_from(count)
+            .ok()
+            .and_then(|count| 1u32.checked_shl(count))
+            .filter(|d| *d <= u32::MAX / 2)
+            .ok_or_else(too_long)?;
+        let (first, second) = match first_longer {
+            true => (2 * denominator - 1, 1),
+            false => (1, 2 * denominator - 1),
+        };
+        let (notes, length) = &self.previous;
+        let changed = *length as u64 * first as u64 / denominator as u64;
+        let change = |time: u32| u32::try_from((time as u64 + changed).saturating_sub(*length as u64));
+        self.time = change(self.time).map_err(|_| too_long())?;
+        for note in notes {
+            self.notes[*note].duration = change(self.notes[*note].duration).map_err(|_| too_long())?;
+        }
+        self.broken = Some((second, denominator));
+        Ok(())
+    }
+
+    /// Play some pitches together, or a rest without pitches, for a length in units.
+    fn play(
+        &mut self,
+        pitches: Vec<(SpelledPitch, bool)>,
+        (mut numerator, mut denominator): (u32, u32),
+    ) -> Result<(), AbcParseError> {
+        let times = |a: u32, b: u32| a.checked_mul(b).ok_or_else(too_long);
+        if let Some((n, d)) = self.broken.take() {
+            (numerator, denominator) = (times(numerator, n)?, times(denominator, d)?);
+        }
+        if let Some((left, p, q)) = self.tuplet {
+            (numerator, denominator) = (times(numerator, q)?, times(denominator, p)?);
+            self.tuplet = Some((left - 1, p, q)).filter(|(left, _, _)| *left > 0);
+        }
+        let duration = self.ticks((numerator, denominator))?;
+        let end = self.time.checked_add(duration).ok_or_else(too_long)?;
+        let tied = std::mem::take(&mut self.tied);
+        let mut played = vec![];
+        for (pitch, tie) in pitches {
+            let continued = tied.iter().copied().find(|t| {
+                let note = &self.notes[*t];
+                note.pitch.midi() == pitch.midi() && note.start + note.duration == self.time
+            });
+            let index = match continued {
+                Some(index) => {
+                    self.notes[index].duration += duration;
+                    index
+                }
+                None => {
+                    self.notes.push(ImportedNote {
+                        pitch,
+                        start: self.time,
+                        duration,
+                    });
+                    self.notes.len() - 1
+                }
+            };
+            if tie {
+                self.tied.push(index);
+            }
+            played.push(index);
+        }
+        self.previous = (played, duration);
+        self.time = end;
+        Ok(())
+    }
+}
+
+/// A meter like `6/8`, `2+3/8`, `C` or `C|`, none for `none`.
+fn meter(value: &str) -> Result<Option<(u8, u8)>, AbcParseError> {
+    let error = || AbcParseError(format!("unknown meter {}", value));
+    match value {
+        "" | "none" => Ok(None),
+        "C" => Ok(Some((4, 4))),
+        "C|" => Ok(Some((2, 2))),
+        value => {
+            let (beats, unit) = value.split_once('/').ok_or_else(error)?;
+            let beats: Option<u32> = beats
+                .trim_matches(['(', ')'])
+                .split('+')
+                .map(|b| b.trim().parse::<u32>().ok())
+                .sum();
+            let beats = beats.and_then(|b| u8::try_from(b).ok()).ok_or_else(error)?;
+            let unit: u8 = unit.trim().parse().ok().filter(|u| *u > 0).ok_or_else(error)?;
+            Ok(Some((beats, unit)))
+        }
+    }
+}
+
+fn fraction(value: &str) -> Option<(u32, u32)> {
+    let (numerator, denominator) = value.trim().split_once('/')?;
+    let fraction = (numerator.parse().ok()?, denominator.parse().ok()?);
+    Some(fraction).filter(|(n, d)| *n > 0 && *d > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::midi::export::Direction;
+    use crate::notation::score::Labels;
+    use crate::note::Note;
+
+    use super::*;
+
+    fn tune(body: &str) -> AbcTune {
+        AbcTune::parse(&format!("X:1\nT:Test\nM:4/4\nL:1/8\nK:D\n{}", body)).unwrap()
+    }
+
+    /// The notes as `name:start:duration`, in eighths.
+    fn notes(tune: &AbcTune) -> String {
+        tune.notes
+            .iter()
+            .map(|n| format!("{}:{}:{}", n.pitch, n.start / 240, n.duration as f64 / 240.0))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[rstest]
+    #[case("G", "G", diatonic::Mode::Ionian, 1)]
+    #[case("Ador", "A", diatonic::Mode::Dorian, 1)]
+    #[case("F#m", "F#", diatonic::Mode::Aeolian, 3)]
+    #[case("Bbmix", "Bb", diatonic::Mode::Mixolydian, -3)]
+    #[case("E Minor", "E", diatonic::Mode::Aeolian, 1)]
+    #[case("D dorian clef=bass", "D", diatonic::Mode::Dorian, 0)]
+    #[case("C#", "C#", diatonic::Mode::Ionian, 7)]
+    #[case("HP", "A", diatonic::Mode::Mixolydian, 2)]
+    fn keys(#[case] field: &str, #[case] tonic: &str, #[case] mode: diatonic::Mode, #[case] fifths: i8) {
+        let key: AbcKey = field.parse().unwrap();
+        assert_eq!(key.tonic, tonic.parse().unwrap());
+        assert_eq!(key.mode, mode);
+        assert_eq!(key.fifths(), fifths);
+    }
+
+    #[test]
+    fn key_accidentals() {
+        let key: AbcKey = "D ^g".parse().unwrap();
+        assert_eq!(key.signature(), [1, 0, 0, 1, 1, 0, 0]);
+        let key: AbcKey = "D exp _b".parse().unwrap();
+        assert_eq!(key.signature(), [0, 0, 0, 0, 0, 0, -1]);
+        assert!("H".parse::<AbcKey>().is_err());
+        assert!("Dfoo".parse::<AbcKey>().is_err());
+    }
+
+    #[test]
+    fn header() {
+        let tune = AbcTune::parse("X:12\nT:The Kesh\nT:The Kesh Jig\nM:6/8\nL:1/8\nQ:3/8=120\nK:G\nGAG GAB|").unwrap();
+        assert_eq!(tune.reference, Some(12));
+        assert_eq!(tune.title.as_deref(), Some("The Kesh"));
+        assert_eq!(tune.meter, Some((6, 8)));
+        assert_eq!(tune.tempos, vec![(0, 180.0)]);
+        assert_eq!(
+            tune.key.unwrap().key(),
+            Key::new(Note::G, Scale::Diatonic(diatonic::Mode::Ionian))
+        );
+        assert_eq!(tune.notes.len(), 6);
+    }
+
+    #[rstest]
+    #[case("M:C\n", (1, 8))]
+    #[case("M:2/4\n", (1, 16))]
+    #[case("M:none\n", (1, 8))]
+    #[case("L:1/4\n", (1, 4))]
+    fn default_unit(#[case] fields: &str, #[case] unit: (u32, u32)) {
+        let tune = AbcTune::parse(&format!("X:1\n{}K:C\nC", fields)).unwrap();
+        assert_eq!(tune.unit, unit);
+    }
+
+    #[rstest]
+    #[case("A B2 c/ d3/2 e// f/4", "A4:0:1 B4:1:2 C#5:3:0.5 D5:3:1.5 E5:5:0.25 F#5:5:0.25")]
+    #[case("A,, a' z2 [DFA]2 G", "A2:0:1 A6:1:1 D4:4:2 F#4:4:2 A4:4:2 G4:6:1")]
+    #[case("A>B c<d A>>B", "A4:0:1.5 B4:1:0.5 C#5:2:0.5 D5:2:1.5 A4:4:1.75 B4:5:0.25")]
+    #[case("(3ABc d2 (3:2:2A2B d", "A4:0:0.6666666666666666 B4:0:0.6666666666666666 C#5:1:0.6666666666666666 D5:2:2 A4:4:1.3333333333333333 B4:5:0.6666666666666666 D5:6:1")]
+    #[case("A2-A2 [CE]-[CE]", "A4:0:4 C#4:4:2 E4:4:2")]
+    #[case(
+        "^f f | f =f _B B c ^^c",
+        "F#5:0:1 F#5:1:1 F#5:2:1 F5:3:1 Bb4:4:1 Bb4:5:1 C#5:6:1 C##5:7:1"
+    )]
+    fn bodies(#[case] body: &str, #[case] expected: &str) {
+        assert_eq!(notes(&tune(body)), expected);
+    }
+
+    #[test]
+    fn decorations_and_repeats() {
+        let tune = tune("|:\"Am\"!trill!A {g}B .c ~d|1 e2 f2:|2 [K:Ddor]f2 g2|]\nw: la la");
+        assert_eq!(
+            notes(&tune),
+            "A4:0:1 B4:1:1 C#5:2:1 D5:3:1 E5:4:2 F#5:6:2 F5:8:2 G5:10:2"
+        );
+    }
+
+    #[test]
+    fn voices() {
+        let tune = tune("V:1\nABcd|\nV:2\nD4 F4|\nV:1\nefga|");
+        assert_eq!(
+            notes(&tune),
+            "A4:0:1 B4:1:1 C#5:2:1 D5:3:1 D4:0:4 F#4:4:4 E5:4:1 F#5:5:1 G5:6:1 A5:7:1"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(AbcTune::parse("X:1\nT:No key\nABC").is_err());
+        assert!(AbcTune::parse("X:1\nT:No key\n").is_err());
+        assert!(AbcTune::parse("X:1\nM:6/x\nK:C\nC").is_err());
+        assert!(AbcTune::parse("X:1\nK:C\nC/0").is_err());
+        let error = AbcTune::parse("X:1\nK:C\nC\nK:Xyz\nD").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid ABC tune: unknown key Xyz in line 4");
+    }
+
+    #[rstest]
+    #[case("Z99999999")]
+    #[case("C4294967296")]
+    #[case("C/65536/65536")]
+    #[case("C9000000 C9000000")]
+    #[case("[C65536E]65536")]
+    #[case("C>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>D")]
+    #[case("(3:4294967295C4294967295")]
+    #[case("^^^C")]
+    #[case("_^C")]
+    #[case("==C")]
+    #[case("M:4/0\nZ")]
+    fn out_of_range(#[case] body: &str) {
+        assert!(AbcTune::parse(&format!("X:1\nK:C\n{}", body)).is_err());
+    }
+
+    #[rstest]
+    #[case("^", "C")]
+    #[case("C", ",")]
+    #[case("c", "'")]
+    fn repeated_marks(#[case] before: &str, #[case] after: &str) {
+        let body = format!("{}{}", before.repeat(300), after.repeat(300));
+        assert!(AbcTune::parse(&format!("X:1\nK:C\n{}", body)).is_err());
+    }
+
+    #[test]
+    fn tune_book() {
+        let book =
+            "%abc-2.1\nL:1/4\n\nX:1\nT:First\nK:G\nGABc|\n\nSome notes on the tunes.\n\nX:2\nT:Second\nK:Em\nE2B2|\n";
+        let tunes = tunes(book).unwrap();
+        assert_eq!(tunes.len(), 2);
+        assert_eq!(tunes[0].unit, (1, 4));
+        assert_eq!(tunes[1].title.as_deref(), Some("Second"));
+        assert_eq!(tunes[1].notes[1].duration, 960);
+    }
+
+    #[rstest]
+    // The Kesh, written in G major.
+    #[case("K:G\nGAG GAB|ABA ABd|edd gdd|edB dBA|GAG GAB|ABA ABd|edd gdB|AGF G3|]", "G major")]
+    // Out on the Ocean, a G major jig written as E minor would be read as G all the same.
+    #[case("K:Em\nGED DED|GED DEG|ABA ABA|dBA ABA|GED DED|GED DEG|ABA cBA|BGF G3|]", "G major")]
+    // Drowsy Maggie, in E dorian.
+    #[case(
+        "K:Edor\nE2BE dEBE|E2BE AFDF|E2BE dEBE|BABc dAFD|E2BE dEBE|E2BE AFDF|B2AB dAFD|FEDF E4|]",
+        "E dorian"
+    )]
+    // A D major reel without C or G, which could as well be in D lydian.
+    #[case("K:D\nFA A2 BAFA|dfed BddB|FA A2 BAFA|dfed BAFA|d4 z4|]", "D major")]
+    #[case("K:D\nA2 FA GAFA|d2 cd e2 dc|ABAF G2 FG|A2 AG FDD2|]", "D major")]
+    #[case("K:Amix\nA2 eA fAeA|BAGB dGBG|A2 eA fAeA|gefd eA A2|]", "A mixolydian")]
+    #[case("K:Ador\nABcd e2 dB|GABG d2 BG|ABcd e2 ge|dBGB A4|]", "A dorian")]
+    fn modes(#[case] body: &str, #[case] expected: &str) {
+        let tune = AbcTune::parse(&format!("X:1\nM:4/4\nL:1/8\n{}", body)).unwrap();
+        assert_eq!(tune.mode(Profile::KrumhanslSchmuckler).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn scale_export() {
+        let key = Key::new(Note::Bb, Scale::Diatonic(diatonic::Mode::Dorian));
+        let mut score = Score::scale(&key, 4, Direction::Ascending);
+        score.label(Labels::Degrees);
+        let abc = to_abc(&score);
+        assert_eq!(
+            abc,
+            "X:1\nT:Bb dorian\nM:4/4\nL:1/8\nK:Bbdor\nB2 c2 d2 e2 | f2 g2 a2 b2 |]\nw:1 2 3 4 5 6 7 1\n"
+        );
+        let tune = AbcTune::parse(&abc).unwrap();
+        let pitches: Vec<SpelledPitch> = tune.notes.iter().map(|n| n.pitch).collect();
+        let written: Vec<SpelledPitch> = score.events.iter().map(|e| e.pitches[0]).collect();
+        assert_eq!(pitches, written);
+        assert_eq!(tune.mode(Profile::KrumhanslSchmuckler), Some(key));
+    }
+
+    #[test]
+    fn accidentals_in_export() {
+        let key = Key::new(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+        let abc = to_abc(&Score::scale(&key, 4, Direction::Both));
+        assert!(abc.contains("K:Am\n"));
+        assert!(abc.contains("A2 B2 c2 d2 | e2 f2 ^g2 a2 | ^g2 f2 e2 d2 | c2 B2 A2 z2 |]\n"));
+        assert!(!abc.contains("w:"));
+    }
+
+    #[test]
+    fn arpeggio_export() {
+        let abc = to_abc(&Score::arpeggio(&"A#m7b5".parse().unwrap(), 4, Direction::Ascending));
+        assert!(abc.contains("K:C\n"));
+        assert!(abc.contains("\"A#m7b5\"^A2 ^c2 e2 ^g2 | ^a2 z6 |]"));
+        let tune = AbcTune::parse(&abc).unwrap();
+        assert_eq!(tune.notes.len(), 5);
+        assert_eq!(tune.notes[4].pitch.to_string(), "A#5");
+    }
+}
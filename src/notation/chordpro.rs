@@ -0,0 +1,677 @@
+//! ChordPro song files: lyrics with the chords in brackets where they fall, like `[Am]Hello [F]world`, and directives
+//! in braces, like `{title: Song}`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::symbol::parse_root;
+use crate::key::Key;
+use crate::notation::spelling::{key_tonic, spell_in, spell_scale, SpelledNote};
+use crate::scales::{diatonic, Scale};
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid ChordPro file: {0}")]
+pub struct ChordProParseError(String);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid chord {0}")]
+pub struct SongChordParseError(String);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid key {0}")]
+pub struct SongKeyParseError(String);
+
+/// A song as written in a ChordPro file, line by line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Song {
+    pub lines: Vec<SongLine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SongLine {
+    Directive(Directive),
+    /// Lyrics with chords, or chords alone.
+    Lyrics(Vec<Segment>),
+    /// Lines kept as they are: comments, and the lines of tabs and grids.
+    Verbatim(String),
+    Empty,
+}
+
+/// A directive like `{title: Song}` or `{start_of_chorus}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Directive {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// A chord and the lyrics sung from it up to the next chord. The lyrics before the first chord of a line have no chord.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub chord: Option<SongChord>,
+    pub lyrics: String,
+}
+
+/// What is written in brackets over the lyrics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SongChord {
+    /// A chord with a spelled root and bass. The suffix is kept as written, as transposing leaves it alone.
+    Named {
+        root: SpelledNote,
+        suffix: String,
+        bass: Option<SpelledNote>,
+    },
+    /// Anything else, like `N.C.` or an annotation like `*Riff`.
+    Other(String),
+}
+
+/// The key of a song, a spelled tonic in major or minor, written like `Eb` or `C#m`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SongKey {
+    pub tonic: SpelledNote,
+    pub minor: bool,
+}
+
+impl SongKey {
+    pub fn key(&self) -> Key {
+        let mode = match self.minor {
+            true => diatonic::Mode::Aeolian,
+            false => diatonic::Mode::Ionian,
+        };
+        Key::new(self.tonic.note(), Scale::Diatonic(mode))
+    }
+
+    /// The key a number of semitones higher, on the spelling of the tonic with the fewest accidentals.
+    pub fn transpose(&self, semitones: isize) -> SongKey {
+        let key = self.key();
+        SongKey {
+            tonic: key_tonic(&Key::new(key.tonic.transpose(semitones), key.scale)),
+            minor: self.minor,
+        }
+    }
+
+    /// The spelling of a note in this key: as in the scale if it belongs to it, otherwise as [`spell_in`] spells it.
+    fn spell(&self, note: SpelledNote) -> SpelledNote {
+        if note.accidentals() < 2 {
+            return note;
+        }
+        let fifths = spell_scale(self.tonic, self.key().scale).iter().map(|n| n.alter).sum();
+        spell_in(note.note(), fifths)
+    }
+}
+
+impl FromStr for SongKey {
+    type Err = SongKeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || SongKeyParseError(s.to_string());
+        let (tonic, rest) = spelled_root(s.trim()).ok_or_else(error)?;
+        let minor = match rest {
+            "" | "maj" | "major" => false,
+            "m" | "min" | "minor" => true,
+            _ => return Err(error()),
+        };
+        Ok(SongKey { tonic, minor })
+    }
+}
+
+impl fmt::Display for SongKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.tonic, if self.minor { "m" } else { "" })
+    }
+}
+
+impl SongChord {
+    /// The chord moved from one key to another: each note goes up by the interval between the tonics, letters
+    /// included, so that the chords keep their function. A note that would take a double sharp or flat is respelled.
+    fn move_between(&self, from: &SongKey, to: &SongKey) -> SongChord {
+        let SongChord::Named { root, suffix, bass } = self else {
+            return self.clone();
+        };
+        let letters = (to.tonic.letter.index() + 7 - from.tonic.letter.index()) % 7;
+        let semitones = to.tonic.note().semitones_from_c() as isize - from.tonic.note().semitones_from_c() as isize;
+        let step = |note: &SpelledNote| {
+            to.spell(SpelledNote::on(
+                note.letter.up(letters),
+                note.note().transpose(semitones),
+            ))
+        };
+        SongChord::Named {
+            root: step(root),
+            suffix: suffix.clone(),
+            bass: bass.as_ref().map(step),
+        }
+    }
+}
+
+impl FromStr for SongChord {
+    type Err = SongChordParseError;
+
+    /// Parse a chord like `F#m7` or `Bb/D`. The suffix can be anything, as long as there is a root.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (root, rest) = spelled_root(s).ok_or_else(|| SongChordParseError(s.to_string()))?;
+        let (suffix, bass) = match rest.rfind('/') {
+            Some(i) => match spelled_root(&rest[i + 1..]) {
+                Some((bass, "")) => (&rest[..i], Some(bass)),
+                _ => (rest, None),
+            },
+            None => (rest, None),
+        };
+        Ok(SongChord::Named {
+            root,
+            suffix: suffix.to_string(),
+            bass,
+        })
+    }
+}
+
+impl fmt::Display for SongChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SongChord::Named { root, suffix, bass } => {
+                write!(f, "{}{}", root, suffix)?;
+                if let Some(bass) = bass {
+                    write!(f, "/{}", bass)?;
+                }
+                Ok(())
+            }
+            SongChord::Other(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl FromStr for Song {
+    type Err = ChordProParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = vec![];
+        let mut verbatim = false;
+        for (number, line) in s.lines().enumerate() {
+            let error = |e: &str| ChordProParseError(format!("{} in line {}", e, number + 1));
+            let trimmed = line.trim();
+            let line = if let Some(directive) = trimmed.strip_prefix('{') {
+                let directive = directive.strip_suffix('}').ok_or_else(|| error("unclosed {"))?;
+                let (name, value) = match directive.find([':', ' ']) {
+                    Some(i) => (&directive[..i], Some(directive[i + 1..].trim().to_string())),
+                    None => (directive, None),
+                };
+                let name = name.trim().to_lowercase();
+                match canonical(&name) {
+                    "start_of_tab" | "start_of_grid" => verbatim = true,
+                    "end_of_tab" | "end_of_grid" => verbatim = false,
+                    _ => {}
+                }
+                SongLine::Directive(Directive {
+                    name,
+                    value: value.filter(|v| !v.is_empty()),
+                })
+            } else if verbatim || trimmed.starts_with('#') {
+                SongLine::Verbatim(line.to_string())
+            } else if trimmed.is_empty() {
+                SongLine::Empty
+            } else {
+                SongLine::Lyrics(segments(line).ok_or_else(|| error("unclosed ["))?)
+            };
+            lines.push(line);
+        }
+        Ok(Song { lines })
+    }
+}
+
+impl Song {
+    /// The value of the first directive with this name or one of its short forms.
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            SongLine::Directive(d) if canonical(&d.name) == name => d.value.as_deref(),
+            _ => None,
+        })
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.directive("title")
+    }
+
+    /// The fret of the capo, 0 without one.
+    pub fn capo(&self) -> u8 {
+        self.directive("capo").and_then(|c| c.parse().ok()).unwrap_or(0)
+    }
+
+    /// The key the chords are written in: the `{key}` directive if there is one, otherwise the key of the first chord,
+    /// minor if its suffix starts with `m` but not `maj`.
+    pub fn key(&self) -> Option<SongKey> {
+        if let Some(key) = self.directive("key").and_then(|k| k.parse().ok()) {
+            return Some(key);
+        }
+        self.chords().find_map(|chord| match chord {
+            SongChord::Named { root, suffix, .. } => Some(SongKey {
+                tonic: *root,
+                minor: suffix.starts_with('m') && !suffix.starts_with("maj"),
+            }),
+            SongChord::Other(_) => None,
+        })
+    }
+
+    pub fn chords(&self) -> impl Iterator<Item = &SongChord> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                SongLine::Lyrics(segments) => Some(segments),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|s| s.chord.as_ref())
+    }
+
+    /// Move every chord into another key, spelled after their function in the old one: the `B7` of a song in G is `G7`
+    /// in Eb. The `{key}` directive, if there is one, is changed too. A song without chords is left as it is.
+    pub fn transpose_to(&mut self, to: &SongKey) {
+        let Some(from) = self.key() else {
+            return;
+        };
+        let to = SongKey {
+            tonic: to.tonic,
+            minor: from.minor,
+        };
+        for line in &mut self.lines {
+            if let SongLine::Lyrics(segments) = line {
+                for segment in segments {
+                    segment.chord = segment.chord.as_ref().map(|c| c.move_between(&from, &to));
+                }
+            }
+        }
+        self.set_directive("key", Some(to.to_string()), false);
+    }
+
+    /// Move every chord a number of semitones up, into the spelling of the new key with the fewest accidentals.
+    pub fn transpose(&mut self, semitones: isize) {
+        if let Some(key) = self.key() {
+            self.transpose_to(&key.transpose(semitones));
+        }
+    }
+
+    /// Put the capo on another fret, moving the chord shapes so that the song sounds the same, and set the `{capo}`
+    /// directive, or take it out for no capo.
+    pub fn set_capo(&mut self, capo: u8) {
+        self.transpose(self.capo() as isize - capo as isize);
+        self.set_directive("capo", Some(capo.to_string()).filter(|_| capo > 0), true);
+    }
+
+    /// Change the value of a directive, adding it after the title if it's missing and `add` is set, or remove it
+    /// without a value.
+    fn set_directive(&mut self, name: &str, value: Option<String>, add: bool) {
+        let position = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, SongLine::Directive(d) if canonical(&d.name) == name));
+        match (position, value) {
+            (Some(i), Some(value)) => {
+                if let SongLine::Directive(directive) = &mut self.lines[i] {
+                    directive.value = Some(value);
+                }
+            }
+            (Some(i), None) => {
+                self.lines.remove(i);
+            }
+            (None, Some(value)) if add => {
+                let after = self
+                    .lines
+                    .iter()
+                    .rposition(|line| {
+                        matches!(line, SongLine::Directive(d) if matches!(canonical(&d.name), "title" | "subtitle" | "artist" | "key"))
+                    })
+                    .map_or(0, |i| i + 1);
+                let directive = Directive {
+                    name: name.to_string(),
+                    value: Some(value),
+                };
+                self.lines.insert(after, SongLine::Directive(directive));
+            }
+            (None, _) => {}
+        }
+    }
+
+    /// Write the song back as ChordPro.
+    pub fn to_chordpro(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            match line {
+                SongLine::Directive(Directive {
+                    name,
+                    value: Some(value),
+                }) => text.push_str(&format!("{{{}: {}}}", name, value)),
+                SongLine::Directive(Directive { name, value: None }) => text.push_str(&format!("{{{}}}", name)),
+                SongLine::Lyrics(segments) => {
+                    for segment in segments {
+                        if let Some(chord) = &segment.chord {
+                            text.push_str(&format!("[{}]", chord));
+                        }
+                        text.push_str(&segment.lyrics);
+                    }
+                }
+                SongLine::Verbatim(line) => text.push_str(line),
+                SongLine::Empty => {}
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Write the song as plain text with the chords above the lyrics: the title and other header directives first,
+    /// sections under their label and choruses indented. Comment lines and unknown directives are left out.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for name in ["title", "subtitle", "artist"] {
+            if let Some(value) = self.directive(name) {
+                text.push_str(&format!("{}\n", value));
+            }
+        }
+        if let Some(key) = self.directive("key") {
+            text.push_str(&format!("Key: {}\n", key));
+        }
+        if self.capo() > 0 {
+            text.push_str(&format!("Capo: {}", self.capo()));
+            if let Some(key) = self.key() {
+                text.push_str(&format!(" (sounds in {})", key.transpose(self.capo() as isize)));
+            }
+            text.push('\n');
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+
+        let mut indent = "";
+        let header = text.len();
+        for line in &self.lines {
+            match line {
+                SongLine::Directive(Directive { name, value }) => {
+                    let label = |default: &str| {
+                        let label = value.as_deref().unwrap_or(default);
+                        format!("{}{}\n", label, if label.ends_with(':') { "" } else { ":" })
+                    };
+                    match canonical(name) {
+                        "start_of_chorus" => {
+                            text.push_str(&label("Chorus"));
+                            indent = "  ";
+                        }
+                        "start_of_bridge" => text.push_str(&label("Bridge")),
+                        "start_of_verse" if value.is_some() => text.push_str(&label("Verse")),
+                        "chorus" => text.push_str(&label("Chorus")),
+                        "end_of_chorus" => indent = "",
+                        "comment" | "comment_italic" | "comment_box" => {
+                            text.push_str(&format!("{}{}\n", indent, value.as_deref().unwrap_or_default()))
+                        }
+                        _ => {}
+                    }
+                }
+                SongLine::Lyrics(segments) => {
+                    for row in chord_and_lyric_rows(segments) {
+                        text.push_str(&format!("{}{}\n", indent, row));
+                    }
+                }
+                SongLine::Verbatim(line) if !line.trim_start().starts_with('#') => {
+                    text.push_str(&format!("{}{}\n", indent, line))
+                }
+                SongLine::Verbatim(_) => {}
+                // The header already ends with a blank line.
+                SongLine::Empty if text.len() == header => {}
+                SongLine::Empty => text.push('\n'),
+            }
+        }
+        text
+    }
+}
+
+/// The row of chords over the row of lyrics, the lyrics spaced out where a chord is longer than the words under it.
+/// A line of chords alone or lyrics alone is a single row.
+fn chord_and_lyric_rows(segments: &[Segment]) -> Vec<String> {
+    let (mut chords, mut lyrics) = (String::new(), String::new());
+    for segment in segments {
+        if let Some(chord) = &segment.chord {
+            let (chords_width, lyrics_width) = (chords.chars().count(), lyrics.chars().count());
+            let column = match chords_width {
+                0 => lyrics_width,
+                width => lyrics_width.max(width + 1),
+            };
+            chords.push_str(&" ".repeat(column - chords_width));
+            lyrics.push_str(&" ".repeat(column - lyrics_width));
+            chords.push_str(&chord.to_string());
+        }
+        lyrics.push_str(&segment.lyrics);
+    }
+    let rows = [chords, lyrics.trim_end().to_string()];
+    rows.into_iter().filter(|row| !row.trim().is_empty()).collect()
+}
+
+/// A line of lyrics cut at its chords, none if a bracket isn't closed.
+fn segments(line: &str) -> Option<Vec<Segment>> {
+    let mut segments = vec![];
+    let mut rest = line;
+    let mut chord = None;
+    loop {
+        let Some(open) = rest.find('[') else {
+            segments.push(Segment {
+                chord,
+                lyrics: rest.to_string(),
+            });
+            break;
+        };
+        let close = open + rest[open..].find(']')?;
+        if open > 0 || chord.is_some() {
+            segments.push(Segment {
+                chord,
+                lyrics: rest[..open].to_string(),
+            });
+        }
+        let name = &rest[open + 1..close];
+        chord = Some(name.parse().unwrap_or_else(|_| SongChord::Other(name.to_string())));
+        rest = &rest[close + 1..];
+    }
+    Some(segments)
+}
+
+/// A root at the start of `s`, as [`parse_root`] reads it, with its spelling.
+fn spelled_root(s: &str) -> Option<(SpelledNote, &str)> {
+    let (_, rest) = parse_root(s)?;
+    let root = s[..s.len() - rest.len()].parse().ok()?;
+    Some((root, rest))
+}
+
+/// The full name of a directive given by its short form.
+fn canonical(name: &str) -> &str {
+    match name {
+        "t" => "title",
+        "st" => "subtitle",
+        "c" => "comment",
+        "ci" => "comment_italic",
+        "cb" => "comment_box",
+        "soc" => "start_of_chorus",
+        "eoc" => "end_of_chorus",
+        "sov" => "start_of_verse",
+        "eov" => "end_of_verse",
+        "sob" => "start_of_bridge",
+        "eob" => "end_of_bridge",
+        "sot" => "start_of_tab",
+        "eot" => "end_of_tab",
+        "sog" => "start_of_grid",
+        "eog" => "end_of_grid",
+        name => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const SONG: &str = "\
+{title: Amazing Grace}
+{artist: John Newton}
+{key: G}
+# Traditional
+
+{start_of_verse: Verse 1}
+A[G]mazing [G7]grace, how [C]sweet the [G]sound
+That [G]saved a [Em]wretch like [D]me
+{end_of_verse}
+
+{soc}
+I [G]once was [B7/D#]lost, but [Em]now am [F]found
+[N.C.]Was blind but [D/F#]now I [G]see
+{eoc}
+
+{sot}
+e|---0---|
+{eot}
+";
+
+    fn chords(song: &Song) -> String {
+        song.chords().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn parse() {
+        let song: Song = SONG.parse().unwrap();
+        assert_eq!(song.title(), Some("Amazing Grace"));
+        assert_eq!(song.key().unwrap().to_string(), "G");
+        assert_eq!(song.capo(), 0);
+        assert_eq!(chords(&song), "G G7 C G G Em D G B7/D# Em F N.C. D/F# G");
+        assert_eq!(
+            song.lines[7],
+            SongLine::Lyrics(vec![
+                Segment {
+                    chord: None,
+                    lyrics: "That ".to_string()
+                },
+                Segment {
+                    chord: Some("G".parse().unwrap()),
+                    lyrics: "saved a ".to_string()
+                },
+                Segment {
+                    chord: Some("Em".parse().unwrap()),
+                    lyrics: "wretch like ".to_string()
+                },
+                Segment {
+                    chord: Some("D".parse().unwrap()),
+                    lyrics: "me".to_string()
+                },
+            ])
+        );
+        assert_eq!(song.lines[16], SongLine::Verbatim("e|---0---|".to_string()));
+        assert_eq!(song.to_chordpro(), SONG);
+    }
+
+    #[rstest]
+    #[case("Eb", "Eb Eb7 Ab Eb Eb Cm Bb Eb G7/B Cm Db N.C. Bb/D Eb", "Eb")]
+    #[case("F#", "F# F#7 B F# F# D#m C# F# A#7/D D#m E N.C. C#/E# F#", "F#")]
+    #[case("Db", "Db Db7 Gb Db Db Bbm Ab Db F7/A Bbm Cb N.C. Ab/C Db", "Db")]
+    fn transpose_to(#[case] to: &str, #[case] expected: &str, #[case] key: &str) {
+        let mut song: Song = SONG.parse().unwrap();
+        song.transpose_to(&to.parse().unwrap());
+        assert_eq!(chords(&song), expected);
+        assert_eq!(song.directive("key"), Some(key));
+    }
+
+    #[test]
+    fn double_accidentals_are_respelled() {
+        let mut song: Song = "{key: C}\n[C][E7][A#dim]".parse().unwrap();
+        song.transpose_to(&"B".parse().unwrap());
+        assert_eq!(chords(&song), "B D#7 Adim");
+    }
+
+    #[rstest]
+    #[case(1, "Ab Ab7 Db")]
+    #[case(6, "Db Db7 Gb")]
+    #[case(-1, "Gb Gb7 Cb")]
+    fn transpose(#[case] semitones: isize, #[case] expected: &str) {
+        let mut song: Song = "[G]Amazing [G7]grace, how [C]sweet".parse().unwrap();
+        song.transpose(semitones);
+        assert_eq!(chords(&song), expected);
+        assert_eq!(song.directive("key"), None);
+    }
+
+    #[test]
+    fn minor_keys() {
+        let mut song: Song = "[Am]one [Dm]two [E7]three".parse().unwrap();
+        assert_eq!(song.key().unwrap().to_string(), "Am");
+        song.transpose_to(&"C#".parse().unwrap());
+        assert_eq!(chords(&song), "C#m F#m G#7");
+    }
+
+    #[test]
+    fn capo() {
+        let mut song: Song = "{title: Song}\n{key: Eb}\n[Eb]one [Ab]two [Bb7]three".parse().unwrap();
+        song.set_capo(1);
+        assert_eq!(chords(&song), "D G A7");
+        assert_eq!(song.capo(), 1);
+        assert_eq!(song.directive("key"), Some("D"));
+        assert_eq!(
+            song.to_chordpro(),
+            "{title: Song}\n{key: D}\n{capo: 1}\n[D]one [G]two [A7]three\n"
+        );
+        song.set_capo(3);
+        assert_eq!(chords(&song), "C F G7");
+        song.set_capo(0);
+        assert_eq!(chords(&song), "Eb Ab Bb7");
+        assert_eq!(song.directive("capo"), None);
+    }
+
+    #[test]
+    fn render() {
+        let mut song: Song = SONG.parse().unwrap();
+        song.set_capo(2);
+        assert_eq!(
+            song.render(),
+            "\
+Amazing Grace
+John Newton
+Key: F
+Capo: 2 (sounds in G)
+
+Verse 1:
+ F      F7         Bb        F
+Amazing grace, how sweet the sound
+     F       Dm          C
+That saved a wretch like me
+
+Chorus:
+    F        A7/C#     Dm     Eb
+  I once was lost, but now am found
+  N.C.          C/E   F
+  Was blind but now I see
+
+e|---0---|
+"
+        );
+    }
+
+    #[test]
+    fn chords_wider_than_lyrics() {
+        let song: Song = "[Cmaj7]I [Dm7b5]a[G7sus4]m".parse().unwrap();
+        assert_eq!(song.render(), "Cmaj7 Dm7b5 G7sus4\nI     a     m\n");
+        let song: Song = "[G] [C] [D]".parse().unwrap();
+        assert_eq!(song.render(), "G C D\n");
+    }
+
+    #[rstest]
+    #[case("{title: Unclosed", "unclosed { in line 1")]
+    #[case("fine\n[G]oh [C no", "unclosed [ in line 2")]
+    fn errors(#[case] text: &str, #[case] message: &str) {
+        let error = text.parse::<Song>().unwrap_err();
+        assert_eq!(error.to_string(), format!("Invalid ChordPro file: {}", message));
+    }
+
+    #[rstest]
+    #[case("F#m7", "F#", "m7", None)]
+    #[case("Bb/D", "Bb", "", Some("D"))]
+    #[case("C6/9", "C", "6/9", None)]
+    #[case("Ebmaj7#11/G", "Eb", "maj7#11", Some("G"))]
+    fn chord_parts(#[case] chord: &str, #[case] root: &str, #[case] suffix: &str, #[case] bass: Option<&str>) {
+        let expected = SongChord::Named {
+            root: root.parse().unwrap(),
+            suffix: suffix.to_string(),
+            bass: bass.map(|b| b.parse().unwrap()),
+        };
+        assert_eq!(chord.parse::<SongChord>().unwrap(), expected);
+        assert_eq!(expected.to_string(), chord);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::key::Key;
+use crate::notation::score::{Score, ScoreEvent};
+use crate::notation::spelling::{signature_notes, tonic_signature, SpelledChord, SpelledNote, SpelledPitch};
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+/// The LilyPond version the output is written for.
+const VERSION: &str = "2.24.0";
+
+/// Write a score as LilyPond source: a staff with a bar per line, the chord symbols above it as chord names and the
+/// labels below it as lyrics. LilyPond prints the accidentals that the key signature calls for.
+pub fn to_lilypond(score: &Score) -> String {
+    let measures = score.measures();
+    let events: Vec<&ScoreEvent> = measures.iter().flatten().collect();
+    let mut ly = format!("\\version \"{}\"\n\n", VERSION);
+    ly.push_str(&format!(
+        "\\header {{\n  title = \"{}\"\n  tagline = ##f\n}}\n\n",
+        escape(&score.title)
+    ));
+    ly.push_str("\\score {\n  <<\n");
+
+    if events.iter().any(|e| e.harmony.is_some()) {
+        let names: Vec<String> = events
+            .iter()
+            .map(|e| match &e.harmony {
+                Some(harmony) => chord_name(harmony, e.eighths),
+                None => format!("s{}", duration(e.eighths)),
+            })
+            .collect();
+        ly.push_str(&format!("    \\new ChordNames \\chordmode {{ {} }}\n", names.join(" ")));
+    }
+
+    ly.push_str("    \\new Staff \\new Voice = \"music\" {\n");
+    if let Some((key, tonic)) = score.key.zip(score.tonic) {
+        ly.push_str(&format!("      {}\n", key_command(&key, tonic)));
+    }
+    ly.push_str(&format!("      \\time {}/{}\n", score.meter.0, score.meter.1));
+    ly.push_str(&format!("      \\clef {}\n", clef(&events)));
+    for measure in &measures {
+        let notes: Vec<String> = measure.iter().map(note).collect();
+        ly.push_str(&format!("      {} |\n", notes.join(" ")));
+    }
+    ly.push_str("      \\bar \"|.\"\n    }\n");
+
+    if events.iter().any(|e| e.lyric.is_some()) {
+        // Lyrics skip rests by themselves, but every note needs a syllable, even an empty one.
+        let syllables: Vec<String> = events
+            .iter()
+            .filter(|e| !e.is_rest())
+            .map(|e| format!("\"{}\"", escape(e.lyric.as_deref().unwrap_or_default())))
+            .collect();
+        ly.push_str(&format!(
+            "    \\new Lyrics \\lyricsto \"music\" {{ {} }}\n",
+            syllables.join(" ")
+        ));
+    }
+    ly.push_str("  >>\n  \\layout { }\n}\n");
+    ly
+}
+
+/// The Dutch note name LilyPond uses by default, like `fis`, `bes`, `es` or `ases`.
+pub fn note_name(note: SpelledNote) -> String {
+    let letter = note.letter.to_string().to_lowercase();
+    match note.alter {
+        alter if alter > 0 => format!("{}{}", letter, "is".repeat(note.accidentals())),
+        // E and A drop the e of their first flat: es, as, eses.
+        alter if alter < 0 && (letter == "e" || letter == "a") => {
+            format!("{}s{}", letter, "es".repeat(note.accidentals() - 1))
+        }
+        _ => format!("{}{}", letter, "es".repeat(note.accidentals())),
+    }
+}
+
+/// A pitch in absolute octave entry, where `c'` is middle C.
+pub fn pitch_name(pitch: SpelledPitch) -> String {
+    let marks = match pitch.octave - 3 {
+        up if up > 0 => "'".repeat(up as usize),
+        down => ",".repeat(down.unsigned_abs() as usize),
+    };
+    format!("{}{}", note_name(pitch.note), marks)
+}
+
+fn duration(eighths: u32) -> &'static str {
+    match eighths {
+        1 => "8",
+        2 => "4",
+        3 => "4.",
+        4 => "2",
+        6 => "2.",
+        8 => "1",
+        _ => unreachable!("scores are split into single note values"),
+    }
+}
+
+fn note(event: &ScoreEvent) -> String {
+    let length = duration(event.eighths);
+    match &event.pitches[..] {
+        [] => format!("r{}", length),
+        [pitch] => format!("{}{}", pitch_name(*pitch), length),
+        pitches => {
+            let names: Vec<String> = pitches.iter().map(|p| pitch_name(*p)).collect();
+            format!("<{}>{}", names.join(" "), length)
+        }
+    }
+}
+
+/// The key of the signature on a spelled tonic. Scales that aren't diatonic modes are written in the major key of their
+/// signature, except for the harmonic minor which is written in its minor key.
+fn key_command(key: &Key, tonic: SpelledNote) -> String {
+    let (tonic, mode) = match key.scale {
+        Scale::Diatonic(mode) => (tonic, mode_name(mode)),
+        Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor) => (tonic, "minor"),
+        _ => (signature_notes(tonic_signature(tonic, key.scale))[0], "major"),
+    };
+    format!("\\key {} \\{}", note_name(tonic), mode)
+}
+
+fn mode_name(mode: diatonic::Mode) -> &'static str {
+    match mode {
+        diatonic::Mode::Ionian => "major",
+        diatonic::Mode::Dorian => "dorian",
+        diatonic::Mode::Phrygian => "phrygian",
+        diatonic::Mode::Lydian => "lydian",
+        diatonic::Mode::Mixolydian => "mixolydian",
+        diatonic::Mode::Aeolian => "minor",
+        diatonic::Mode::Locrian => "locrian",
+    }
+}
+
+/// The bass clef when the notes lie mostly below middle C.
+fn clef(events: &[&ScoreEvent]) -> &'static str {
+    let pitches: Vec<i32> = events.iter().flat_map(|e| e.pitches.iter().map(|p| p.midi())).collect();
+    match pitches.is_empty() || pitches.iter().sum::<i32>() >= 60 * pitches.len() as i32 {
+        true => "treble",
+        false => "bass",
+    }
+}
+
+/// A chord in chord mode, like `d1:m7` or `c2/+bes`.
+pub fn chord_name(harmony: &SpelledChord, eighths: u32) -> String {
+    let modifiers = match harmony.suffix().as_str() {
+        "" => String::new(),
+        "5" => "1.5".to_string(),
+        "mMaj7" => "m7+".to_string(),
+        "m7b5" => "m7.5-".to_string(),
+        suffix @ ("m" | "dim" | "aug" | "sus4" | "sus2" | "7" | "maj7" | "m7" | "dim7" | "aug7" | "6" | "m6" | "9"
+        | "maj9" | "m9" | "11" | "m11") => suffix.to_string(),
+        _ => steps(harmony),
+    };
+    let mut name = format!("{}{}", note_name(harmony.root), duration(eighths));
+    if !modifiers.is_empty() {
+        name.push_str(&format!(":{}", modifiers));
+    }
+    if let Some(bass) = harmony.bass {
+        let inversion = harmony.notes().contains(&bass);
+        name.push_str(&format!("/{}{}", if inversion { "" } else { "+" }, note_name(bass)));
+    }
+    name
+}
+
+/// The chord as a list of steps above the root, like `1.3.5.7.9+`, where a `7` is a minor seventh.
+fn steps(harmony: &SpelledChord) -> String {
+    let notes = harmony.notes();
+    let semitones = harmony.symbol.chord.semitones();
+    let has_third = semitones.contains(&3) || semitones.contains(&4);
+    let has_seventh = semitones.contains(&10) || semitones.contains(&11);
+    let mut steps = vec!["1".to_string()];
+    for note in &notes[1..] {
+        let number = (note.letter.index() + 7 - harmony.root.letter.index()) % 7 + 1;
+        let (step, plain) = match number {
+            2 if has_third => (9, 2),
+            2 => (2, 2),
+            3 => (3, 4),
+            4 if has_third => (11, 5),
+            4 => (4, 5),
+            5 => (5, 7),
+            6 if has_seventh => (13, 9),
+            6 => (6, 9),
+            _ => (7, 10),
+        };
+        let distance = (note.note().semitones_from_c() + 12 - harmony.root.note().semitones_from_c()) % 12;
+        let alteration = match distance as i32 - plain {
+            1 | -11 => "+",
+            -1 | 11 => "-",
+            _ => "",
+        };
+        steps.push(format!("{}{}", step, alteration));
+    }
+    steps.join(".")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::ChordSymbol;
+    use crate::midi::export::Direction;
+    use crate::notation::score::Labels;
+    use crate::notation::spelling::written_tonic;
+    use crate::note::Note;
+    use crate::scales::melodic_minor;
+
+    use super::*;
+
+    #[rstest]
+    #[case("Bb", 4, "bes'")]
+    #[case("Eb", 3, "es")]
+    #[case("Ab", 2, "as,")]
+    #[case("Abb", 2, "ases,")]
+    #[case("F#", 5, "fis''")]
+    #[case("Cx", 4, "cisis'")]
+    #[case("Db", 1, "des,,")]
+    fn pitches(#[case] note: &str, #[case] octave: i8, #[case] expected: &str) {
+        assert_eq!(pitch_name(SpelledPitch::new(note.parse().unwrap(), octave)), expected);
+    }
+
+    #[rstest]
+    #[case("D", Scale::Diatonic(diatonic::Mode::Ionian), "\\key d \\major")]
+    #[case("G", Scale::Diatonic(diatonic::Mode::Dorian), "\\key g \\dorian")]
+    #[case("Ab", Scale::Diatonic(diatonic::Mode::Aeolian), "\\key gis \\minor")]
+    #[case("F#", Scale::Diatonic(diatonic::Mode::Ionian), "\\key fis \\major")]
+    #[case("Gb", Scale::Diatonic(diatonic::Mode::Ionian), "\\key ges \\major")]
+    #[case("D#", Scale::Diatonic(diatonic::Mode::Aeolian), "\\key dis \\minor")]
+    #[case("A", Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor), "\\key a \\minor")]
+    #[case("C", Scale::MelodicMinor(melodic_minor::Mode::Altered), "\\key des \\major")]
+    fn keys(#[case] tonic: &str, #[case] scale: Scale, #[case] expected: &str) {
+        let tonic: SpelledNote = tonic.parse().unwrap();
+        let key = Key::new(tonic.note(), scale);
+        assert_eq!(key_command(&key, written_tonic(&key, tonic)), expected);
+    }
+
+    #[rstest]
+    #[case("Dm7", "d1:m7")]
+    #[case("C5", "c1:1.5")]
+    #[case("C7#9", "c1:1.3.5.7.9+")]
+    #[case("C13", "c1:1.3.5.7.9.13")]
+    #[case("Ebmaj7#11", "es1:1.3.5.7+.11+")]
+    #[case("Cadd9", "c1:1.3.5.9")]
+    #[case("Am/E", "a1:m/e")]
+    #[case("C/Bb", "c1/+bes")]
+    #[case("F#m7b5", "fis1:m7.5-")]
+    fn chord_names(#[case] symbol: &str, #[case] expected: &str) {
+        let symbol: ChordSymbol = symbol.parse().unwrap();
+        assert_eq!(chord_name(&SpelledChord::new(&symbol, None), 8), expected);
+    }
+
+    #[test]
+    fn scale_with_degrees() {
+        let key = Key::new(Note::Eb, Scale::Diatonic(diatonic::Mode::Ionian));
+        let mut score = Score::scale(&key, 4, Direction::Both);
+        score.label(Labels::Degrees);
+        let ly = to_lilypond(&score);
+        assert!(ly.starts_with("\\version \"2.24.0\""));
+        assert!(ly.contains("title = \"Eb major\""));
+        assert!(ly.contains("\\key es \\major"));
+        assert!(ly.contains("      es'4 f'4 g'4 as'4 |\n"));
+        assert!(ly.contains("      g'4 f'4 es'4 r4 |\n"));
+        assert!(ly.contains("\\lyricsto \"music\" { \"1\" \"2\" \"3\""));
+        assert!(!ly.contains("ChordNames"));
+        assert!(ly.contains("\\clef treble"));
+    }
+
+    #[test]
+    fn chord_as_written() {
+        let ly = to_lilypond(&Score::chord(&"F#maj7".parse().unwrap(), 4));
+        assert!(ly.contains("\\chordmode { fis1:maj7 }"));
+        assert!(ly.contains("<fis' ais' cis'' eis''>1 |"));
+    }
+
+    #[test]
+    fn progression_with_chord_names() {
+        let symbols: Vec<ChordSymbol> = ["Am", "E7/G#"].iter().map(|s| s.parse().unwrap()).collect();
+        let key = Key::new(Note::A, Scale::Diatonic(diatonic::Mode::Aeolian));
+        let ly = to_lilypond(&Score::progression(Some(&key), &symbols));
+        assert!(ly.contains("\\chordmode { a1:m e1:7/gis }"));
+        assert!(ly.contains("<gis e' gis' b' d''>1 |"));
+        assert!(!ly.contains("Lyrics"));
+    }
+
+    #[test]
+    fn low_chords_in_the_bass_clef() {
+        let ly = to_lilypond(&Score::chord(&"C".parse().unwrap(), 2));
+        assert!(ly.contains("\\clef bass"));
+        assert!(ly.contains("<c, e, g,>1"));
+        assert!(!ly.contains("\\key"));
+    }
+}
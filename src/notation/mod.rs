@@ -0,0 +1,18 @@
+pub use abc::{to_abc, tunes, AbcKey, AbcParseError, AbcTune};
+pub use chordpro::{Song, SongChord, SongKey};
+pub use lilypond::to_lilypond;
+pub use musicxml::{to_musicxml, Harmony, ImportedScore};
+pub use score::{Labels, Score, ScoreEvent};
+pub use spelling::{
+    key_name, key_signature, key_tonic, spell_chord, spell_in, spell_key, spell_scale, tonic_signature,
+    transpose_tonic, written_tonic, Letter, SpelledChord, SpelledNote, SpelledNoteParseError, SpelledPitch,
+};
+pub use staff::{to_staff, Clef, ClefParseError};
+
+pub mod abc;
+pub mod chordpro;
+pub mod lilypond;
+pub mod musicxml;
+pub mod score;
+pub mod spelling;
+pub mod staff;
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use crate::notation::score::Score;
+use crate::notation::spelling::{signature_notes, Letter, SpelledChord, SpelledPitch};
+use crate::scales::{diatonic, harmonic_minor, Scale};
+
+/// Divisions of a quarter note; every duration in a score is a whole number of eighths.
+const DIVISIONS: u32 = 2;
+
+/// Write a score as a MusicXML 4.0 partwise document with a single part.
+pub fn to_musicxml(score: &Score) -> String {
+    let fifths = score.fifths();
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    xml.push_str("<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" ");
+    xml.push_str("\"http://www.musicxml.org/dtds/partwise.dtd\">\n");
+    xml.push_str("<score-partwise version=\"4.0\">\n");
+    xml.push_str(&format!(
+        "  <work>\n    <work-title>{}</work-title>\n  </work>\n",
+        escape(&score.title)
+    ));
+    xml.push_str("  <identification>\n    <encoding>\n      <software>musicionist</software>\n");
+    xml.push_str("    </encoding>\n  </identification>\n");
+    xml.push_str("  <part-list>\n    <score-part id=\"P1\">\n      <part-name>Music</part-name>\n");
+    xml.push_str("    </score-part>\n  </part-list>\n");
+    xml.push_str("  <part id=\"P1\">\n");
+
+    let measures = score.measures();
+    for (number, measure) in measures.iter().enumerate() {
+        xml.push_str(&format!("    <measure number=\"{}\">\n", number + 1));
+        if number == 0 {
+            xml.push_str(&format!(
+                "      <attributes>\n        <divisions>{}</divisions>\n",
+                DIVISIONS
+            ));
+            xml.push_str(&format!("        <key>\n          <fifths>{}</fifths>\n", fifths));
+            if let Some(mode) = score.key.map(|k| mode(k.scale)) {
+                xml.push_str(&format!("          <mode>{}</mode>\n", mode));
+            }
+            xml.push_str("        </key>\n");
+            xml.push_str(&format!(
+                "        <time>\n          <beats>{}</beats>\n          <beat-type>{}</beat-type>\n        </time>\n",
+                score.meter.0, score.meter.1
+            ));
+            xml.push_str("        <clef>\n          <sign>G</sign>\n          <line>2</line>\n        </clef>\n");
+            xml.push_str("      </attributes>\n");
+        }
+
+        // Accidentals last until the end of the bar.
+        let mut alters: HashMap<(Letter, i8), i8> = HashMap::new();
+        let signature = signature_notes(fifths);
+        for event in measure {
+            if let Some(harmony) = &event.harmony {
+                xml.push_str(&harmony_element(harmony));
+            }
+            if event.is_rest() {
+                xml.push_str("      <note>\n        <rest/>\n");
+                xml.push_str(&duration_elements(event.eighths));
+                xml.push_str("      </note>\n");
+                continue;
+            }
+            for (i, pitch) in event.pitches.iter().enumerate() {
+                let key = (pitch.note.letter, pitch.octave);
+                let current = alters.get(&key).copied().unwrap_or_else(|| {
+                    signature
+                        .iter()
+                        .find(|n| n.letter == pitch.note.letter)
+                        .map_or(0, |n| n.alter)
+                });
+                xml.push_str("      <note>\n");
+                if i > 0 {
+                    xml.push_str("        <chord/>\n");
+                }
+                xml.push_str(&pitch_element(pitch));
+                xml.push_str(&duration_elements(event.eighths));
+                if current != pitch.note.alter {
+                    xml.push_str(&format!(
+                        "        <accidental>{}</accidental>\n",
+                        accidental(pitch.note.alter)
+                    ));
+                    alters.insert(key, pitch.note.alter);
+                }
+                if let Some(lyric) = event.lyric.as_ref().filter(|_| i == 0) {
+                    xml.push_str(&format!(
+                        "        <lyric number=\"1\">\n          <syllabic>single</syllabic>\n          <text>{}</text>\n        </lyric>\n",
+                        escape(lyric)
+                    ));
+                }
+                xml.push_str("      </note>\n");
+            }
+        }
+        if number + 1 == measures.len() {
+            xml.push_str("      <barline location=\"right\">\n        <bar-style>light-heavy</bar-style>\n");
+            xml.push_str("      </barline>\n");
+        }
+        xml.push_str("    </measure>\n");
+    }
+    xml.push_str("  </part>\n</score-partwise>\n");
+    xml
+}
+
+fn pitch_element(pitch: &SpelledPitch) -> String {
+    let alter = match pitch.note.alter {
+        0 => String::new(),
+        alter => format!("          <alter>{}</alter>\n", alter),
+    };
+    format!(
+        "        <pitch>\n          <step>{}</step>\n{}          <octave>{}</octave>\n        </pitch>\n",
+        pitch.note.letter, alter, pitch.octave
+    )
+}
+
+/// The duration, voice, type and dot of a note or rest.
+fn duration_elements(eighths: u32) -> String {
+    let (kind, dotted) = match eighths {
+        1 => ("eighth", false),
+        2 => ("quarter", false),
+        3 => ("quarter", true),
+        4 => ("half", false),
+        6 => ("half", true),
+        8 => ("whole", false),
+        _ => unreachable!("scores are split into single note values"),
+    };
+    format!(
+        "        <duration>{}</duration>\n        <voice>1</voice>\n        <type>{}</type>\n{}",
+        eighths * DIVISIONS / 2,
+        kind,
+        if dotted { "        <dot/>\n" } else { "" }
+    )
+}
+
+fn harmony_element(harmony: &SpelledChord) -> String {
+    let suffix = harmony.suffix();
+    let mut xml = String::from("      <harmony>\n        <root>\n");
+    xml.push_str(&format!("          <root-step>{}</root-step>\n", harmony.root.letter));
+    if harmony.root.alter != 0 {
+        xml.push_str(&format!("          <root-alter>{}</root-alter>\n", harmony.root.alter));
+    }
+    xml.push_str("        </root>\n");
+    xml.push_str(&format!(
+        "        <kind text=\"{}\">{}</kind>\n",
+        escape(&suffix),
+        kind(&suffix)
+    ));
+    if let Some(bass) = harmony.bass {
+        xml.push_str(&format!(
+            "        <bass>\n          <bass-step>{}</bass-step>\n",
+            bass.letter
+        ));
+        if bass.alter != 0 {
+            xml.push_str(&format!("          <bass-alter>{}</bass-alter>\n", bass.alter));
+        }
+        xml.push_str("        </bass>\n");
+    }
+    xml.push_str("      </harmony>\n");
+    xml
+}
+
+/// The MusicXML chord kind of a suffix, `other` for the ones MusicXML has no name for. The suffix itself is always
+/// given as the text to display.
+fn kind(suffix: &str) -> &'static str {
+    match suffix {
+        "" => "major",
+        "m" => "minor",
+        "dim" => "diminished",
+        "aug" => "augmented",
+        "sus4" => "suspended-fourth",
+        "sus2" => "suspended-second",
+        "5" => "power",
+        "7" => "dominant",
+        "maj7" => "major-seventh",
+        "m7" => "minor-seventh",
+        "mMaj7" => "major-minor",
+        "m7b5" => "half-diminished",
+        "dim7" => "diminished-seventh",
+        "aug7" => "augmented-seventh",
+        "6" => "major-sixth",
+        "m6" => "minor-sixth",
+        "9" => "dominant-ninth",
+        "maj9" => "major-ninth",
+        "m9" => "minor-ninth",
+        "11" => "dominant-11th",
+        "m11" => "minor-11th",
+        "13" => "dominant-13th",
+        "maj13" => "major-13th",
+        "m13" => "minor-13th",
+        _ => "other",
+    }
+}
+
+/// The mode of a key signature. Scales other than the diatonic modes are written in the signature of a major or minor
+/// key, but only the harmonic minor itself is named after it.
+fn mode(scale: Scale) -> &'static str {
+    match scale {
+        Scale::Diatonic(diatonic::Mode::Ionian) => "major",
+        Scale::Diatonic(diatonic::Mode::Dorian) => "dorian",
+        Scale::Diatonic(diatonic::Mode::Phrygian) => "phrygian",
+        Scale::Diatonic(diatonic::Mode::Lydian) => "lydian",
+        Scale::Diatonic(diatonic::Mode::Mixolydian) => "mixolydian",
+        Scale::Diatonic(diatonic::Mode::Aeolian) => "minor",
+        Scale::Diatonic(diatonic::Mode::Locrian) => "locrian",
+        Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor) => "minor",
+        _ => "none",
+    }
+}
+
+fn accidental(alter: i8) -> &'static str {
+    match alter {
+        -2 => "flat-flat",
+        -1 => "flat",
+        0 => "natural",
+        1 => "sharp",
+        _ => "double-sharp",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::ChordSymbol;
+    use crate::key::Key;
+    use crate::midi::export::Direction;
+    use crate::notation::score::Labels;
+    use crate::note::Note;
+
+    use super::*;
+
+    fn count(xml: &str, needle: &str) -> usize {
+        xml.matches(needle).count()
+    }
+
+    #[test]
+    fn scale_with_a_signature() {
+        let key = Key::new(Note::D, Scale::Diatonic(diatonic::Mode::Ionian));
+        let xml = to_musicxml(&Score::scale(&key, 4, Direction::Ascending));
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<fifths>2</fifths>"));
+        assert!(xml.contains("<mode>major</mode>"));
+        assert!(xml.contains("<work-title>D major</work-title>"));
+        assert_eq!(count(&xml, "<measure "), 2);
+        assert_eq!(count(&xml, "<step>F</step>\n          <alter>1</alter>"), 1);
+        // F# and C# are in the signature.
+        assert_eq!(count(&xml, "<accidental>"), 0);
+        assert_eq!(count(&xml, "<type>quarter</type>"), 8);
+        assert!(xml.contains("<bar-style>light-heavy</bar-style>"));
+    }
+
+    #[rstest]
+    #[case("F#", 6, "F# major")]
+    #[case("Gb", -6, "Gb major")]
+    fn enharmonic_keys_as_written(#[case] tonic: &str, #[case] fifths: i8, #[case] title: &str) {
+        let scale = Scale::Diatonic(diatonic::Mode::Ionian);
+        let xml = to_musicxml(&Score::scale_on(tonic.parse().unwrap(), scale, 4, Direction::Ascending));
+        assert!(xml.contains(&format!("<fifths>{}</fifths>", fifths)));
+        assert!(xml.contains(&format!("<work-title>{}</work-title>", title)));
+        assert_eq!(count(&xml, "<accidental>"), 0);
+    }
+
+    #[test]
+    fn raised_seventh_of_the_harmonic_minor() {
+        let key = Key::new(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+        let xml = to_musicxml(&Score::scale(&key, 4, Direction::Both));
+        assert!(xml.contains("<fifths>0</fifths>"));
+        // Written once on the way up and again in the next bar on the way down.
+        assert_eq!(count(&xml, "<accidental>sharp</accidental>"), 2);
+        assert_eq!(count(&xml, "<rest/>"), 1);
+    }
+
+    #[test]
+    fn chord_symbols() {
+        let symbols: Vec<ChordSymbol> = ["F#m7b5", "B7#9", "Em/G"].iter().map(|s| s.parse().unwrap()).collect();
+        let key = Key::new(Note::E, Scale::Diatonic(diatonic::Mode::Aeolian));
+        let xml = to_musicxml(&Score::progression(Some(&key), &symbols));
+        assert!(xml.contains("<fifths>1</fifths>"));
+        assert!(xml.contains("<root-step>F</root-step>\n          <root-alter>1</root-alter>"));
+        assert!(xml.contains("<kind text=\"m7b5\">half-diminished</kind>"));
+        assert!(xml.contains("<kind text=\"7#9\">other</kind>"));
+        assert!(xml.contains("<bass>\n          <bass-step>G</bass-step>\n        </bass>"));
+        assert!(xml.contains("<accidental>double-sharp</accidental>"));
+        assert_eq!(count(&xml, "<chord/>"), 3 + 4 + 3);
+        assert_eq!(count(&xml, "<type>whole</type>"), 4 + 5 + 4);
+    }
+
+    #[test]
+    fn labels_as_lyrics() {
+        let key = Key::new(Note::F, Scale::Diatonic(diatonic::Mode::Lydian));
+        let mut score = Score::scale(&key, 4, Direction::Ascending);
+        score.label(Labels::Intervals);
+        let xml = to_musicxml(&score);
+        assert_eq!(count(&xml, "<lyric number=\"1\">"), 8);
+        assert!(xml.contains("<text>A4</text>"));
+    }
+
+    #[test]
+    fn escaped_title() {
+        let mut score = Score::chord(&"C".parse().unwrap(), 4);
+        score.title = "Rock & <Roll>".to_string();
+        assert!(to_musicxml(&score).contains("<work-title>Rock &amp; &lt;Roll&gt;</work-title>"));
+    }
+}
@@ -0,0 +1,723 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::chord::symbol::{chord_from_semitones, parse_suffix};
+use crate::chord::ChordSymbol;
+use crate::key::Key;
+use crate::midi::import::{NoteEvent, TempoMap};
+use crate::notation::musicxml::mxl;
+use crate::notation::spelling::{Letter, SpelledNote, SpelledPitch};
+use crate::note::Note;
+use crate::scales::{diatonic, Scale};
+
+/// Ticks per quarter note of an imported score, whatever the divisions of the file.
+pub const TICKS_PER_QUARTER: u16 = 480;
+
+/// MusicXML chord kinds and the semitones of their tones above the root.
+const KINDS: &[(&str, &[usize])] = &[
+    ("major", &[0, 4, 7]),
+    ("minor", &[0, 3, 7]),
+    ("augmented", &[0, 4, 8]),
+    ("diminished", &[0, 3, 6]),
+    ("dominant", &[0, 4, 7, 10]),
+    ("major-seventh", &[0, 4, 7, 11]),
+    ("minor-seventh", &[0, 3, 7, 10]),
+    ("diminished-seventh", &[0, 3, 6, 9]),
+    ("augmented-seventh", &[0, 4, 8, 10]),
+    ("half-diminished", &[0, 3, 6, 10]),
+    ("major-minor", &[0, 3, 7, 11]),
+    ("major-sixth", &[0, 4, 7, 9]),
+    ("minor-sixth", &[0, 3, 7, 9]),
+    ("dominant-ninth", &[0, 2, 4, 7, 10]),
+    ("major-ninth", &[0, 2, 4, 7, 11]),
+    ("minor-ninth", &[0, 2, 3, 7, 10]),
+    ("dominant-11th", &[0, 2, 4, 5, 7, 10]),
+    ("major-11th", &[0, 2, 4, 5, 7, 11]),
+    ("minor-11th", &[0, 2, 3, 5, 7, 10]),
+    ("dominant-13th", &[0, 2, 4, 7, 9, 10]),
+    ("major-13th", &[0, 2, 4, 7, 9, 11]),
+    ("minor-13th", &[0, 2, 3, 7, 9, 10]),
+    ("suspended-second", &[0, 2, 7]),
+    ("suspended-fourth", &[0, 5, 7]),
+    ("power", &[0, 7]),
+    ("pedal", &[0]),
+    ("Neapolitan", &[0, 4, 7]),
+    ("Italian", &[0, 4, 10]),
+    ("French", &[0, 4, 6, 10]),
+    ("German", &[0, 4, 7, 10]),
+    ("Tristan", &[0, 3, 6, 10]),
+];
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid MusicXML file: {0}")]
+pub struct MusicXmlParseError(String);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid chord symbol: {0}")]
+pub struct HarmonyError(String);
+
+/// The parts of a MusicXML score, timed in ticks of [`TICKS_PER_QUARTER`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedScore {
+    pub title: Option<String>,
+    pub parts: Vec<ImportedPart>,
+    /// Ticks at which a tempo starts and its quarter notes per minute, from `<sound tempo>`.
+    pub tempos: Vec<(u32, f64)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedPart {
+    pub id: String,
+    pub name: String,
+    pub measures: Vec<Measure>,
+    pub notes: Vec<ImportedNote>,
+    pub harmonies: Vec<Harmony>,
+    pub keys: Vec<KeySignature>,
+    /// Ticks at which a meter starts and the meter.
+    pub meters: Vec<(u32, (u8, u8))>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measure {
+    /// The number printed in the score, which need not count from 1: a pickup is often `0`.
+    pub number: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A note with its spelling, tied notes merged into one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportedNote {
+    pub pitch: SpelledPitch,
+    pub start: u32,
+    pub duration: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeySignature {
+    pub start: u32,
+    pub fifths: i8,
+    pub mode: Option<&'static str>,
+}
+
+/// A `<harmony>` chord symbol as written in the file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Harmony {
+    pub start: u32,
+    /// The number of the measure it is in.
+    pub measure: String,
+    /// Missing for Roman numeral and function harmonies.
+    pub root: Option<SpelledNote>,
+    pub kind: String,
+    /// The suffix shown in the score, from the `text` of the kind.
+    pub text: Option<String>,
+    pub bass: Option<SpelledNote>,
+    pub degrees: Vec<ChordDegree>,
+}
+
+/// A tone added to, altered in or taken from a harmony, like the `b9` of `C7b9`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChordDegree {
+    pub value: u8,
+    pub alter: i8,
+    pub kind: DegreeKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegreeKind {
+    Add,
+    Alter,
+    Subtract,
+}
+
+impl ImportedScore {
+    /// Read a `.musicxml` file, or an `.mxl` archive, in UTF-8 or UTF-16. Only partwise scores are supported, which is
+    /// what notation software writes.
+    pub fn parse(data: &[u8]) -> Result<ImportedScore, MusicXmlParseError> {
+        let data = match mxl::is_zip(data) {
+            true => mxl::score(data).map_err(MusicXmlParseError)?,
+            false => data.to_vec(),
+        };
+        let text = decode(&data).ok_or_else(|| MusicXmlParseError("not UTF-8 or UTF-16 text".to_string()))?;
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let document = Document::parse_with_options(&text, options).map_err(|e| MusicXmlParseError(e.to_string()))?;
+        let root = document.root_element();
+        match root.tag_name().name() {
+            "score-partwise" => {}
+            "score-timewise" => return Err(MusicXmlParseError("timewise scores are not supported".to_string())),
+            other => return Err(MusicXmlParseError(format!("a {} is not a score", other))),
+        }
+
+        let title = child(root, "work")
+            .and_then(|work| text_of(work, "work-title"))
+            .or_else(|| text_of(root, "movement-title"))
+            .map(str::to_string);
+        let names: HashMap<&str, &str> = child(root, "part-list")
+            .into_iter()
+            .flat_map(|list| elements(list, "score-part"))
+            .filter_map(|part| Some((part.attribute("id")?, text_of(part, "part-name").unwrap_or_default())))
+            .collect();
+        let mut tempos: Vec<(u32, f64)> = vec![];
+        let parts = elements(root, "part")
+            .map(|part| {
+                let id = part.attribute("id").unwrap_or_default();
+                parse_part(part, id, names.get(id).copied().unwrap_or(id), &mut tempos)
+            })
+            .collect::<Result<_, _>>()?;
+        tempos.sort_by_key(|(tick, _)| *tick);
+        tempos.dedup_by_key(|(tick, _)| *tick);
+        Ok(ImportedScore { title, parts, tempos })
+    }
+
+    /// The notes of every part as MIDI note events, a track per part, so that they can be analyzed as a MIDI file is.
+    /// MusicXML dynamics aren't read: every note has velocity 64.
+    pub fn note_events(&self) -> Vec<NoteEvent> {
+        let tempo = TempoMap::with_changes(
+            TICKS_PER_QUARTER,
+            self.tempos
+                .iter()
+                .map(|(tick, bpm)| (*tick, (60_000_000.0 / bpm).round() as u32))
+                .collect(),
+        );
+        let mut events: Vec<NoteEvent> = self
+            .parts
+            .iter()
+            .enumerate()
+            .flat_map(|(track, part)| part.notes.iter().map(move |n| (track, n)))
+            .map(|(track, note)| NoteEvent {
+                pitch: note.pitch.pitch(),
+                velocity: 64,
+                channel: 0,
+                track,
+                start: note.start,
+                end: note.start + note.duration,
+                start_seconds: tempo.seconds(note.start),
+                end_seconds: tempo.seconds(note.start + note.duration),
+            })
+            .collect();
+        events.sort_by_key(|n| (n.start, n.pitch));
+        events
+    }
+
+    /// The meters of the first part, 4/4 if it has none.
+    pub fn meters(&self) -> Vec<(u32, (u8, u8))> {
+        match self.parts.first().map(|p| p.meters.clone()) {
+            Some(meters) if !meters.is_empty() => meters,
+            _ => vec![(0, (4, 4))],
+        }
+    }
+
+    /// The first key signature of the first part.
+    pub fn key_signature(&self) -> Option<KeySignature> {
+        self.parts.first().and_then(|p| p.keys.first().copied())
+    }
+}
+
+impl KeySignature {
+    /// The key of the signature and its mode, major if it has none. Returns `None` for modes that aren't diatonic.
+    pub fn key(&self) -> Option<Key> {
+        let mode = match self.mode.unwrap_or("major") {
+            "major" | "ionian" => diatonic::Mode::Ionian,
+            "dorian" => diatonic::Mode::Dorian,
+            "phrygian" => diatonic::Mode::Phrygian,
+            "lydian" => diatonic::Mode::Lydian,
+            "mixolydian" => diatonic::Mode::Mixolydian,
+            "minor" | "aeolian" => diatonic::Mode::Aeolian,
+            "locrian" => diatonic::Mode::Locrian,
+            _ => return None,
+        };
+        let degree = diatonic::Mode::array()
+            .iter()
+            .position(|m| *m == mode)
+            .expect("every mode is in the array");
+        let major = Note::C.transpose(7 * self.fifths as isize);
+        let tonic = major.transpose(Scale::Diatonic(diatonic::Mode::Ionian).semitones()[degree] as isize);
+        Some(Key::new(tonic, Scale::Diatonic(mode)))
+    }
+}
+
+impl Harmony {
+    /// The chord symbol of the harmony, or `None` for no chord (`N.C.`).
+    ///
+    /// The kind is read with its added, altered and subtracted degrees, counting the seventh as minor as chord symbols
+    /// do. A kind of `other` is read from its text. A text that names another chord than the kind is an error, as
+    /// software would show one chord and play the other.
+    pub fn symbol(&self) -> Result<Option<ChordSymbol>, HarmonyError> {
+        if self.kind == "none" {
+            return Ok(None);
+        }
+        let root = self
+            .root
+            .ok_or_else(|| HarmonyError(format!("a {} chord without a root", self.kind)))?;
+        let text = self.text.as_deref().filter(|t| !t.is_empty());
+        let written = text.and_then(|t| parse_suffix(t).ok()).map(|c| c.semitones());
+        let mut semitones = match (kind_semitones(&self.kind), &written) {
+            (Some(kind), Some(written)) if *written != kind => {
+                return Err(HarmonyError(format!(
+                    "{}{} is played as a {} chord",
+                    root,
+                    text.unwrap_or_default(),
+                    self.kind
+                )))
+            }
+            (Some(kind), _) => kind,
+            (None, Some(written)) => written.clone(),
+            (None, None) => {
+                return Err(HarmonyError(format!(
+                    "{}{} has an unknown kind {}",
+                    root,
+                    text.unwrap_or_default(),
+                    self.kind
+                )))
+            }
+        };
+        for degree in &self.degrees {
+            apply(&mut semitones, degree);
+        }
+        Ok(Some(ChordSymbol {
+            root: root.note(),
+            chord: chord_from_semitones(&semitones),
+            bass: self.bass.map(|b| b.note()).filter(|b| *b != root.note()),
+        }))
+    }
+}
+
+fn kind_semitones(kind: &str) -> Option<Vec<usize>> {
+    KINDS.iter().find(|(k, _)| *k == kind).map(|(_, tones)| tones.to_vec())
+}
+
+/// Add, alter or take out a degree. Altering or taking out a degree removes whatever form of it the chord has: the
+/// third, minor or major, the fifth, diminished, perfect or augmented, and so on.
+fn apply(semitones: &mut Vec<usize>, degree: &ChordDegree) {
+    let base: usize = match degree.value % 7 {
+        1 => 0,
+        2 => 2,
+        3 => 4,
+        4 => 5,
+        5 => 7,
+        6 => 9,
+        _ => 10,
+    };
+    let tone = (base as i32 + degree.alter as i32).rem_euclid(12) as usize;
+    let forms: Vec<usize> = match degree.value {
+        3 => vec![3, 4],
+        5 => vec![6, 7, 8],
+        7 => vec![10, 11],
+        9 if semitones.contains(&4) => vec![1, 2, 3],
+        9 => vec![1, 2],
+        11 => vec![5, 6],
+        13 => vec![8, 9],
+        _ => vec![tone],
+    };
+    match degree.kind {
+        DegreeKind::Add => semitones.push(tone),
+        DegreeKind::Alter => {
+            semitones.retain(|s| !forms.contains(s));
+            semitones.push(tone);
+        }
+        DegreeKind::Subtract => semitones.retain(|s| !forms.contains(s)),
+    }
+    semitones.sort();
+    semitones.dedup();
+}
+
+/// Read a part, failing when its durations run past the last tick a `u32` can hold.
+fn parse_part(
+    part: Node,
+    id: &str,
+    name: &str,
+    tempos: &mut Vec<(u32, f64)>,
+) -> Result<ImportedPart, MusicXmlParseError> {
+    let mut imported = ImportedPart {
+        id: id.to_string(),
+        name: name.to_string(),
+        measures: vec![],
+        notes: vec![],
+        harmonies: vec![],
+        keys: vec![],
+        meters: vec![],
+    };
+    let mut divisions: u64 = 1;
+    let mut measure_start = 0;
+    for measure in elements(part, "measure") {
+        let number = measure.attribute("number").unwrap_or_default().to_string();
+        let (mut cursor, mut furthest, mut chord_start) = (measure_start, measure_start, measure_start);
+        let too_long = || MusicXmlParseError(format!("measure {} is too long", number));
+        let ticks = |divisions: u64, duration: u64| {
+            duration
+                .checked_mul(TICKS_PER_QUARTER as u64)
+                .and_then(|d| u32::try_from(d / divisions.max(1)).ok())
+                .ok_or_else(too_long)
+        };
+        for element in measure.children().filter(Node::is_element) {
+            match element.tag_name().name() {
+                "attributes" => {
+                    divisions = number_of(element, "divisions").unwrap_or(divisions);
+                    for key in elements(element, "key") {
+                        if let Some(fifths) = number_of(key, "fifths") {
+                            imported.keys.push(KeySignature {
+                                start: cursor,
+                                fifths,
+                                mode: text_of(key, "mode").and_then(mode_name),
+                            });
+                        }
+                    }
+                    for time in elements(element, "time") {
+                        let beats =
+                            text_of(time, "beats").map(|b| b.split('+').filter_map(|b| b.parse::<u8>().ok()).sum());
+                        if let (Some(beats), Some(beat_type)) = (beats, number_of(time, "beat-type")) {
+                            imported.meters.push((cursor, (beats, beat_type)));
+                        }
+                    }
+                }
+                "note" => {
+                    if child(element, "grace").is_some() || child(element, "cue").is_some() {
+                        continue;
+                    }
+                    let duration = ticks(divisions, number_of(element, "duration").unwrap_or(0))?;
+                    if child(element, "chord").is_none() {
+                        chord_start = cursor;
+                    }
+                    let end = chord_start.checked_add(duration).ok_or_else(too_long)?;
+                    if child(element, "chord").is_none() {
+                        cursor = end;
+                        furthest = furthest.max(cursor);
+                    }
+                    let Some(pitch) = child(element, "pitch").and_then(pitch) else {
+                        continue;
+                    };
+                    let tied = elements(element, "tie").any(|t| t.attribute("type") == Some("stop"));
+                    let previous = imported
+                        .notes
+                        .iter_mut()
+                        .rev()
+                        .find(|n| n.pitch.midi() == pitch.midi() && n.start + n.duration == chord_start);
+                    match previous {
+                        Some(previous) if tied => previous.duration += duration,
+                        _ => imported.notes.push(ImportedNote {
+                            pitch,
+                            start: chord_start,
+                            duration,
+                        }),
+                    }
+                }
+                "backup" => {
+                    cursor = cursor.saturating_sub(ticks(divisions, number_of(element, "duration").unwrap_or(0))?)
+                }
+                "forward" => {
+                    let duration = ticks(divisions, number_of(element, "duration").unwrap_or(0))?;
+                    cursor = cursor.checked_add(duration).ok_or_else(too_long)?;
+                    furthest = furthest.max(cursor);
+                }
+                "harmony" => {
+                    let offset: i64 = child(element, "offset")
+                        .and_then(|o| o.text())
+                        .and_then(|o| o.trim().parse().ok())
+                        .unwrap_or(0);
+                    let offset = offset.signum() * ticks(divisions, offset.unsigned_abs())? as i64;
+                    let start = (cursor as i64 + offset).clamp(0, u32::MAX as i64);
+                    imported.harmonies.push(harmony(element, start as u32, &number));
+                }
+                _ => {}
+            }
+            for sound in element.descendants().filter(|n| n.has_tag_name("sound")) {
+                if let Some(tempo) = sound.attribute("tempo").and_then(|t| t.parse::<f64>().ok()) {
+                    tempos.push((cursor, tempo));
+                }
+            }
+        }
+        imported.measures.push(Measure {
+            number,
+            start: measure_start,
+            end: furthest,
+        });
+        measure_start = furthest;
+    }
+    imported.notes.sort_by_key(|n| (n.start, n.pitch.midi()));
+    Ok(imported)
+}
+
+fn harmony(element: Node, start: u32, measure: &str) -> Harmony {
+    let note = |parent: Option<Node>, step: &str, alter: &str| -> Option<SpelledNote> {
+        let parent = parent?;
+        Some(SpelledNote::new(
+            letter(text_of(parent, step)?)?,
+            alter_of(parent, alter),
+        ))
+    };
+    let kind = child(element, "kind");
+    Harmony {
+        start,
+        measure: measure.to_string(),
+        root: note(child(element, "root"), "root-step", "root-alter"),
+        kind: kind.and_then(|k| k.text()).map_or("major", str::trim).to_string(),
+        text: kind.and_then(|k| k.attribute("text")).map(str::to_string),
+        bass: note(child(element, "bass"), "bass-step", "bass-alter"),
+        degrees: elements(element, "degree")
+            .filter_map(|degree| {
+                Some(ChordDegree {
+                    value: number_of(degree, "degree-value")?,
+                    alter: alter_of(degree, "degree-alter"),
+                    kind: match text_of(degree, "degree-type")? {
+                        "add" => DegreeKind::Add,
+                        "subtract" => DegreeKind::Subtract,
+                        _ => DegreeKind::Alter,
+                    },
+                })
+            })
+            .collect(),
+    }
+}
+
+fn pitch(element: Node) -> Option<SpelledPitch> {
+    Some(SpelledPitch::new(
+        SpelledNote::new(letter(text_of(element, "step")?)?, alter_of(element, "alter")),
+        number_of(element, "octave")?,
+    ))
+}
+
+fn letter(step: &str) -> Option<Letter> {
+    Letter::array().into_iter().find(|l| l.to_string() == step)
+}
+
+/// A chromatic alteration, rounding microtones to the nearest semitone.
+fn alter_of(element: Node, name: &str) -> i8 {
+    text_of(element, name)
+        .and_then(|a| a.parse::<f64>().ok())
+        .map_or(0, |a| a.round() as i8)
+}
+
+fn mode_name(mode: &str) -> Option<&'static str> {
+    [
+        "major",
+        "minor",
+        "ionian",
+        "dorian",
+        "phrygian",
+        "lydian",
+        "mixolydian",
+        "aeolian",
+        "locrian",
+        "none",
+    ]
+    .into_iter()
+    .find(|m| *m == mode)
+}
+
+/// Text in UTF-8, or in UTF-16 with a byte order mark.
+fn decode(data: &[u8]) -> Option<String> {
+    let utf16 = |bytes: &[u8], read: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| read([c[0], c[1]])).collect();
+        String::from_utf16(&units).ok()
+    };
+    match data {
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        _ => String::from_utf8(data.to_vec()).ok(),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn elements<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+fn text_of<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|n| n.text()).map(str::trim)
+}
+
+fn number_of<T: FromStr>(node: Node, name: &str) -> Option<T> {
+    text_of(node, name).and_then(|t| t.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::midi::export::Direction;
+    use crate::notation::musicxml::export::to_musicxml;
+    use crate::notation::musicxml::mxl::tests::zip;
+    use crate::notation::score::Score;
+
+    use super::*;
+
+    const LEAD_SHEET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">
+<score-partwise version="4.0">
+  <movement-title>Blues &amp; Greens</movement-title>
+  <part-list><score-part id="P1"><part-name>Lead</part-name></score-part></part-list>
+  <part id="P1">
+    <measure number="0" implicit="yes">
+      <attributes>
+        <divisions>4</divisions>
+        <key><fifths>-1</fifths><mode>dorian</mode></key>
+        <time><beats>3</beats><beat-type>4</beat-type></time>
+      </attributes>
+      <direction><sound tempo="90"/></direction>
+      <note><pitch><step>A</step><octave>4</octave></pitch><duration>4</duration></note>
+    </measure>
+    <measure number="1">
+      <harmony>
+        <root><root-step>E</root-step><root-alter>-1</root-alter></root>
+        <kind text="Maj7">major-seventh</kind>
+        <bass><bass-step>G</bass-step></bass>
+      </harmony>
+      <note><grace/><pitch><step>C</step><octave>5</octave></pitch></note>
+      <note><pitch><step>B</step><alter>-1</alter><octave>4</octave></pitch><duration>8</duration>
+        <tie type="start"/></note>
+      <note><pitch><step>B</step><alter>-1</alter><octave>4</octave></pitch><duration>4</duration>
+        <tie type="stop"/></note>
+      <backup><duration>12</duration></backup>
+      <note><pitch><step>E</step><alter>-1</alter><octave>3</octave></pitch><duration>12</duration></note>
+      <note><chord/><pitch><step>G</step><octave>3</octave></pitch><duration>12</duration></note>
+    </measure>
+    <measure number="2">
+      <harmony>
+        <root><root-step>C</root-step></root>
+        <kind>dominant</kind>
+        <degree><degree-value>9</degree-value><degree-alter>-1</degree-alter><degree-type>add</degree-type></degree>
+        <degree><degree-value>5</degree-value><degree-alter>1</degree-alter><degree-type>alter</degree-type></degree>
+      </harmony>
+      <note><rest/><duration>4</duration></note>
+      <harmony><root><root-step>F</root-step></root><kind text="m7">major</kind></harmony>
+      <note><rest/><duration>4</duration></note>
+      <harmony><kind>none</kind></harmony>
+      <note><rest/><duration>4</duration></note>
+    </measure>
+  </part>
+</score-partwise>"#;
+
+    fn lead_sheet() -> ImportedScore {
+        ImportedScore::parse(LEAD_SHEET.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn notes_and_measures() {
+        let score = lead_sheet();
+        assert_eq!(score.title.as_deref(), Some("Blues & Greens"));
+        let part = &score.parts[0];
+        assert_eq!(part.name, "Lead");
+        let measures: Vec<(&str, u32, u32)> = part
+            .measures
+            .iter()
+            .map(|m| (m.number.as_str(), m.start, m.end))
+            .collect();
+        assert_eq!(measures, vec![("0", 0, 480), ("1", 480, 1920), ("2", 1920, 3360)]);
+        let notes: Vec<(String, u32, u32)> = part
+            .notes
+            .iter()
+            .map(|n| (n.pitch.to_string(), n.start, n.duration))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("A4".to_string(), 0, 480),
+                ("Eb3".to_string(), 480, 1440),
+                ("G3".to_string(), 480, 1440),
+                ("Bb4".to_string(), 480, 1440),
+            ]
+        );
+        assert_eq!(score.meters(), vec![(0, (3, 4))]);
+    }
+
+    #[test]
+    fn key_and_tempo() {
+        let score = lead_sheet();
+        let signature = score.key_signature().unwrap();
+        assert_eq!((signature.fifths, signature.mode), (-1, Some("dorian")));
+        assert_eq!(signature.key().unwrap().to_string(), "G dorian");
+        let events = score.note_events();
+        assert_eq!(events.len(), 4);
+        assert!((events[0].end_seconds - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn harmonies() {
+        let score = lead_sheet();
+        let harmonies = &score.parts[0].harmonies;
+        let starts: Vec<(u32, &str)> = harmonies.iter().map(|h| (h.start, h.measure.as_str())).collect();
+        assert_eq!(starts, vec![(480, "1"), (1920, "2"), (2400, "2"), (2880, "2")]);
+
+        let symbol = |i: usize| harmonies[i].symbol().map(|s| s.map(|s| s.to_string()));
+        assert_eq!(symbol(0).unwrap().as_deref(), Some("Ebmaj7/G"));
+        assert_eq!(symbol(1).unwrap().as_deref(), Some("Caug7b9"));
+        assert!(symbol(2).is_err());
+        assert_eq!(symbol(3).unwrap(), None);
+    }
+
+    #[rstest]
+    #[case(0, Some("minor"), "A minor")]
+    #[case(3, None, "A major")]
+    #[case(-2, Some("mixolydian"), "F mixolydian")]
+    fn keys_of_signatures(#[case] fifths: i8, #[case] mode: Option<&'static str>, #[case] expected: &str) {
+        let signature = KeySignature { start: 0, fifths, mode };
+        assert_eq!(signature.key().unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = Key::new(Note::Db, Scale::Diatonic(diatonic::Mode::Ionian));
+        let symbols: Vec<ChordSymbol> = ["Bbm7", "Eb7", "Dbmaj7/F", "C7#9"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let exported = Score::progression(Some(&key), &symbols);
+        let imported = ImportedScore::parse(to_musicxml(&exported).as_bytes()).unwrap();
+        let part = &imported.parts[0];
+        assert_eq!(imported.key_signature().unwrap().key(), Some(key));
+        let read: Vec<String> = part
+            .harmonies
+            .iter()
+            .map(|h| h.symbol().unwrap().unwrap().to_string())
+            .collect();
+        assert_eq!(read, vec!["Bbm7", "Eb7", "Dbmaj7/F", "C7#9"]);
+        let pitches: Vec<SpelledPitch> = part.notes.iter().map(|n| n.pitch).collect();
+        let expected: Vec<SpelledPitch> = exported.events.iter().flat_map(|e| e.pitches.clone()).collect();
+        assert_eq!(pitches, expected);
+    }
+
+    #[test]
+    fn compressed_and_utf16() {
+        let key = Key::new(Note::E, Scale::Diatonic(diatonic::Mode::Aeolian));
+        let xml = to_musicxml(&Score::scale(&key, 4, Direction::Ascending));
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+        let archive = zip(&[("score.musicxml", &utf16, true)]);
+        let score = ImportedScore::parse(&archive).unwrap();
+        assert_eq!(score.title.as_deref(), Some("E minor"));
+        assert_eq!(score.parts[0].notes.len(), 8);
+    }
+
+    #[rstest]
+    #[case("<score-timewise/>")]
+    #[case("<html/>")]
+    #[case("<score-partwise>")]
+    fn not_scores(#[case] xml: &str) {
+        assert!(ImportedScore::parse(xml.as_bytes()).is_err());
+    }
+
+    #[rstest]
+    #[case("<note><rest/><duration>18446744073709551615</duration></note>")]
+    #[case("<note><rest/><duration>8947848</duration></note><note><rest/><duration>8947848</duration></note>")]
+    #[case("<forward><duration>8947848</duration></forward><forward><duration>8947848</duration></forward>")]
+    #[case(concat!(
+        "<note><rest/><duration>8947840</duration></note><note><rest/><duration>8</duration></note>",
+        "<note><chord/><rest/><duration>10</duration></note>"
+    ))]
+    fn too_long(#[case] notes: &str) {
+        let xml = format!(
+            "<score-partwise><part id=\"P1\"><measure number=\"1\">{}</measure></part></score-partwise>",
+            notes
+        );
+        assert!(ImportedScore::parse(xml.as_bytes()).is_err());
+    }
+}
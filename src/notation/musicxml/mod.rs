@@ -0,0 +1,9 @@
+pub use export::to_musicxml;
+pub use import::{
+    ChordDegree, DegreeKind, Harmony, HarmonyError, ImportedNote, ImportedPart, ImportedScore, KeySignature, Measure,
+    MusicXmlParseError,
+};
+
+pub mod export;
+pub mod import;
+mod mxl;
@@ -0,0 +1,191 @@
+//! Compressed MusicXML: a zip archive holding the score, with `META-INF/container.xml` pointing at it.
+
+use miniz_oxide::inflate::decompress_to_vec;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+struct Entry {
+    name: String,
+    method: u16,
+    compressed_size: usize,
+    offset: usize,
+}
+
+pub(crate) fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+}
+
+/// The uncompressed score of an `.mxl` archive: the first root file of the container, or the first MusicXML file
+/// outside `META-INF` when there is no container.
+pub(crate) fn score(data: &[u8]) -> Result<Vec<u8>, String> {
+    let entries = entries(data)?;
+    let path = match entries.iter().find(|e| e.name == "META-INF/container.xml") {
+        Some(container) => {
+            let xml = String::from_utf8(read(data, container)?).map_err(|e| e.to_string())?;
+            let document = roxmltree::Document::parse(&xml).map_err(|e| e.to_string())?;
+            document
+                .descendants()
+                .find(|n| n.has_tag_name("rootfile"))
+                .and_then(|n| n.attribute("full-path"))
+                .ok_or("the container has no root file")?
+                .to_string()
+        }
+        None => entries
+            .iter()
+            .find(|e| !e.name.starts_with("META-INF/") && (e.name.ends_with(".musicxml") || e.name.ends_with(".xml")))
+            .ok_or("the archive holds no MusicXML file")?
+            .name
+            .clone(),
+    };
+    let entry = entries
+        .iter()
+        .find(|e| e.name == path)
+        .ok_or_else(|| format!("the archive has no {}", path))?;
+    read(data, entry)
+}
+
+/// The files listed in the central directory at the end of the archive.
+fn entries(data: &[u8]) -> Result<Vec<Entry>, String> {
+    // The end of central directory record is 22 bytes, followed by a comment of up to 65535 bytes.
+    let end = (0..data.len().saturating_sub(21))
+        .rev()
+        .take(22 + 65535)
+        .find(|at| u32_at(data, *at) == Ok(END_OF_CENTRAL_DIRECTORY))
+        .ok_or("no zip central directory")?;
+    let count = u16_at(data, end + 10)?;
+    let mut at = u32_at(data, end + 16)? as usize;
+    let mut entries = vec![];
+    for _ in 0..count {
+        if u32_at(data, at)? != CENTRAL_HEADER {
+            return Err("broken zip central directory".to_string());
+        }
+        let name_length = u16_at(data, at + 28)? as usize;
+        let name = data
+            .get(at + 46..at + 46 + name_length)
+            .ok_or("truncated zip file name")?;
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method: u16_at(data, at + 10)?,
+            compressed_size: u32_at(data, at + 20)? as usize,
+            offset: u32_at(data, at + 42)? as usize,
+        });
+        at += 46 + name_length + u16_at(data, at + 30)? as usize + u16_at(data, at + 32)? as usize;
+    }
+    Ok(entries)
+}
+
+fn read(data: &[u8], entry: &Entry) -> Result<Vec<u8>, String> {
+    if u32_at(data, entry.offset)? != LOCAL_HEADER {
+        return Err(format!("broken zip entry {}", entry.name));
+    }
+    let start =
+        entry.offset + 30 + u16_at(data, entry.offset + 26)? as usize + u16_at(data, entry.offset + 28)? as usize;
+    let compressed = data
+        .get(start..start + entry.compressed_size)
+        .ok_or_else(|| format!("truncated zip entry {}", entry.name))?;
+    match entry.method {
+        STORED => Ok(compressed.to_vec()),
+        DEFLATED => decompress_to_vec(compressed).map_err(|e| format!("can't inflate {}: {:?}", entry.name, e)),
+        method => Err(format!(
+            "unsupported zip compression method {} for {}",
+            method, entry.name
+        )),
+    }
+}
+
+fn u16_at(data: &[u8], at: usize) -> Result<u16, String> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| "truncated zip file".to_string())
+}
+
+fn u32_at(data: &[u8], at: usize) -> Result<u32, String> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "truncated zip file".to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use miniz_oxide::deflate::compress_to_vec;
+
+    use super::*;
+
+    /// A zip archive of the given files, deflated or stored. CRCs are left out, as the reader doesn't check them.
+    pub(crate) fn zip(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let (mut data, mut directory) = (vec![], vec![]);
+        for (name, content, deflate) in files {
+            let (method, stored) = match deflate {
+                true => (DEFLATED, compress_to_vec(content, 6)),
+                false => (STORED, content.to_vec()),
+            };
+            let offset = data.len() as u32;
+            let header = |signature: u32| {
+                let mut header = signature.to_le_bytes().to_vec();
+                if signature == CENTRAL_HEADER {
+                    header.extend([20, 0]);
+                }
+                header.extend([20, 0, 0, 0]);
+                header.extend(method.to_le_bytes());
+                header.extend([0; 8]);
+                header.extend((stored.len() as u32).to_le_bytes());
+                header.extend((content.len() as u32).to_le_bytes());
+                header.extend((name.len() as u16).to_le_bytes());
+                header.extend([0, 0]);
+                if signature == CENTRAL_HEADER {
+                    header.extend([0; 10]);
+                    header.extend(offset.to_le_bytes());
+                }
+                header.extend(name.as_bytes());
+                header
+            };
+            data.extend(header(LOCAL_HEADER));
+            data.extend(&stored);
+            directory.extend(header(CENTRAL_HEADER));
+        }
+        let directory_offset = data.len() as u32;
+        data.extend(&directory);
+        data.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        data.extend([0; 4]);
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((directory.len() as u32).to_le_bytes());
+        data.extend(directory_offset.to_le_bytes());
+        data.extend([0, 0]);
+        data
+    }
+
+    const CONTAINER: &[u8] = b"<?xml version=\"1.0\"?><container><rootfiles>\
+        <rootfile full-path=\"score/lead.musicxml\"/></rootfiles></container>";
+
+    #[test]
+    fn root_file_of_the_container() {
+        let archive = zip(&[
+            ("mimetype", b"application/vnd.recordare.musicxml", false),
+            ("META-INF/container.xml", CONTAINER, true),
+            ("score/other.musicxml", b"<other/>", true),
+            ("score/lead.musicxml", b"<score-partwise/>", true),
+        ]);
+        assert!(is_zip(&archive));
+        assert_eq!(score(&archive).unwrap(), b"<score-partwise/>");
+    }
+
+    #[test]
+    fn without_a_container() {
+        let archive = zip(&[("META-INF/notes.xml", b"<no/>", false), ("song.xml", b"<yes/>", false)]);
+        assert_eq!(score(&archive).unwrap(), b"<yes/>");
+    }
+
+    #[test]
+    fn broken_archives() {
+        assert!(score(b"PK\x03\x04 not really").is_err());
+        let mut archive = zip(&[("song.musicxml", b"<score-partwise/>", true)]);
+        archive.truncate(40);
+        assert!(score(&archive).is_err());
+    }
+}
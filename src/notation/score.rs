@@ -0,0 +1,363 @@
+use crate::chord::ChordSymbol;
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::midi::export::{scale_line, Direction};
+use crate::notation::spelling::{
+    key_name, key_tonic, spell_scale, tonic_signature, SpelledChord, SpelledNote, SpelledPitch,
+};
+use crate::pitch::Pitch;
+use crate::scales::Scale;
+
+/// Note values that can be written as a single note, in eighths: whole, dotted half, half, dotted quarter, quarter and
+/// eighth.
+pub const NOTE_VALUES: [u32; 6] = [8, 6, 4, 3, 2, 1];
+
+/// Pitches sounding together for a number of eighth notes, with the chord symbol written above them and a label below.
+/// An event without pitches is a rest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreEvent {
+    pub pitches: Vec<SpelledPitch>,
+    pub eighths: u32,
+    pub harmony: Option<SpelledChord>,
+    pub lyric: Option<String>,
+}
+
+/// What to write under the notes of a scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Labels {
+    /// The degree of the key, from 1 to 7.
+    Degrees,
+    /// The interval above the tonic, like `m3`, up to `P8`.
+    Intervals,
+}
+
+impl ScoreEvent {
+    pub fn rest(eighths: u32) -> ScoreEvent {
+        ScoreEvent {
+            pitches: vec![],
+            eighths,
+            harmony: None,
+            lyric: None,
+        }
+    }
+
+    pub fn is_rest(&self) -> bool {
+        self.pitches.is_empty()
+    }
+}
+
+/// A single staff of music, spelled and ready to be written out in a notation format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub title: String,
+    /// The key the signature is taken from, none for a score without signature.
+    pub key: Option<Key>,
+    /// The spelling of the key's tonic, which tells apart keys like F# and Gb major.
+    pub tonic: Option<SpelledNote>,
+    pub meter: (u8, u8),
+    pub events: Vec<ScoreEvent>,
+}
+
+impl Score {
+    /// A scale in quarter notes from the tonic, spelled, in `octave`, as [`scale_line`] plays it.
+    pub fn scale(key: &Key, octave: i8, direction: Direction) -> Score {
+        Score::scale_on(key_tonic(key), key.scale, octave, direction)
+    }
+
+    /// A scale as [`Score::scale`] writes it, on a spelled tonic.
+    pub fn scale_on(tonic: SpelledNote, scale: Scale, octave: i8, direction: Direction) -> Score {
+        let notes = spell_scale(tonic, scale);
+        let events = scale_line(SpelledPitch::new(tonic, octave).pitch(), &scale.intervals(), direction)
+            .into_iter()
+            .map(|pitch| {
+                let note = notes
+                    .iter()
+                    .find(|n| n.note() == pitch.note)
+                    .expect("the line is in the key");
+                ScoreEvent {
+                    pitches: vec![SpelledPitch::of(pitch, *note)],
+                    eighths: 2,
+                    harmony: None,
+                    lyric: None,
+                }
+            })
+            .collect();
+        Score {
+            title: format!("{} {}", tonic, scale),
+            key: Some(Key::new(tonic.note(), scale)),
+            tonic: Some(tonic),
+            meter: (4, 4),
+            events,
+        }
+    }
+
+    /// A chord in close position held for a bar of 4/4 under its symbol, with its root in `octave`.
+    pub fn chord(harmony: &SpelledChord, octave: i8) -> Score {
+        let pitches = voicing(harmony, Pitch::new(harmony.symbol.root, octave));
+        Score {
+            title: harmony.to_string(),
+            key: None,
+            tonic: None,
+            meter: (4, 4),
+            events: vec![ScoreEvent {
+                pitches,
+                eighths: 8,
+                harmony: Some(harmony.clone()),
+                lyric: None,
+            }],
+        }
+    }
+
+    /// A chord in quarter notes under its symbol, from the bottom of its close position above the root in `octave` to
+    /// the root an octave up, in the given direction.
+    pub fn arpeggio(harmony: &SpelledChord, octave: i8, direction: Direction) -> Score {
+        let root = Pitch::new(harmony.symbol.root, octave);
+        let mut up = voicing(harmony, root);
+        up.push(SpelledPitch::of(root.transpose(12), harmony.root));
+        let line: Vec<SpelledPitch> = match direction {
+            Direction::Ascending => up,
+            Direction::Descending => up.into_iter().rev().collect(),
+            Direction::Both => up.iter().chain(up.iter().rev().skip(1)).copied().collect(),
+        };
+        let events = line
+            .into_iter()
+            .enumerate()
+            .map(|(i, pitch)| ScoreEvent {
+                pitches: vec![pitch],
+                eighths: 2,
+                harmony: Some(harmony.clone()).filter(|_| i == 0),
+                lyric: None,
+            })
+            .collect();
+        Score {
+            title: format!("{} arpeggio", harmony),
+            key: None,
+            tonic: None,
+            meter: (4, 4),
+            events,
+        }
+    }
+
+    /// A progression with a chord per bar of 4/4, voiced as [`progression`](crate::midi::export::progression) voices
+    /// them, in the signature of `key` if there is one.
+    pub fn progression(key: Option<&Key>, symbols: &[ChordSymbol]) -> Score {
+        let events = symbols
+            .iter()
+            .map(|symbol| {
+                let harmony = SpelledChord::new(symbol, key);
+                // Roots from F3 to E4, as in MIDI export.
+                let root = Pitch::from_midi(53 + (symbol.root.semitones_from_c() as i32 + 7) % 12);
+                ScoreEvent {
+                    pitches: voicing(&harmony, root),
+                    eighths: 8,
+                    harmony: Some(harmony),
+                    lyric: None,
+                }
+            })
+            .collect();
+        Score {
+            title: key.map_or("Progression".to_string(), |k| format!("Progression in {}", key_name(k))),
+            key: key.copied(),
+            tonic: key.map(key_tonic),
+            meter: (4, 4),
+            events,
+        }
+    }
+
+    /// Label the single notes of the score, if it has a key, with their degree or their interval above the lowest tonic.
+    pub fn label(&mut self, labels: Labels) {
+        let (Some(key), Some(tonic)) = (self.key, self.tonic) else {
+            return;
+        };
+        let notes = spell_scale(tonic, key.scale);
+        let intervals = key.scale.intervals();
+        let lowest_tonic = self
+            .events
+            .iter()
+            .filter_map(|e| e.pitches.first())
+            .filter(|p| p.note == notes[0])
+            .map(|p| p.midi())
+            .min();
+        for event in &mut self.events {
+            let [pitch] = event.pitches[..] else {
+                continue;
+            };
+            let Some(degree) = notes.iter().position(|n| *n == pitch.note) else {
+                continue;
+            };
+            event.lyric = Some(match labels {
+                Labels::Degrees => (degree + 1).to_string(),
+                Labels::Intervals if degree == 0 && lowest_tonic.is_some_and(|t| pitch.midi() >= t + 12) => {
+                    Interval::PerfectOctave.abbreviation().to_string()
+                }
+                Labels::Intervals => intervals[degree].abbreviation().to_string(),
+            });
+        }
+    }
+
+    /// The key signature in sharps, negative for flats.
+    pub fn fifths(&self) -> i8 {
+        match (self.key, self.tonic) {
+            (Some(key), Some(tonic)) => tonic_signature(tonic, key.scale),
+            _ => 0,
+        }
+    }
+
+    /// The length of a bar in eighths.
+    pub fn bar_eighths(&self) -> u32 {
+        self.meter.0 as u32 * 8 / self.meter.1 as u32
+    }
+
+    /// The events bar by bar, the last bar filled up with rests. Events are expected not to cross bar lines.
+    pub fn measures(&self) -> Vec<Vec<ScoreEvent>> {
+        let bar = self.bar_eighths();
+        let mut measures: Vec<Vec<ScoreEvent>> = vec![];
+        let mut filled = bar;
+        for event in &self.events {
+            if filled >= bar {
+                measures.push(vec![]);
+                filled = 0;
+            }
+            filled += event.eighths;
+            measures.last_mut().expect("a bar was started").push(event.clone());
+        }
+        if let Some(last) = measures.last_mut() {
+            last.extend(split(bar.saturating_sub(filled)).into_iter().map(ScoreEvent::rest));
+        }
+        measures
+    }
+}
+
+/// A length in eighths as note values that can each be written as a single note, longest first.
+pub fn split(mut eighths: u32) -> Vec<u32> {
+    let mut values = vec![];
+    for value in NOTE_VALUES {
+        while eighths >= value {
+            values.push(value);
+            eighths -= value;
+        }
+    }
+    values
+}
+
+/// The chord in close position above `root`, lowest first, with a slash bass right below the root.
+fn voicing(harmony: &SpelledChord, root: Pitch) -> Vec<SpelledPitch> {
+    let mut pitches: Vec<SpelledPitch> = harmony
+        .notes()
+        .into_iter()
+        .map(|note| {
+            let above = (note.note().semitones_from_c() + 12 - root.note.semitones_from_c()) % 12;
+            SpelledPitch::of(root.transpose(above as i32), note)
+        })
+        .collect();
+    if let Some(bass) = harmony.bass.filter(|b| b.note() != harmony.root.note()) {
+        let below = (root.note.semitones_from_c() + 12 - bass.note().semitones_from_c()) % 12;
+        pitches.push(SpelledPitch::of(root.transpose(-(below as i32)), bass));
+    }
+    pitches.sort_by_key(|p| p.midi());
+    pitches
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note;
+    use crate::scales::{diatonic, harmonic_minor, melodic_minor, Scale};
+
+    use super::*;
+
+    fn names(pitches: &[SpelledPitch]) -> String {
+        pitches.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn scale_in_bars() {
+        let key = Key::new(Note::Gb, Scale::Diatonic(diatonic::Mode::Ionian));
+        let score = Score::scale(&key, 4, Direction::Both);
+        assert_eq!(score.fifths(), -6);
+        assert_eq!(score.title, "Gb major");
+        let measures = score.measures();
+        assert_eq!(measures.len(), 4);
+        assert_eq!(names(&measures[1][0].pitches), "Db5");
+        assert_eq!(names(&measures[0][3].pitches), "Cb5");
+        // 15 quarter notes and a quarter rest.
+        assert!(measures[3][3].is_rest());
+        assert_eq!(measures[3][3].eighths, 2);
+    }
+
+    #[rstest]
+    #[case(Note::Gb, Scale::Diatonic(diatonic::Mode::Aeolian), "F# minor")]
+    #[case(Note::Db, Scale::Diatonic(diatonic::Mode::Ionian), "Db major")]
+    #[case(
+        Note::Ab,
+        Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor),
+        "Ab harmonic-minor"
+    )]
+    fn scale_titles(#[case] tonic: Note, #[case] scale: Scale, #[case] expected: &str) {
+        assert_eq!(
+            Score::scale(&Key::new(tonic, scale), 4, Direction::Ascending).title,
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("D7", 4, "D4 F#4 A4 C5")]
+    #[case("C/E", 4, "E3 C4 E4 G4")]
+    #[case("B7#9", 3, "B3 C##4 D#4 F#4 A4")]
+    fn chord_voicings(#[case] symbol: &str, #[case] octave: i8, #[case] expected: &str) {
+        let score = Score::chord(&symbol.parse().unwrap(), octave);
+        assert_eq!(names(&score.events[0].pitches), expected);
+        assert_eq!(score.measures().len(), 1);
+    }
+
+    #[test]
+    fn progression_in_a_key() {
+        let key = Key::new(Note::A, Scale::Diatonic(diatonic::Mode::Aeolian));
+        let symbols: Vec<ChordSymbol> = ["Am", "Dm", "E7"].iter().map(|s| s.parse().unwrap()).collect();
+        let score = Score::progression(Some(&key), &symbols);
+        assert_eq!(score.title, "Progression in A minor");
+        assert_eq!(score.measures().len(), 3);
+        assert_eq!(names(&score.events[2].pitches), "E4 G#4 B4 D5");
+        assert_eq!(score.events[2].harmony.as_ref().unwrap().to_string(), "E7");
+    }
+
+    #[rstest]
+    #[case(Labels::Degrees, "1 2 3 4 5 6 7 1 7 6 5 4 3 2 1")]
+    #[case(Labels::Intervals, "P1 M2 m3 P4 P5 M6 M7 P8 M7 M6 P5 P4 m3 M2 P1")]
+    fn scale_labels(#[case] labels: Labels, #[case] expected: &str) {
+        let key = Key::new(Note::C, Scale::MelodicMinor(melodic_minor::Mode::MelodicMinor));
+        let mut score = Score::scale(&key, 4, Direction::Both);
+        score.label(labels);
+        let lyrics: Vec<&str> = score.events.iter().filter_map(|e| e.lyric.as_deref()).collect();
+        assert_eq!(lyrics.join(" "), expected);
+    }
+
+    #[rstest]
+    #[case("Dm7", Direction::Ascending, "D4 F4 A4 C5 D5")]
+    #[case("F#", Direction::Both, "F#4 A#4 C#5 F#5 C#5 A#4 F#4")]
+    #[case("C/E", Direction::Descending, "C5 G4 E4 C4 E3")]
+    fn arpeggios(#[case] symbol: &str, #[case] direction: Direction, #[case] expected: &str) {
+        let score = Score::arpeggio(&symbol.parse().unwrap(), 4, direction);
+        let pitches: Vec<SpelledPitch> = score.events.iter().flat_map(|e| e.pitches.clone()).collect();
+        assert_eq!(names(&pitches), expected);
+        assert!(score.events[0].harmony.is_some());
+        assert!(score.events[1].harmony.is_none());
+    }
+
+    #[test]
+    fn chords_have_no_labels() {
+        let mut score = Score::chord(&"C".parse().unwrap(), 4);
+        score.label(Labels::Degrees);
+        assert_eq!(score.events[0].lyric, None);
+    }
+
+    #[rstest]
+    #[case(8, vec![8])]
+    #[case(7, vec![6, 1])]
+    #[case(5, vec![4, 1])]
+    #[case(0, vec![])]
+    fn note_values(#[case] eighths: u32, #[case] expected: Vec<u32>) {
+        assert_eq!(split(eighths), expected);
+    }
+}
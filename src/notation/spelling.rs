@@ -0,0 +1,623 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chord::symbol::{chord_from_semitones, parse_root, suffix, ChordSymbolParseError};
+use crate::chord::{Chord, ChordSymbol};
+use crate::interval::Interval;
+use crate::key::Key;
+use crate::note::Note;
+use crate::pitch::Pitch;
+use crate::scales::{diatonic, harmonic_minor, melodic_minor, Scale};
+
+/// The seven letter names of the natural notes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl Letter {
+    pub fn array() -> [Letter; 7] {
+        [
+            Letter::C,
+            Letter::D,
+            Letter::E,
+            Letter::F,
+            Letter::G,
+            Letter::A,
+            Letter::B,
+        ]
+    }
+
+    pub fn semitones_from_c(&self) -> usize {
+        match self {
+            Letter::C => 0,
+            Letter::D => 2,
+            Letter::E => 4,
+            Letter::F => 5,
+            Letter::G => 7,
+            Letter::A => 9,
+            Letter::B => 11,
+        }
+    }
+
+    /// The position of the letter from C, 0 to 6.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The letter `steps` letters higher, going from B back to C.
+    pub fn up(&self, steps: usize) -> Letter {
+        Letter::array()[(self.index() + steps) % 7]
+    }
+}
+
+impl fmt::Display for Letter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A note with its letter name, telling apart the enharmonic spellings that [`Note`] leaves out, like `F#` and `Gb`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpelledNote {
+    pub letter: Letter,
+    /// Sharps when positive, flats when negative.
+    pub alter: i8,
+}
+
+impl SpelledNote {
+    pub fn new(letter: Letter, alter: i8) -> SpelledNote {
+        SpelledNote { letter, alter }
+    }
+
+    /// The spelling of `note` on `letter`, like `E#` for F on E.
+    pub fn on(letter: Letter, note: Note) -> SpelledNote {
+        let distance = (note.semitones_from_c() as i8 - letter.semitones_from_c() as i8).rem_euclid(12);
+        let alter = if distance > 6 { distance - 12 } else { distance };
+        SpelledNote { letter, alter }
+    }
+
+    /// The spelling [`Note`] uses, with flats for the black keys.
+    pub fn flat(note: Note) -> SpelledNote {
+        let letter = Letter::array()
+            .into_iter()
+            .rev()
+            .find(|l| l.semitones_from_c() <= note.semitones_from_c())
+            .expect("C is at 0");
+        SpelledNote::on(
+            if letter.semitones_from_c() == note.semitones_from_c() {
+                letter
+            } else {
+                letter.up(1)
+            },
+            note,
+        )
+    }
+
+    /// The spelling with sharps for the black keys.
+    pub fn sharp(note: Note) -> SpelledNote {
+        let letter = Letter::array()
+            .into_iter()
+            .rev()
+            .find(|l| l.semitones_from_c() <= note.semitones_from_c())
+            .expect("C is at 0");
+        SpelledNote::on(letter, note)
+    }
+
+    pub fn note(&self) -> Note {
+        Note::C.transpose(self.letter.semitones_from_c() as isize + self.alter as isize)
+    }
+
+    /// The note an interval higher, on the letter the interval's number calls for: a minor third above A is C, an
+    /// augmented second is B#.
+    pub fn above(&self, interval: Interval) -> SpelledNote {
+        let letter = self.letter.up(interval.number() - 1);
+        SpelledNote::on(letter, self.note().transpose(interval.semitones() as isize))
+    }
+
+    /// The note moved by the interval between two tonics, letters included, so that it keeps its role: `C#` moved
+    /// from E to F# is `D#`, and `G#` moved from E to Gb is `Bb`.
+    pub fn moved(&self, from: SpelledNote, to: SpelledNote) -> SpelledNote {
+        let letters = (to.letter.index() + 7 - from.letter.index()) % 7;
+        let semitones = (to.note() - from.note()).semitones() as isize;
+        SpelledNote::on(self.letter.up(letters), self.note().transpose(semitones))
+    }
+
+    /// The number of sharps or flats.
+    pub fn accidentals(&self) -> usize {
+        self.alter.unsigned_abs() as usize
+    }
+}
+
+impl From<Note> for SpelledNote {
+    fn from(note: Note) -> SpelledNote {
+        SpelledNote::flat(note)
+    }
+}
+
+impl fmt::Display for SpelledNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let accidental = if self.alter > 0 { "#" } else { "b" };
+        write!(f, "{}{}", self.letter, accidental.repeat(self.accidentals()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid note {0}")]
+pub struct SpelledNoteParseError(String);
+
+impl FromStr for SpelledNote {
+    type Err = SpelledNoteParseError;
+
+    /// Parse a letter, in either case, followed by any number of `#`, `♯`, `b`, `♭`, or `x` for a double sharp.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || SpelledNoteParseError(s.to_string());
+        let mut chars = s.chars();
+        let letter = match chars.next().ok_or_else(error)?.to_ascii_uppercase() {
+            'C' => Letter::C,
+            'D' => Letter::D,
+            'E' => Letter::E,
+            'F' => Letter::F,
+            'G' => Letter::G,
+            'A' => Letter::A,
+            'B' => Letter::B,
+            _ => return Err(error()),
+        };
+        let mut alter = 0;
+        for c in chars {
+            alter += match c {
+                '#' | '♯' => 1,
+                'x' | '𝄪' => 2,
+                'b' | '♭' => -1,
+                _ => return Err(error()),
+            };
+        }
+        Ok(SpelledNote { letter, alter })
+    }
+}
+
+/// A spelled note in an octave. The octave goes with the letter, so `B#3` sounds like `C4`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpelledPitch {
+    pub note: SpelledNote,
+    pub octave: i8,
+}
+
+impl SpelledPitch {
+    pub fn new(note: SpelledNote, octave: i8) -> SpelledPitch {
+        SpelledPitch { note, octave }
+    }
+
+    /// `note` in the octave that makes it sound at `pitch`, which must be the same note.
+    pub fn of(pitch: Pitch, note: SpelledNote) -> SpelledPitch {
+        let natural = pitch.midi() - note.alter as i32 - note.letter.semitones_from_c() as i32;
+        SpelledPitch {
+            note,
+            octave: (natural.div_euclid(12) - 1) as i8,
+        }
+    }
+
+    /// The MIDI note number, where `C4` is 60.
+    pub fn midi(&self) -> i32 {
+        (self.octave as i32 + 1) * 12 + self.note.letter.semitones_from_c() as i32 + self.note.alter as i32
+    }
+
+    pub fn pitch(&self) -> Pitch {
+        Pitch::from_midi(self.midi())
+    }
+
+    /// The number of staff positions above `C0`, counting a step per letter.
+    pub fn step(&self) -> i32 {
+        self.octave as i32 * 7 + self.note.letter.index() as i32
+    }
+}
+
+impl fmt::Display for SpelledPitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.note, self.octave)
+    }
+}
+
+/// The scale on a spelled tonic, a letter per degree.
+pub fn spell_scale(tonic: SpelledNote, scale: Scale) -> [SpelledNote; 7] {
+    let semitones = scale.semitones();
+    std::array::from_fn(|i| SpelledNote::on(tonic.letter.up(i), tonic.note().transpose(semitones[i] as isize)))
+}
+
+/// The spelling of the tonic whose scale has the fewest accidentals, flats breaking ties: Db major rather than C#
+/// major, but G# minor rather than Ab minor.
+pub fn key_tonic(key: &Key) -> SpelledNote {
+    written_tonic(key, SpelledNote::flat(key.tonic))
+}
+
+/// The spelling of the tonic whose scale has the fewest accidentals, the tonic as written breaking ties: F# major
+/// when written `F#`, Gb major when written `Gb`.
+pub fn written_tonic(key: &Key, written: SpelledNote) -> SpelledNote {
+    let accidentals =
+        |tonic: SpelledNote| -> usize { spell_scale(tonic, key.scale).iter().map(|n| n.accidentals()).sum() };
+    let (flat, sharp) = (SpelledNote::flat(key.tonic), SpelledNote::sharp(key.tonic));
+    match accidentals(sharp).cmp(&accidentals(flat)) {
+        Ordering::Less => sharp,
+        Ordering::Greater => flat,
+        Ordering::Equal if written.alter > 0 => sharp,
+        Ordering::Equal => flat,
+    }
+}
+
+/// The tonic of a key in `scale` on `tonic` moved up `semitones`, on the spelling whose scale has the fewest
+/// accidentals, ties going to the side of the key it comes from: E major a tone up is F# major, Ab major a tone down
+/// is Gb major.
+pub fn transpose_tonic(tonic: SpelledNote, scale: Scale, semitones: isize) -> SpelledNote {
+    let spelled = |tonic: SpelledNote| spell_scale(tonic, scale);
+    let accidentals = |tonic: SpelledNote| -> usize { spelled(tonic).iter().map(|n| n.accidentals()).sum() };
+    let note = tonic.note().transpose(semitones);
+    let (flat, sharp) = (SpelledNote::flat(note), SpelledNote::sharp(note));
+    match accidentals(sharp).cmp(&accidentals(flat)) {
+        Ordering::Less => sharp,
+        Ordering::Greater => flat,
+        Ordering::Equal if spelled(tonic).iter().any(|n| n.alter > 0) => sharp,
+        Ordering::Equal => flat,
+    }
+}
+
+/// The name of a key with its tonic spelled by [`key_tonic`], like `F# minor` where [`Key`] shows `Gb minor`.
+pub fn key_name(key: &Key) -> String {
+    format!("{} {}", key_tonic(key), key.scale)
+}
+
+/// The notes of a key, a letter each.
+pub fn spell_key(key: &Key) -> [SpelledNote; 7] {
+    spell_scale(key_tonic(key), key.scale)
+}
+
+/// The key signature of a key as a number of sharps, negative for flats.
+///
+/// Diatonic modes take the signature of their own notes. Modes of the harmonic minor take the signature of the natural
+/// minor on the harmonic minor's tonic, and modes of the melodic minor the one of the major key on the melodic minor's
+/// tonic, so that only the raised seventh or the lowered third needs an accidental.
+pub fn key_signature(key: &Key) -> i8 {
+    tonic_signature(key_tonic(key), key.scale)
+}
+
+/// The key signature of a scale on a spelled tonic, as [`key_signature`] gives it.
+pub fn tonic_signature(tonic: SpelledNote, scale: Scale) -> i8 {
+    let (index, parent, signature) = match scale {
+        Scale::Diatonic(mode) => (
+            position(&diatonic::Mode::array(), mode),
+            Scale::Diatonic(diatonic::Mode::Ionian),
+            Scale::Diatonic(diatonic::Mode::Ionian),
+        ),
+        Scale::HarmonicMinor(mode) => (
+            position(&harmonic_minor::Mode::array(), mode),
+            Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor),
+            Scale::Diatonic(diatonic::Mode::Aeolian),
+        ),
+        Scale::MelodicMinor(mode) => (
+            position(&melodic_minor::Mode::array(), mode),
+            Scale::MelodicMinor(melodic_minor::Mode::MelodicMinor),
+            Scale::Diatonic(diatonic::Mode::Ionian),
+        ),
+    };
+    let parent_tonic = SpelledNote::on(
+        tonic.letter.up(7 - index),
+        tonic.note().transpose(-(parent.semitones()[index] as isize)),
+    );
+    spell_scale(parent_tonic, signature).iter().map(|n| n.alter).sum()
+}
+
+/// The notes of the major key with a signature of `fifths` sharps, or flats when negative.
+pub fn signature_notes(fifths: i8) -> [SpelledNote; 7] {
+    let tonic = SpelledNote::on(
+        Letter::C.up((4 * fifths as isize).rem_euclid(7) as usize),
+        Note::C.transpose(7 * fifths as isize),
+    );
+    spell_scale(tonic, Scale::Diatonic(diatonic::Mode::Ionian))
+}
+
+/// The spelling of a note under a key signature: as in the signature if it belongs to it, otherwise with sharps in
+/// sharp keys and flats in flat keys. Without a signature, only `F#` is sharp.
+pub fn spell_in(note: Note, fifths: i8) -> SpelledNote {
+    if let Some(spelled) = signature_notes(fifths).into_iter().find(|n| n.note() == note) {
+        return spelled;
+    }
+    if fifths > 0 || (fifths == 0 && note == Note::Gb) {
+        SpelledNote::sharp(note)
+    } else {
+        SpelledNote::flat(note)
+    }
+}
+
+/// The notes of a chord on a spelled root, each on the letter of the role it plays: `D F# A C` for `D7`, `C E G Bb D#`
+/// for `C7#9`. The root comes first, followed by the third, fifth, seventh and tensions.
+pub fn spell_chord(root: SpelledNote, chord: &Chord) -> Vec<SpelledNote> {
+    chord_from_semitones(&chord.semitones())
+        .intervals
+        .iter()
+        .map(|i| root.above(*i))
+        .collect()
+}
+
+/// A chord symbol with its root and slash bass spelled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpelledChord {
+    pub symbol: ChordSymbol,
+    pub root: SpelledNote,
+    pub bass: Option<SpelledNote>,
+}
+
+impl SpelledChord {
+    /// Spell a chord symbol in a key or on its own.
+    ///
+    /// In a key, the root is spelled as in the key if it belongs to it, and as in the key signature otherwise. On its
+    /// own, the root gets the spelling that gives the chord the fewest accidentals. A slash bass that belongs to the
+    /// chord is spelled as in the chord.
+    pub fn new(symbol: &ChordSymbol, key: Option<&Key>) -> SpelledChord {
+        let in_key = |note: Note| match key {
+            Some(key) => spell_key(key)
+                .into_iter()
+                .find(|n| n.note() == note)
+                .unwrap_or_else(|| spell_in(note, key_signature(key))),
+            None => spell_in(note, 0),
+        };
+        let root = match key {
+            Some(_) => in_key(symbol.root),
+            None => {
+                let accidentals = |root: SpelledNote| -> usize {
+                    spell_chord(root, &symbol.chord).iter().map(|n| n.accidentals()).sum()
+                };
+                let usual = spell_in(symbol.root, 0);
+                [SpelledNote::flat(symbol.root), SpelledNote::sharp(symbol.root)]
+                    .into_iter()
+                    .filter(|n| *n != usual)
+                    .find(|n| accidentals(*n) < accidentals(usual))
+                    .unwrap_or(usual)
+            }
+        };
+        let bass = symbol.bass.map(|bass| {
+            spell_chord(root, &symbol.chord)
+                .into_iter()
+                .find(|n| n.note() == bass)
+                .unwrap_or_else(|| in_key(bass))
+        });
+        SpelledChord {
+            symbol: symbol.clone(),
+            root,
+            bass,
+        }
+    }
+
+    /// The notes of the chord, root first, as [`spell_chord`] gives them.
+    pub fn notes(&self) -> Vec<SpelledNote> {
+        spell_chord(self.root, &self.symbol.chord)
+    }
+
+    /// The chord's suffix, like `m7`.
+    pub fn suffix(&self) -> String {
+        suffix(&self.symbol.chord)
+    }
+}
+
+impl FromStr for SpelledChord {
+    type Err = ChordSymbolParseError;
+
+    /// Parse a chord symbol, keeping its root and slash bass spelled as written: `F#maj7` stays on `F#`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let symbol: ChordSymbol = s.parse()?;
+        let written = |s: &str| parse_root(s).and_then(|(_, rest)| s[..s.len() - rest.len()].parse().ok());
+        let root = written(s).expect("a chord symbol starts with its root");
+        let bass = symbol.bass.and(s.rsplit_once('/')).and_then(|(_, bass)| written(bass));
+        Ok(SpelledChord { symbol, root, bass })
+    }
+}
+
+impl fmt::Display for SpelledChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.root, self.suffix())?;
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", bass)?;
+        }
+        Ok(())
+    }
+}
+
+fn position<T: PartialEq>(array: &[T], item: T) -> usize {
+    array
+        .iter()
+        .position(|i| *i == item)
+        .expect("every mode is in its array")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn names(notes: &[SpelledNote]) -> String {
+        notes.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn key(tonic: Note, scale: Scale) -> Key {
+        Key::new(tonic, scale)
+    }
+
+    const MAJOR: Scale = Scale::Diatonic(diatonic::Mode::Ionian);
+    const MINOR: Scale = Scale::Diatonic(diatonic::Mode::Aeolian);
+
+    #[rstest]
+    #[case("C", Letter::C, 0)]
+    #[case("f#", Letter::F, 1)]
+    #[case("Bb", Letter::B, -1)]
+    #[case("Ebb", Letter::E, -2)]
+    #[case("Fx", Letter::F, 2)]
+    #[case("C♯", Letter::C, 1)]
+    fn parse(#[case] s: &str, #[case] letter: Letter, #[case] alter: i8) {
+        assert_eq!(s.parse::<SpelledNote>().unwrap(), SpelledNote::new(letter, alter));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("H")]
+    #[case("C#m")]
+    fn invalid(#[case] s: &str) {
+        assert!(s.parse::<SpelledNote>().is_err());
+    }
+
+    #[rstest]
+    #[case(Note::Db, "Db", "C#")]
+    #[case(Note::Gb, "Gb", "F#")]
+    #[case(Note::B, "B", "B")]
+    #[case(Note::C, "C", "C")]
+    fn flats_and_sharps(#[case] note: Note, #[case] flat: &str, #[case] sharp: &str) {
+        assert_eq!(SpelledNote::flat(note).to_string(), flat);
+        assert_eq!(SpelledNote::sharp(note).to_string(), sharp);
+        assert_eq!(SpelledNote::sharp(note).note(), note);
+    }
+
+    #[rstest]
+    #[case(key(Note::D, MAJOR), "D E F# G A B C#")]
+    #[case(key(Note::Gb, MAJOR), "Gb Ab Bb Cb Db Eb F")]
+    #[case(key(Note::Db, MAJOR), "Db Eb F Gb Ab Bb C")]
+    #[case(key(Note::Ab, MINOR), "G# A# B C# D# E F#")]
+    #[case(key(Note::Eb, MINOR), "Eb F Gb Ab Bb Cb Db")]
+    #[case(
+        key(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor)),
+        "A B C D E F G#"
+    )]
+    #[case(
+        key(Note::E, Scale::HarmonicMinor(harmonic_minor::Mode::PhrygianDominant)),
+        "E F G# A B C D"
+    )]
+    #[case(
+        key(Note::C, Scale::MelodicMinor(melodic_minor::Mode::Altered)),
+        "C Db Eb Fb Gb Ab Bb"
+    )]
+    #[case(key(Note::F, Scale::Diatonic(diatonic::Mode::Lydian)), "F G A B C D E")]
+    fn spelled_keys(#[case] key: Key, #[case] expected: &str) {
+        assert_eq!(names(&spell_key(&key)), expected);
+    }
+
+    #[rstest]
+    #[case("F#", MAJOR, "F#")]
+    #[case("Gb", MAJOR, "Gb")]
+    #[case("D#", MINOR, "D#")]
+    #[case("Eb", MINOR, "Eb")]
+    #[case("C#", MAJOR, "Db")]
+    #[case("Ab", MINOR, "G#")]
+    fn written_tonics(#[case] written: &str, #[case] scale: Scale, #[case] expected: &str) {
+        let written: SpelledNote = written.parse().unwrap();
+        let key = key(written.note(), scale);
+        assert_eq!(written_tonic(&key, written).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("E", MAJOR, 2, "F#")]
+    #[case("Ab", MAJOR, -2, "Gb")]
+    #[case("E", MAJOR, 1, "F")]
+    #[case("B", MAJOR, 2, "Db")]
+    #[case("C#", MINOR, -2, "B")]
+    #[case("C", MAJOR, 6, "Gb")]
+    #[case("A", MAJOR, -3, "F#")]
+    fn transposed_tonics(#[case] tonic: &str, #[case] scale: Scale, #[case] semitones: isize, #[case] expected: &str) {
+        let tonic: SpelledNote = tonic.parse().unwrap();
+        assert_eq!(transpose_tonic(tonic, scale, semitones).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("C#", "E", "F#", "D#")]
+    #[case("G#", "E", "Gb", "Bb")]
+    #[case("Bb", "F", "A", "D")]
+    #[case("E", "C", "Db", "F")]
+    fn moved_notes(#[case] note: &str, #[case] from: &str, #[case] to: &str, #[case] expected: &str) {
+        let spelled = |s: &str| -> SpelledNote { s.parse().unwrap() };
+        assert_eq!(spelled(note).moved(spelled(from), spelled(to)).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(key(Note::C, MAJOR), 0)]
+    #[case(key(Note::E, MAJOR), 4)]
+    #[case(key(Note::Gb, MAJOR), -6)]
+    #[case(key(Note::Ab, MINOR), 5)]
+    #[case(key(Note::D, Scale::Diatonic(diatonic::Mode::Dorian)), 0)]
+    #[case(key(Note::Bb, Scale::Diatonic(diatonic::Mode::Mixolydian)), -3)]
+    #[case(key(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor)), 0)]
+    #[case(key(Note::E, Scale::HarmonicMinor(harmonic_minor::Mode::PhrygianDominant)), 0)]
+    #[case(key(Note::C, Scale::MelodicMinor(melodic_minor::Mode::MelodicMinor)), 0)]
+    #[case(key(Note::C, Scale::MelodicMinor(melodic_minor::Mode::Altered)), -5)]
+    fn signatures(#[case] key: Key, #[case] fifths: i8) {
+        assert_eq!(key_signature(&key), fifths);
+    }
+
+    #[rstest]
+    #[case(Note::Gb, 2, "F#")]
+    #[case(Note::Bb, 2, "A#")]
+    #[case(Note::Bb, -1, "Bb")]
+    #[case(Note::B, -6, "Cb")]
+    #[case(Note::Gb, 0, "F#")]
+    #[case(Note::Eb, 0, "Eb")]
+    fn notes_in_a_signature(#[case] note: Note, #[case] fifths: i8, #[case] expected: &str) {
+        assert_eq!(spell_in(note, fifths).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("D7", "D", "D F# A C")]
+    #[case("C7#9", "C", "C E G Bb D#")]
+    #[case("Bdim7", "B", "B D F Ab")]
+    #[case("Gsus4", "G", "G C D")]
+    #[case("Caug", "C", "C E G#")]
+    #[case("Ebm7b5", "D#", "D# F# A C#")]
+    fn spelled_chords(#[case] symbol: &str, #[case] root: &str, #[case] expected: &str) {
+        let symbol: ChordSymbol = symbol.parse().unwrap();
+        assert_eq!(names(&spell_chord(root.parse().unwrap(), &symbol.chord)), expected);
+    }
+
+    #[rstest]
+    #[case("B#", 60, 3)]
+    #[case("Cb", 59, 4)]
+    #[case("C", 60, 4)]
+    #[case("A", 57, 3)]
+    fn octaves_follow_the_letter(#[case] note: &str, #[case] midi: i32, #[case] octave: i8) {
+        let pitch = SpelledPitch::of(Pitch::from_midi(midi), note.parse().unwrap());
+        assert_eq!(pitch.octave, octave);
+        assert_eq!(pitch.midi(), midi);
+    }
+
+    #[rstest]
+    #[case("Db7", None, "Db7")]
+    #[case("Gb", None, "F#")]
+    #[case("Ab", None, "Ab")]
+    #[case("Ebm7b5", None, "D#m7b5")]
+    #[case("Db/F", None, "Db/F")]
+    #[case("A/Db", None, "A/C#")]
+    #[case("Gb/Bb", Some(key(Note::Db, MAJOR)), "Gb/Bb")]
+    #[case("Db7", Some(key(Note::Gb, Scale::Diatonic(diatonic::Mode::Lydian))), "Db7")]
+    #[case("Db7", Some(key(Note::D, MAJOR)), "C#7")]
+    #[case("Bb", Some(key(Note::G, MINOR)), "Bb")]
+    #[case(
+        "E/Ab",
+        Some(key(Note::A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor))),
+        "E/G#"
+    )]
+    fn spelled_symbols(#[case] symbol: &str, #[case] key: Option<Key>, #[case] expected: &str) {
+        let symbol: ChordSymbol = symbol.parse().unwrap();
+        assert_eq!(SpelledChord::new(&symbol, key.as_ref()).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("F#maj7", "F#maj7")]
+    #[case("Bbdim", "Bbdim")]
+    #[case("A#dim", "A#dim")]
+    #[case("C/E", "C/E")]
+    #[case("Gb/Bb", "Gb/Bb")]
+    #[case("F#/Gb", "F#/Gb")]
+    fn written_symbols(#[case] symbol: &str, #[case] expected: &str) {
+        assert_eq!(symbol.parse::<SpelledChord>().unwrap().to_string(), expected);
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::notation::score::{Score, ScoreEvent};
+use crate::notation::spelling::{signature_notes, Letter, SpelledNote, SpelledPitch};
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid clef {0}")]
+pub struct ClefParseError(String);
+
+/// The staves to write the music on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clef {
+    Treble,
+    Bass,
+    /// A treble staff over a bass staff, with the notes from middle C up on the treble staff.
+    Grand,
+}
+
+/// The bottom lines of the treble staff (E4) and the bass staff (G2), in steps as [`SpelledPitch::step`] counts them.
+const TREBLE: i32 = 30;
+const BASS: i32 = 18;
+const MIDDLE_C: i32 = 28;
+
+/// Where the sharps and flats of a key signature go on the treble staff, in the order they are added.
+const SHARPS: [i32; 7] = [38, 35, 39, 36, 33, 37, 34];
+const FLATS: [i32; 7] = [34, 37, 33, 36, 32, 35, 31];
+
+impl FromStr for Clef {
+    type Err = ClefParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "treble" => Ok(Clef::Treble),
+            "bass" => Ok(Clef::Bass),
+            "grand" => Ok(Clef::Grand),
+            _ => Err(ClefParseError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Clef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Clef::Treble => "treble",
+            Clef::Bass => "bass",
+            Clef::Grand => "grand",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Draw a score on a five-line staff, a row of text per line and space, like
+///
+/// ```text
+/// |---#------------------------|--------------------||
+/// |                            |                    ||
+/// |----------------------------|-----------------o--||
+/// |     #                      |            o       ||
+/// |----------------------------|-------o------------||
+/// |                            |  o                 ||
+/// |-G-----------------------o--|--------------------||
+/// |                    o       |                    ||
+/// |---------------o------------|--------------------||
+///            o
+///            1    2    3    4     5    6    7    1
+/// ```
+///
+/// with the clef, the key signature, accidentals wherever the signature and the earlier notes of the bar don't give
+/// the note, ledger lines, chord symbols above and labels below. Notes are drawn as filled heads for quarters and
+/// shorter and open heads from halves up, a dot after dotted notes, without stems or beams. With `unicode`, the clefs,
+/// accidentals, heads and rests use music symbols and the lines box drawing characters; without, the clefs are the
+/// letters of the line they mark and the heads `o` and `O`.
+pub fn to_staff(score: &Score, clef: Clef, unicode: bool) -> String {
+    let staves: &[i32] = match clef {
+        Clef::Treble => &[TREBLE],
+        Clef::Bass => &[BASS],
+        Clef::Grand => &[TREBLE, BASS],
+    };
+    let staff_of = |step: i32| match clef {
+        Clef::Treble => TREBLE,
+        Clef::Bass => BASS,
+        Clef::Grand if step >= MIDDLE_C => TREBLE,
+        Clef::Grand => BASS,
+    };
+    let fifths = score.fifths();
+    let (positions, symbol) = if fifths > 0 { (SHARPS, '♯') } else { (FLATS, '♭') };
+    let positions = &positions[..fifths.unsigned_abs() as usize];
+    // The bass staff has them two octaves lower, a step lower on the staff than the treble staff.
+    let offset = |staff: i32| if staff == TREBLE { 0 } else { -14 };
+    // The signature counts in the range too: the third sharp, on G5, is above the treble staff.
+    let signature = staves
+        .iter()
+        .flat_map(|&staff| positions.iter().map(move |position| position + offset(staff)));
+    let steps: Vec<i32> = score
+        .events
+        .iter()
+        .flat_map(|e| e.pitches.iter().map(|p| p.step()))
+        .chain(signature)
+        .collect();
+    let top = steps.iter().copied().fold(staves[0] + 8, i32::max);
+    let bottom = steps.iter().copied().fold(staves[staves.len() - 1], i32::min);
+    let mut grid = Grid::new(top, bottom, staves);
+
+    grid.barline('│');
+    let column = grid.width();
+    grid.blank(3);
+    for &staff in staves {
+        match staff {
+            TREBLE => grid.set(TREBLE + 2, column + 1, '𝄞'),
+            _ => grid.set(BASS + 6, column + 1, '𝄢'),
+        }
+    }
+    for position in positions {
+        let column = grid.width();
+        grid.blank(2);
+        for &staff in staves {
+            grid.set(position + offset(staff), column, symbol);
+        }
+    }
+    grid.blank(1);
+
+    let signature = signature_notes(fifths);
+    for measure in score.measures() {
+        // Accidentals last until the end of the bar.
+        let mut alters: HashMap<(Letter, i8), i8> = HashMap::new();
+        for event in &measure {
+            let harmony = event.harmony.as_ref().map(|h| h.to_string()).unwrap_or_default();
+            let lyric = event.lyric.clone().unwrap_or_default();
+            let width = 5.max(harmony.chars().count() + 3).max(lyric.chars().count() + 3);
+            let column = grid.width();
+            grid.blank(width);
+            grid.text(true, column + 2, &harmony);
+            grid.text(false, column + 2, &lyric);
+            if event.is_rest() {
+                for &staff in staves {
+                    grid.set(staff + 4, column + 2, rest(event));
+                }
+                continue;
+            }
+            let mut pitches = event.pitches.clone();
+            pitches.sort_by_key(|p| p.step());
+            let mut previous: Option<(i32, bool)> = None;
+            for pitch in pitches {
+                let step = pitch.step();
+                let staff = staff_of(step);
+                // A second above a note can't share its column, so it goes right of it.
+                let displaced = previous.is_some_and(|(s, displaced)| s + 1 == step && !displaced);
+                previous = Some((step, displaced));
+                let ledgers = (staff + 10..=step).chain(step..=staff - 2);
+                for ledger in ledgers.filter(|s| (s - staff) % 2 == 0) {
+                    for c in column + 1..=column + 3 + displaced as usize {
+                        grid.set(ledger, c, '─');
+                    }
+                }
+                if let Some(alter) = accidental(&pitch, &mut alters, &signature) {
+                    let symbol = accidental_symbol(alter, unicode);
+                    let start = column + 2 - symbol.chars().count();
+                    for (i, c) in symbol.chars().enumerate() {
+                        grid.set(step, start + i, c);
+                    }
+                }
+                let head = if event.eighths >= 4 { '○' } else { '●' };
+                grid.set(step, column + 2 + displaced as usize, head);
+                if event.eighths == 3 || event.eighths == 6 {
+                    grid.set(step, column + 3 + displaced as usize, '.');
+                }
+            }
+        }
+        grid.barline('│');
+    }
+    grid.barline('│');
+    grid.render(unicode)
+}
+
+/// The accidental to write before a pitch, if the signature and the accidentals earlier in the bar don't give its
+/// alteration.
+fn accidental(
+    pitch: &SpelledPitch,
+    alters: &mut HashMap<(Letter, i8), i8>,
+    signature: &[SpelledNote; 7],
+) -> Option<i8> {
+    let key = (pitch.note.letter, pitch.octave);
+    let current = alters.get(&key).copied().unwrap_or_else(|| {
+        signature
+            .iter()
+            .find(|n| n.letter == pitch.note.letter)
+            .map_or(0, |n| n.alter)
+    });
+    alters.insert(key, pitch.note.alter);
+    (pitch.note.alter != current).then_some(pitch.note.alter)
+}
+
+fn accidental_symbol(alter: i8, unicode: bool) -> &'static str {
+    match (alter, unicode) {
+        (-2, true) => "𝄫",
+        (-1, true) => "♭",
+        (0, true) => "♮",
+        (1, true) => "♯",
+        (_, true) => "𝄪",
+        (-2, false) => "bb",
+        (-1, false) => "b",
+        (0, false) => "n",
+        (1, false) => "#",
+        (_, false) => "x",
+    }
+}
+
+fn rest(event: &ScoreEvent) -> char {
+    match event.eighths {
+        8.. => '𝄻',
+        4..=7 => '𝄼',
+        2 | 3 => '𝄽',
+        _ => '𝄾',
+    }
+}
+
+/// The staff drawn so far: a row per step from `top` down to `bottom`, with a row of text above and below.
+struct Grid {
+    top: i32,
+    bottom: i32,
+    /// The bottom lines of the staves, highest first.
+    staves: Vec<i32>,
+    rows: Vec<Vec<char>>,
+    above: Vec<char>,
+    below: Vec<char>,
+}
+
+impl Grid {
+    fn new(top: i32, bottom: i32, staves: &[i32]) -> Grid {
+        Grid {
+            top,
+            bottom,
+            staves: staves.to_vec(),
+            rows: vec![vec![]; (top - bottom + 1) as usize],
+            above: vec![],
+            below: vec![],
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.above.len()
+    }
+
+    fn is_line(&self, step: i32) -> bool {
+        self.staves
+            .iter()
+            .any(|&s| (s..=s + 8).contains(&step) && (step - s) % 2 == 0)
+    }
+
+    /// Add columns of empty staff.
+    fn blank(&mut self, width: usize) {
+        for step in self.bottom..=self.top {
+            let c = if self.is_line(step) { '─' } else { ' ' };
+            let row = (self.top - step) as usize;
+            self.rows[row].extend(std::iter::repeat_n(c, width));
+        }
+        self.above.extend(std::iter::repeat_n(' ', width));
+        self.below.extend(std::iter::repeat_n(' ', width));
+    }
+
+    /// Add a bar line from the top line of the highest staff to the bottom line of the lowest.
+    fn barline(&mut self, c: char) {
+        let (high, low) = (self.staves[0] + 8, self.staves[self.staves.len() - 1]);
+        let column = self.width();
+        self.blank(1);
+        for step in low..=high {
+            self.set(step, column, c);
+        }
+    }
+
+    fn set(&mut self, step: i32, column: usize, c: char) {
+        if (self.bottom..=self.top).contains(&step) {
+            self.rows[(self.top - step) as usize][column] = c;
+        }
+    }
+
+    fn text(&mut self, above: bool, column: usize, text: &str) {
+        let row = if above { &mut self.above } else { &mut self.below };
+        for (i, c) in text.chars().enumerate() {
+            if let Some(cell) = row.get_mut(column + i) {
+                *cell = c;
+            }
+        }
+    }
+
+    fn render(&self, unicode: bool) -> String {
+        let mut lines = vec![];
+        let line = |row: &[char]| -> String {
+            let text: String = row.iter().map(|&c| if unicode { c } else { ascii(c) }).collect();
+            text.trim_end().to_string()
+        };
+        if self.above.iter().any(|c| *c != ' ') {
+            lines.push(line(&self.above));
+        }
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        if self.below.iter().any(|c| *c != ' ') {
+            lines.push(line(&self.below));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+fn ascii(c: char) -> char {
+    match c {
+        '─' => '-',
+        '│' => '|',
+        '𝄞' => 'G',
+        '𝄢' => 'F',
+        '♯' => '#',
+        '♭' => 'b',
+        '●' => 'o',
+        '○' => 'O',
+        '𝄻' | '𝄼' | '𝄽' | '𝄾' => 'r',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::key::Key;
+    use crate::midi::Direction;
+    use crate::notation::Labels;
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, harmonic_minor, Scale};
+
+    use super::*;
+
+    #[test]
+    fn scale_in_treble_clef() {
+        let expected = "\
+|---#------------------------|--------------------||
+|                            |                    ||
+|----------------------------|-----------------o--||
+|     #                      |            o       ||
+|----------------------------|-------o------------||
+|                            |  o                 ||
+|-G-----------------------o--|--------------------||
+|                    o       |                    ||
+|---------------o------------|--------------------||
+           o
+           1    2    3    4     5    6    7    1
+";
+        let key = Key::new(D, Scale::Diatonic(diatonic::Mode::Ionian));
+        let mut score = Score::scale(&key, 4, Direction::Ascending);
+        score.label(Labels::Degrees);
+        assert_eq!(to_staff(&score, Clef::Treble, false), expected);
+    }
+
+    #[rstest]
+    #[case("E", Clef::Treble, 4)]
+    #[case("C#", Clef::Treble, 7)]
+    #[case("C#", Clef::Bass, 7)]
+    #[case("E", Clef::Grand, 2 * 4)]
+    #[case("Cb", Clef::Bass, 7)]
+    fn whole_signatures(#[case] tonic: &str, #[case] clef: Clef, #[case] count: usize) {
+        let score = Score::scale_on(
+            tonic.parse().unwrap(),
+            Scale::Diatonic(diatonic::Mode::Ionian),
+            4,
+            Direction::Ascending,
+        );
+        let staff = to_staff(&score, clef, true);
+        // Notes in the key carry no accidentals of their own.
+        assert_eq!(staff.matches(['♯', '♭']).count(), count);
+    }
+
+    #[test]
+    fn chord_in_bass_clef() {
+        let expected = "       C7#9
+      bO
+|-----------||
+|      O    ||
+|-F---------||
+|      O    ||
+|-----#-O---||
+|      O    ||
+|-----------||
+|           ||
+|-----------||
+";
+        let score = Score::chord(&"C7#9".parse().unwrap(), 3);
+        assert_eq!(to_staff(&score, Clef::Bass, false), expected);
+    }
+
+    #[test]
+    fn unicode() {
+        let expected = "       Ebm
+│──────────││
+│          ││
+│──────────││
+│          ││
+│─────♭○───││
+│          ││
+│─𝄞───♭○───││
+│          ││
+│─────♭○───││
+";
+        let score = Score::chord(&"Ebm".parse().unwrap(), 4);
+        assert_eq!(to_staff(&score, Clef::Treble, true), expected);
+    }
+
+    #[test]
+    fn grand_staff() {
+        let key = Key::new(Bb, Scale::Diatonic(diatonic::Mode::Ionian));
+        let score = Score::scale(&key, 3, Direction::Ascending);
+        let staff = to_staff(&score, Clef::Grand, false);
+        let lines: Vec<&str> = staff.lines().collect();
+        // From F5 down to G2, with B flats on the middle lines and E flats in the third spaces.
+        assert_eq!(lines.len(), 21);
+        assert!(lines[4].starts_with("|---b---"), "{}", staff);
+        assert!(lines[1].starts_with("|     b "), "{}", staff);
+        assert!(lines[18].starts_with("|---b---"), "{}", staff);
+        assert!(lines[15].starts_with("|     b "), "{}", staff);
+        // Middle C on a ledger line of the treble staff, B flat 3 above the bass staff.
+        assert_eq!(
+            lines[10].trim_end(),
+            "|              -o-           |                    ||"
+        );
+        assert_eq!(
+            lines[11].trim_end(),
+            "|          o                 |                    ||"
+        );
+    }
+
+    #[test]
+    fn accidentals_last_a_bar() {
+        let key = Key::new(A, Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor));
+        let score = Score::scale(&key, 4, Direction::Both);
+        let staff = to_staff(&score, Clef::Treble, false);
+        // G sharp once in each of the two bars it is in, and A5 on a ledger line.
+        assert_eq!(staff.matches("#o").count(), 2, "{}", staff);
+        assert!(staff.lines().next().unwrap().contains("-o-"), "{}", staff);
+    }
+
+    #[rstest]
+    #[case("treble", Clef::Treble)]
+    #[case("Bass", Clef::Bass)]
+    #[case("grand", Clef::Grand)]
+    fn parse_clef(#[case] s: &str, #[case] expected: Clef) -> Result<(), ClefParseError> {
+        assert_eq!(s.parse::<Clef>()?, expected);
+        assert!("alto".parse::<Clef>().is_err());
+        Ok(())
+    }
+}
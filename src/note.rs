@@ -1,7 +1,8 @@
+use std::fmt;
 use std::ops::{Shl, Shr};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Note {
     C,
     Db,
@@ -55,6 +56,12 @@ impl Note {
     }
 }
 
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 impl Shl<isize> for Note {
     type Output = Note;
     fn shl(self, rhs: isize) -> Note {
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::note::Note;
+
+/// A note placed in a specific octave, using scientific pitch notation (middle C is `C4`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Pitch {
+    pub note: Note,
+    pub octave: i8,
+}
+
+impl Pitch {
+    pub fn new(note: Note, octave: i8) -> Pitch {
+        Pitch { note, octave }
+    }
+
+    /// The MIDI note number of this pitch, where `C4` is 60.
+    pub fn midi(&self) -> i32 {
+        (self.octave as i32 + 1) * 12 + self.note.semitones_from_c() as i32
+    }
+
+    pub fn from_midi(midi: i32) -> Pitch {
+        Pitch {
+            note: Note::C.transpose(midi.rem_euclid(12) as isize),
+            octave: (midi.div_euclid(12) - 1) as i8,
+        }
+    }
+
+    pub fn transpose(&self, semitones: i32) -> Pitch {
+        Pitch::from_midi(self.midi() + semitones)
+    }
+}
+
+impl PartialOrd for Pitch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pitch {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.midi().cmp(&other.midi())
+    }
+}
+
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.note, self.octave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Pitch::new(C, 4), 60)]
+    #[case(Pitch::new(A, 4), 69)]
+    #[case(Pitch::new(B, 3), 59)]
+    #[case(Pitch::new(C, -1), 0)]
+    #[case(Pitch::new(G, 9), 127)]
+    fn midi_number(#[case] pitch: Pitch, #[case] expected: i32) {
+        assert_eq!(pitch.midi(), expected);
+        assert_eq!(Pitch::from_midi(expected), pitch);
+    }
+
+    #[rstest]
+    #[case(Pitch::new(B, 3), 1, Pitch::new(C, 4))]
+    #[case(Pitch::new(C, 4), -1, Pitch::new(B, 3))]
+    #[case(Pitch::new(E, 2), 12, Pitch::new(E, 3))]
+    fn transpose(#[case] pitch: Pitch, #[case] semitones: i32, #[case] expected: Pitch) {
+        assert_eq!(pitch.transpose(semitones), expected);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Pitch::new(Eb, 5).to_string(), "Eb5");
+    }
+}
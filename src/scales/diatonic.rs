@@ -4,7 +4,7 @@ use crate::scales::Degree;
 
 const SEMITONES: &[usize; 7] = &[2, 2, 1, 2, 2, 2, 1];
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Mode {
     Ionian,
     Dorian,
@@ -16,6 +16,18 @@ pub enum Mode {
 }
 
 impl Mode {
+    pub fn array() -> [Mode; 7] {
+        [
+            Mode::Ionian,
+            Mode::Dorian,
+            Mode::Phrygian,
+            Mode::Lydian,
+            Mode::Mixolydian,
+            Mode::Aeolian,
+            Mode::Locrian,
+        ]
+    }
+
     pub fn intervals(&self) -> [Interval; 7] {
         Degree::array().map(|d| self.interval_for(d))
     }
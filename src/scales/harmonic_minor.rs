@@ -4,7 +4,7 @@ use crate::scales::Degree;
 
 const SEMITONES: &[usize; 7] = &[2, 1, 2, 2, 1, 3, 1];
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Mode {
     HarmonicMinor,
     LocrianMaj6,
@@ -16,6 +16,18 @@ pub enum Mode {
 }
 
 impl Mode {
+    pub fn array() -> [Mode; 7] {
+        [
+            Mode::HarmonicMinor,
+            Mode::LocrianMaj6,
+            Mode::IonianAug5,
+            Mode::DorianLydian,
+            Mode::PhrygianDominant,
+            Mode::LydianAug2,
+            Mode::SuperLocrian,
+        ]
+    }
+
     pub fn intervals(&self) -> [Interval; 7] {
         Degree::array().map(|d| self.interval_for(d))
     }
@@ -0,0 +1,178 @@
+use crate::chord::{Chord, Stacking};
+use crate::interval::{canonical_interval, Interval};
+use crate::note::Note;
+use crate::scales::Degree;
+
+/// The triad built on each degree of a scale by stacking diatonic thirds, as `(root, chord)` pairs.
+pub fn triads(key: Note, intervals: &[Interval; 7]) -> Vec<(Note, Chord)> {
+    stack(key, intervals, 3)
+}
+
+/// The seventh chord built on each degree of a scale by stacking diatonic thirds, as `(root, chord)` pairs.
+pub fn sevenths(key: Note, intervals: &[Interval; 7]) -> Vec<(Note, Chord)> {
+    stack(key, intervals, 4)
+}
+
+/// The quartal, quintal or secondal chord of `size` notes built on each degree of a scale of any length, as
+/// `(root, chord)` pairs. The notes stay in the scale, so quartal chords in major keys include the augmented fourth.
+/// Panics unless `size` is in [`Stacking::SIZES`].
+pub fn stacked(key: Note, intervals: &[Interval], stacking: Stacking, size: usize) -> Vec<(Note, Chord)> {
+    assert!(
+        Stacking::SIZES.contains(&size),
+        "a stacked chord has 3 to 6 notes, not {}",
+        size
+    );
+    let semitones: Vec<usize> = intervals.iter().map(|i| i.semitones()).collect();
+    let len = semitones.len();
+    (0..len)
+        .map(|degree| {
+            let chord_intervals = (0..size)
+                .map(|n| {
+                    let target = degree + stacking.steps() * n;
+                    canonical_interval(semitones[target % len] + 12 * (target / len) - semitones[degree])
+                })
+                .collect();
+            (
+                key.transpose(semitones[degree] as isize),
+                Chord {
+                    intervals: chord_intervals,
+                },
+            )
+        })
+        .collect()
+}
+
+fn stack(key: Note, intervals: &[Interval; 7], size: usize) -> Vec<(Note, Chord)> {
+    let semitones = intervals.map(|i| i.semitones());
+    let degrees = [Degree::First, Degree::Third, Degree::Fifth, Degree::Seventh];
+    (0..7)
+        .map(|degree| {
+            let chord_intervals = degrees
+                .iter()
+                .take(size)
+                .enumerate()
+                .map(|(n, chord_degree)| {
+                    let target = degree + 2 * n;
+                    let distance = semitones[target % 7] + 12 * (target / 7) - semitones[degree];
+                    match chord_degree.interval(distance) {
+                        Some(interval) => interval,
+                        None => unreachable!(),
+                    }
+                })
+                .collect();
+            (
+                key.transpose(semitones[degree] as isize),
+                Chord {
+                    intervals: chord_intervals,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::chord::stacked::name;
+    use crate::chord::{Tetrad, Triad};
+    use crate::note::Note::*;
+    use crate::scales::{diatonic, harmonic_minor, symmetric};
+
+    use super::*;
+
+    #[rstest]
+    #[case(C, diatonic::Mode::Ionian.intervals(), [
+        (C, Triad::Major), (D, Triad::Minor), (E, Triad::Minor), (F, Triad::Major),
+        (G, Triad::Major), (A, Triad::Minor), (B, Triad::Diminished),
+    ])]
+    #[case(A, harmonic_minor::Mode::HarmonicMinor.intervals(), [
+        (A, Triad::Minor), (B, Triad::Diminished), (C, Triad::Augmented), (D, Triad::Minor),
+        (E, Triad::Major), (F, Triad::Major), (Ab, Triad::Diminished),
+    ])]
+    fn harmonized_triads(#[case] key: Note, #[case] intervals: [Interval; 7], #[case] expected: [(Note, Triad); 7]) {
+        let result = triads(key, &intervals);
+        for ((root, chord), (expected_root, triad)) in result.iter().zip(expected) {
+            assert_eq!(*root, expected_root);
+            assert_eq!(chord.intervals, triad.intervals());
+        }
+    }
+
+    #[rstest]
+    #[case(C, diatonic::Mode::Ionian.intervals(), [
+        (C, Tetrad::Major7), (D, Tetrad::Minor7), (E, Tetrad::Minor7), (F, Tetrad::Major7),
+        (G, Tetrad::Dominant), (A, Tetrad::Minor7), (B, Tetrad::Minor7Flat5),
+    ])]
+    #[case(D, diatonic::Mode::Dorian.intervals(), [
+        (D, Tetrad::Minor7), (E, Tetrad::Minor7), (F, Tetrad::Major7), (G, Tetrad::Dominant),
+        (A, Tetrad::Minor7), (B, Tetrad::Minor7Flat5), (C, Tetrad::Major7),
+    ])]
+    fn harmonized_sevenths(#[case] key: Note, #[case] intervals: [Interval; 7], #[case] expected: [(Note, Tetrad); 7]) {
+        let result = sevenths(key, &intervals);
+        for ((root, chord), (expected_root, tetrad)) in result.iter().zip(expected) {
+            assert_eq!(*root, expected_root);
+            assert_eq!(chord.intervals, tetrad.intervals());
+        }
+    }
+
+    #[test]
+    fn harmonic_minor_sevenths() {
+        let result = sevenths(C, &harmonic_minor::Mode::HarmonicMinor.intervals());
+        let tetrads = [
+            (0, Tetrad::MinorMajor7),
+            (1, Tetrad::Minor7Flat5),
+            (4, Tetrad::Dominant),
+            (6, Tetrad::Diminished7),
+        ];
+        for (degree, tetrad) in tetrads {
+            assert_eq!(result[degree].1.intervals, tetrad.intervals());
+        }
+        assert_eq!(result[2].0, Eb);
+        assert_eq!(
+            result[2].1.intervals,
+            vec![
+                Interval::PerfectUnison,
+                Interval::MajorThird,
+                Interval::AugmentedFifth,
+                Interval::MajorSeventh
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(D, diatonic::Mode::Dorian.intervals().to_vec(), Stacking::Quartal, 4, vec![
+        "G7sus4/D", "A7sus4/E", "F quartal(4)", "G quartal(4)", "D7sus4/A", "E7sus4/B", "C quartal(4)",
+    ])]
+    #[case(D, diatonic::Mode::Dorian.intervals().to_vec(), Stacking::Quartal, 3, vec![
+        "Gsus4/D", "Asus4/E", "F quartal(3)", "Csus4/G", "Dsus4/A", "Esus4/B", "C quartal(3)",
+    ])]
+    #[case(C, diatonic::Mode::Ionian.intervals().to_vec(), Stacking::Quintal, 3, vec![
+        "Csus2", "Dsus2", "E quintal(3)", "Fsus2", "Gsus2", "Asus2", "B quintal(3)",
+    ])]
+    #[case(C, diatonic::Mode::Ionian.intervals().to_vec(), Stacking::Secondal, 3, vec![
+        "C cluster(3)", "D cluster(3)", "E cluster(3)", "F cluster(3)", "G cluster(3)", "A cluster(3)", "B cluster(3)",
+    ])]
+    #[case(C, symmetric::Mode::WholeTone.intervals(), Stacking::Secondal, 4, vec![
+        "C cluster(4)", "D cluster(4)", "E cluster(4)", "Gb cluster(4)", "Ab cluster(4)", "Bb cluster(4)",
+    ])]
+    fn stacked_chords(
+        #[case] key: Note,
+        #[case] intervals: Vec<Interval>,
+        #[case] stacking: Stacking,
+        #[case] size: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        let names: Vec<String> = stacked(key, &intervals, stacking, size)
+            .iter()
+            .map(|(root, chord)| name(*root, chord, stacking))
+            .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn quartal_chords_stay_in_the_scale() {
+        let result = stacked(C, &diatonic::Mode::Ionian.intervals(), Stacking::Quartal, 3);
+        assert_eq!(result[3].1.apply_to(result[3].0), vec![F, B, E]);
+        assert_eq!(result[1].1.apply_to(result[1].0), vec![D, G, C]);
+    }
+}
@@ -0,0 +1,74 @@
+use super::degree::interval_for;
+use crate::interval::Interval;
+use crate::scales::Degree;
+
+const SEMITONES: &[usize; 7] = &[2, 1, 2, 2, 2, 2, 1];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    MelodicMinor,
+    DorianFlat2,
+    LydianAugmented,
+    LydianDominant,
+    MixolydianFlat6,
+    LocrianNatural2,
+    Altered,
+}
+
+impl Mode {
+    pub fn array() -> [Mode; 7] {
+        [
+            Mode::MelodicMinor,
+            Mode::DorianFlat2,
+            Mode::LydianAugmented,
+            Mode::LydianDominant,
+            Mode::MixolydianFlat6,
+            Mode::LocrianNatural2,
+            Mode::Altered,
+        ]
+    }
+
+    pub fn intervals(&self) -> [Interval; 7] {
+        Degree::array().map(|d| self.interval_for(d))
+    }
+
+    fn starting_degree(&self) -> Degree {
+        match self {
+            Mode::MelodicMinor => Degree::First,
+            Mode::DorianFlat2 => Degree::Second,
+            Mode::LydianAugmented => Degree::Third,
+            Mode::LydianDominant => Degree::Fourth,
+            Mode::MixolydianFlat6 => Degree::Fifth,
+            Mode::LocrianNatural2 => Degree::Sixth,
+            Mode::Altered => Degree::Seventh,
+        }
+    }
+
+    fn interval_for(&self, degree: Degree) -> Interval {
+        match interval_for(SEMITONES, self.starting_degree().as_number() - 1, degree) {
+            Some(interval) => interval,
+            None => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    use crate::interval::Interval::*;
+
+    #[rstest]
+    #[case(Mode::MelodicMinor,    [PerfectUnison, MajorSecond, MinorThird, PerfectFourth,    PerfectFifth,    MajorSixth,  MajorSeventh])]
+    #[case(Mode::DorianFlat2,     [PerfectUnison, MinorSecond, MinorThird, PerfectFourth,    PerfectFifth,    MajorSixth,  MinorSeventh])]
+    #[case(Mode::LydianAugmented, [PerfectUnison, MajorSecond, MajorThird, AugmentedFourth,  AugmentedFifth,  MajorSixth,  MajorSeventh])]
+    #[case(Mode::LydianDominant,  [PerfectUnison, MajorSecond, MajorThird, AugmentedFourth,  PerfectFifth,    MajorSixth,  MinorSeventh])]
+    #[case(Mode::MixolydianFlat6, [PerfectUnison, MajorSecond, MajorThird, PerfectFourth,    PerfectFifth,    MinorSixth,  MinorSeventh])]
+    #[case(Mode::LocrianNatural2, [PerfectUnison, MajorSecond, MinorThird, PerfectFourth,    DiminishedFifth, MinorSixth,  MinorSeventh])]
+    #[case(Mode::Altered,         [PerfectUnison, MinorSecond, MinorThird, DiminishedFourth, DiminishedFifth, MinorSixth,  MinorSeventh])]
+    fn mode_intervals(#[case] mode: Mode, #[case] expected: [Interval; 7]) {
+        assert_eq!(mode.intervals(), expected);
+    }
+}
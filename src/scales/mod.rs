@@ -1,7 +1,12 @@
 pub use degree::Degree;
 pub use generator::{diatonic_mode, generate_scale, major, minor};
+pub use scale::Scale;
 
 mod degree;
 pub mod diatonic;
 mod generator;
 pub mod harmonic_minor;
+pub mod harmonize;
+pub mod melodic_minor;
+mod scale;
+pub mod symmetric;
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use crate::interval::Interval;
+use crate::scales::{diatonic, harmonic_minor, melodic_minor};
+
+/// Any of the seven-note scales the crate knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Diatonic(diatonic::Mode),
+    HarmonicMinor(harmonic_minor::Mode),
+    MelodicMinor(melodic_minor::Mode),
+}
+
+impl Scale {
+    /// Every supported scale, diatonic modes first.
+    pub fn all() -> Vec<Scale> {
+        diatonic::Mode::array()
+            .into_iter()
+            .map(Scale::Diatonic)
+            .chain(harmonic_minor::Mode::array().into_iter().map(Scale::HarmonicMinor))
+            .chain(melodic_minor::Mode::array().into_iter().map(Scale::MelodicMinor))
+            .collect()
+    }
+
+    pub fn intervals(&self) -> [Interval; 7] {
+        match self {
+            Scale::Diatonic(mode) => mode.intervals(),
+            Scale::HarmonicMinor(mode) => mode.intervals(),
+            Scale::MelodicMinor(mode) => mode.intervals(),
+        }
+    }
+
+    /// The distance of each degree from the tonic, in semitones.
+    pub fn semitones(&self) -> [usize; 7] {
+        self.intervals().map(|i| i.semitones())
+    }
+
+    /// Whether the third degree is a major third above the tonic.
+    pub fn is_major(&self) -> bool {
+        self.semitones()[2] == 4
+    }
+}
+
+impl fmt::Display for Scale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Scale::Diatonic(diatonic::Mode::Ionian) => "major",
+            Scale::Diatonic(diatonic::Mode::Dorian) => "dorian",
+            Scale::Diatonic(diatonic::Mode::Phrygian) => "phrygian",
+            Scale::Diatonic(diatonic::Mode::Lydian) => "lydian",
+            Scale::Diatonic(diatonic::Mode::Mixolydian) => "mixolydian",
+            Scale::Diatonic(diatonic::Mode::Aeolian) => "minor",
+            Scale::Diatonic(diatonic::Mode::Locrian) => "locrian",
+            Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor) => "harmonic-minor",
+            Scale::HarmonicMinor(harmonic_minor::Mode::LocrianMaj6) => "locrian-maj6",
+            Scale::HarmonicMinor(harmonic_minor::Mode::IonianAug5) => "ionian-aug5",
+            Scale::HarmonicMinor(harmonic_minor::Mode::DorianLydian) => "dorian-lydian",
+            Scale::HarmonicMinor(harmonic_minor::Mode::PhrygianDominant) => "phrygian-dominant",
+            Scale::HarmonicMinor(harmonic_minor::Mode::LydianAug2) => "lydian-aug2",
+            Scale::HarmonicMinor(harmonic_minor::Mode::SuperLocrian) => "superlocrian",
+            Scale::MelodicMinor(melodic_minor::Mode::MelodicMinor) => "melodic-minor",
+            Scale::MelodicMinor(melodic_minor::Mode::DorianFlat2) => "dorian-flat2",
+            Scale::MelodicMinor(melodic_minor::Mode::LydianAugmented) => "lydian-augmented",
+            Scale::MelodicMinor(melodic_minor::Mode::LydianDominant) => "lydian-dominant",
+            Scale::MelodicMinor(melodic_minor::Mode::MixolydianFlat6) => "mixolydian-flat6",
+            Scale::MelodicMinor(melodic_minor::Mode::LocrianNatural2) => "locrian-natural2",
+            Scale::MelodicMinor(melodic_minor::Mode::Altered) => "altered",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Scale::Diatonic(diatonic::Mode::Ionian), [0, 2, 4, 5, 7, 9, 11], true)]
+    #[case(Scale::Diatonic(diatonic::Mode::Aeolian), [0, 2, 3, 5, 7, 8, 10], false)]
+    #[case(Scale::HarmonicMinor(harmonic_minor::Mode::HarmonicMinor), [0, 2, 3, 5, 7, 8, 11], false)]
+    #[case(Scale::HarmonicMinor(harmonic_minor::Mode::PhrygianDominant), [0, 1, 4, 5, 7, 8, 10], true)]
+    #[case(Scale::MelodicMinor(melodic_minor::Mode::Altered), [0, 1, 3, 4, 6, 8, 10], false)]
+    fn scale_semitones(#[case] scale: Scale, #[case] expected: [usize; 7], #[case] major: bool) {
+        assert_eq!(scale.semitones(), expected);
+        assert_eq!(scale.is_major(), major);
+    }
+
+    #[test]
+    fn all_scales() {
+        let all = Scale::all();
+        assert_eq!(all.len(), 21);
+        assert_eq!(all[0].to_string(), "major");
+        assert_eq!(all[7].to_string(), "harmonic-minor");
+        assert_eq!(all[14].to_string(), "melodic-minor");
+    }
+}
@@ -0,0 +1,67 @@
+use crate::interval::Interval;
+
+/// Scales built by repeating a fixed pattern of steps, which don't have seven notes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    HalfWholeDiminished,
+    WholeHalfDiminished,
+    WholeTone,
+}
+
+impl Mode {
+    pub fn array() -> [Mode; 3] {
+        [Mode::HalfWholeDiminished, Mode::WholeHalfDiminished, Mode::WholeTone]
+    }
+
+    pub fn intervals(&self) -> Vec<Interval> {
+        match self {
+            Mode::HalfWholeDiminished => vec![
+                Interval::PerfectUnison,
+                Interval::MinorSecond,
+                Interval::AugmentedSecond,
+                Interval::MajorThird,
+                Interval::AugmentedFourth,
+                Interval::PerfectFifth,
+                Interval::MajorSixth,
+                Interval::MinorSeventh,
+            ],
+            Mode::WholeHalfDiminished => vec![
+                Interval::PerfectUnison,
+                Interval::MajorSecond,
+                Interval::MinorThird,
+                Interval::PerfectFourth,
+                Interval::DiminishedFifth,
+                Interval::MinorSixth,
+                Interval::DiminishedSeventh,
+                Interval::MajorSeventh,
+            ],
+            Mode::WholeTone => vec![
+                Interval::PerfectUnison,
+                Interval::MajorSecond,
+                Interval::MajorThird,
+                Interval::AugmentedFourth,
+                Interval::AugmentedFifth,
+                Interval::MinorSeventh,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note;
+    use crate::note::Note::*;
+    use crate::scales::generate_scale;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Mode::HalfWholeDiminished, C, vec![C, Db, Eb, E, Gb, G, A, Bb, C])]
+    #[case(Mode::WholeHalfDiminished, C, vec![C, D, Eb, F, Gb, Ab, A, B, C])]
+    #[case(Mode::WholeTone, C, vec![C, D, E, Gb, Ab, Bb, C])]
+    fn symmetric_scale(#[case] mode: Mode, #[case] key: Note, #[case] expected: Vec<Note>) {
+        assert_eq!(generate_scale(key, &mode.intervals()), expected);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::note::Note;
+use crate::pitch::Pitch;
+
+/// Ratios of the just major scale and the chromatic notes between, as used in 5-limit tuning.
+const JUST: [f64; 12] = [
+    1.0,
+    16.0 / 15.0,
+    9.0 / 8.0,
+    6.0 / 5.0,
+    5.0 / 4.0,
+    4.0 / 3.0,
+    45.0 / 32.0,
+    3.0 / 2.0,
+    8.0 / 5.0,
+    5.0 / 3.0,
+    9.0 / 5.0,
+    15.0 / 8.0,
+];
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid temperament {0}")]
+pub struct TemperamentParseError(String);
+
+/// How the twelve notes of the octave are tuned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Temperament {
+    /// Twelve equal semitones.
+    Equal,
+    /// 5-limit just intonation, with pure thirds and fifths above the tonic.
+    Just,
+    /// A chain of pure fifths from a flat fifth below the tonic to an augmented fourth above it.
+    Pythagorean,
+    /// Quarter-comma meantone, with fifths narrowed to give pure major thirds.
+    Meantone,
+}
+
+impl Temperament {
+    /// The frequency ratio of the note `semitones` above the tonic, from 0 to 11.
+    pub fn ratio(&self, semitones: usize) -> f64 {
+        match self {
+            Temperament::Equal => 2f64.powf(semitones as f64 / 12.0),
+            Temperament::Just => JUST[semitones],
+            Temperament::Pythagorean => stacked_fifths(1.5, semitones),
+            Temperament::Meantone => stacked_fifths(5f64.powf(0.25), semitones),
+        }
+    }
+}
+
+/// The ratio of the note `semitones` above the tonic, reached by stacking between 5 fifths down and 6 fifths up and
+/// folding the result into the octave.
+fn stacked_fifths(fifth: f64, semitones: usize) -> f64 {
+    let fifths = (-5..=6)
+        .find(|k: &i32| (k * 7).rem_euclid(12) as usize == semitones)
+        .expect("every semitone is reached by a fifth");
+    let ratio = fifth.powi(fifths);
+    ratio / 2f64.powf(ratio.log2().floor())
+}
+
+impl FromStr for Temperament {
+    type Err = TemperamentParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "equal" | "12-tet" => Ok(Temperament::Equal),
+            "just" => Ok(Temperament::Just),
+            "pythagorean" => Ok(Temperament::Pythagorean),
+            "meantone" => Ok(Temperament::Meantone),
+            _ => Err(TemperamentParseError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Temperament {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Temperament::Equal => "equal",
+            Temperament::Just => "just",
+            Temperament::Pythagorean => "pythagorean",
+            Temperament::Meantone => "meantone",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A temperament built on a tonic, with `A4` sounding at the reference frequency.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tuning {
+    pub temperament: Temperament,
+    pub tonic: Note,
+    /// The frequency of `A4` in Hz.
+    pub reference: f64,
+}
+
+impl Default for Tuning {
+    fn default() -> Tuning {
+        Tuning {
+            temperament: Temperament::Equal,
+            tonic: Note::C,
+            reference: 440.0,
+        }
+    }
+}
+
+impl Tuning {
+    pub fn new(temperament: Temperament, tonic: Note, reference: f64) -> Tuning {
+        Tuning {
+            temperament,
+            tonic,
+            reference,
+        }
+    }
+
+    /// The frequency of a pitch in Hz.
+    pub fn frequency(&self, pitch: Pitch) -> f64 {
+        let tonic = Pitch::new(self.tonic, 4).midi();
+        self.reference
+            * (self.above_tonic(pitch.midi() - tonic) / self.above_tonic(Pitch::new(Note::A, 4).midi() - tonic))
+    }
+
+    fn above_tonic(&self, semitones: i32) -> f64 {
+        2f64.powi(semitones.div_euclid(12)) * self.temperament.ratio(semitones.rem_euclid(12) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::note::Note::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Temperament::Equal, 7, 1.498307)]
+    #[case(Temperament::Just, 4, 1.25)]
+    #[case(Temperament::Just, 10, 1.8)]
+    #[case(Temperament::Pythagorean, 4, 1.265625)]
+    #[case(Temperament::Pythagorean, 1, 1.053498)]
+    #[case(Temperament::Pythagorean, 6, 1.423828)]
+    #[case(Temperament::Meantone, 4, 1.25)]
+    #[case(Temperament::Meantone, 7, 1.495349)]
+    fn ratios(#[case] temperament: Temperament, #[case] semitones: usize, #[case] expected: f64) {
+        assert!((temperament.ratio(semitones) - expected).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[case(Tuning::default(), Pitch::new(A, 4), 440.0)]
+    #[case(Tuning::default(), Pitch::new(C, 4), 261.625565)]
+    #[case(Tuning::default(), Pitch::new(A, 2), 110.0)]
+    #[case(Tuning::new(Temperament::Equal, C, 432.0), Pitch::new(A, 5), 864.0)]
+    #[case(Tuning::new(Temperament::Just, A, 440.0), Pitch::new(E, 5), 660.0)]
+    #[case(Tuning::new(Temperament::Just, A, 440.0), Pitch::new(Db, 5), 550.0)]
+    #[case(Tuning::new(Temperament::Just, C, 440.0), Pitch::new(C, 4), 264.0)]
+    #[case(Tuning::new(Temperament::Just, C, 440.0), Pitch::new(E, 3), 165.0)]
+    #[case(Tuning::new(Temperament::Pythagorean, D, 440.0), Pitch::new(D, 4), 293.333333)]
+    fn frequencies(#[case] tuning: Tuning, #[case] pitch: Pitch, #[case] expected: f64) {
+        assert!((tuning.frequency(pitch) - expected).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[case("equal", Temperament::Equal)]
+    #[case("Just", Temperament::Just)]
+    #[case("pythagorean", Temperament::Pythagorean)]
+    #[case("meantone", Temperament::Meantone)]
+    fn parse_temperament(#[case] input: &str, #[case] expected: Temperament) -> Result<(), TemperamentParseError> {
+        assert_eq!(input.parse::<Temperament>()?, expected);
+        assert_eq!(expected.to_string(), input.to_lowercase());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_temperament_error() {
+        assert!("werckmeister".parse::<Temperament>().is_err());
+    }
+}